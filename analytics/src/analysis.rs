@@ -1,2 +1,232 @@
-//! analysis stub implementation
+//! Session analysis that turns raw error counts into coaching insights
+use centotype_core::types::*;
+
+/// Errors of one kind needed before a pattern is worth reporting
+const MIN_PATTERN_COUNT: u32 = 3;
+
+/// Keystrokes a finger needs before its statistics are trusted
+const MIN_FINGER_KEYSTROKES: u32 = 20;
+
+/// Finger error rate worth calling out (0.0-1.0)
+const WEAK_FINGER_ERROR_RATE: f64 = 0.08;
+
+/// Timed keystrokes needed before rhythm statistics are trusted
+const MIN_TIMED_KEYSTROKES: u32 = 20;
+
+/// Average dwell long enough to slow the next key down
+const LONG_DWELL: std::time::Duration = std::time::Duration::from_millis(120);
+
+/// Flight time variation that marks an uneven rhythm
+const UNEVEN_FLIGHT_VARIATION: f64 = 0.5;
+
+/// Insight generation for a single session
 pub struct Analysis {}
+
+impl Default for Analysis {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Analysis {
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    /// Describe recurring layout-related mistakes, most specific first
+    pub fn layout_insights(&self, stats: &LayoutErrorStats) -> Vec<String> {
+        let mut insights = Vec::new();
+
+        if let Some(drift) = stats
+            .dominant_drift()
+            .filter(|drift| drift.count >= MIN_PATTERN_COUNT)
+        {
+            let direction = match drift.direction {
+                DriftDirection::Up => "up a row",
+                DriftDirection::Down => "down a row",
+                DriftDirection::Left => "to the left",
+                DriftDirection::Right => "to the right",
+            };
+            insights.push(format!(
+                "Your {} drifts {} ({} slips)",
+                drift.finger.name(),
+                direction,
+                drift.count
+            ));
+        }
+
+        if stats.same_finger >= MIN_PATTERN_COUNT {
+            insights.push(format!(
+                "{} same-finger confusions: slow down on keys that share a finger",
+                stats.same_finger
+            ));
+        }
+
+        if stats.wrong_hand >= MIN_PATTERN_COUNT {
+            insights.push(format!(
+                "{} wrong-hand mirror errors: keep both hands anchored on the home row",
+                stats.wrong_hand
+            ));
+        }
+
+        if stats.shift >= MIN_PATTERN_COUNT {
+            insights.push(format!(
+                "{} Shift errors: hold Shift fully before striking the key",
+                stats.shift
+            ));
+        }
+
+        if stats.case >= MIN_PATTERN_COUNT {
+            insights.push(format!("{} case errors: watch for capital letters", stats.case));
+        }
+
+        insights
+    }
+
+    /// Point out the least accurate and the slowest finger of the session
+    pub fn finger_insights(&self, usage: &FingerUsageStats) -> Vec<String> {
+        let mut insights = Vec::new();
+
+        if let Some(weakest) = usage
+            .least_accurate_finger(MIN_FINGER_KEYSTROKES)
+            .filter(|stats| stats.error_rate() >= WEAK_FINGER_ERROR_RATE)
+        {
+            insights.push(format!(
+                "Your {} misses {:.0}% of its keys ({} of {})",
+                weakest.finger.name(),
+                weakest.error_rate() * 100.0,
+                weakest.errors,
+                weakest.keystrokes
+            ));
+        }
+
+        // A slowest finger only means something when there is another to compare
+        let judged_fingers = usage
+            .fingers
+            .iter()
+            .filter(|stats| stats.finger != Finger::Thumb)
+            .filter(|stats| stats.keystrokes >= MIN_FINGER_KEYSTROKES)
+            .count();
+        if let Some(slowest) = usage
+            .slowest_finger(MIN_FINGER_KEYSTROKES)
+            .filter(|stats| judged_fingers > 1 && stats.finger != Finger::Thumb)
+        {
+            insights.push(format!(
+                "Your {} is the slowest finger at {}ms per key",
+                slowest.finger.name(),
+                slowest.average_interval.as_millis()
+            ));
+        }
+
+        insights
+    }
+
+    /// Comment on press and release timing when the terminal reported it
+    pub fn rhythm_insights(&self, timing: &KeyTimingStats) -> Vec<String> {
+        let mut insights = Vec::new();
+        if timing.timed_keystrokes < MIN_TIMED_KEYSTROKES {
+            return insights;
+        }
+
+        if timing.average_dwell >= LONG_DWELL {
+            insights.push(format!(
+                "Keys stay down for {}ms on average; release them sooner",
+                timing.average_dwell.as_millis()
+            ));
+        }
+        if timing.flight_variation >= UNEVEN_FLIGHT_VARIATION {
+            insights.push(format!(
+                "Gaps between keys vary by {:.0}%; aim for an even rhythm",
+                timing.flight_variation * 100.0
+            ));
+        }
+
+        insights
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_drift_insight() {
+        let mut stats = LayoutErrorStats::default();
+        for _ in 0..4 {
+            stats.record(
+                SubstitutionKind::AdjacentKey,
+                Some((Finger::RightRing, DriftDirection::Up)),
+            );
+        }
+        stats.record(
+            SubstitutionKind::AdjacentKey,
+            Some((Finger::LeftIndex, DriftDirection::Right)),
+        );
+
+        let insights = Analysis::new().layout_insights(&stats);
+        assert_eq!(insights, vec!["Your right ring finger drifts up a row (4 slips)"]);
+    }
+
+    #[test]
+    fn test_rare_errors_are_not_reported() {
+        let mut stats = LayoutErrorStats::default();
+        stats.record(SubstitutionKind::Shift, None);
+        stats.record(SubstitutionKind::Case, None);
+
+        assert!(Analysis::new().layout_insights(&stats).is_empty());
+    }
+
+    fn finger_stats(finger: Finger, keystrokes: u32, errors: u32, interval_ms: u64) -> FingerStats {
+        FingerStats {
+            finger,
+            keystrokes,
+            errors,
+            average_interval: std::time::Duration::from_millis(interval_ms),
+        }
+    }
+
+    #[test]
+    fn test_finger_insights() {
+        let usage = FingerUsageStats {
+            fingers: vec![
+                finger_stats(Finger::LeftPinky, 40, 6, 260),
+                finger_stats(Finger::LeftIndex, 80, 2, 180),
+                // Too few keystrokes to judge
+                finger_stats(Finger::RightPinky, 5, 4, 900),
+            ],
+            ..Default::default()
+        };
+
+        let insights = Analysis::new().finger_insights(&usage);
+        assert_eq!(
+            insights,
+            vec![
+                "Your left pinky misses 15% of its keys (6 of 40)",
+                "Your left pinky is the slowest finger at 260ms per key",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_rhythm_insights() {
+        let timing = KeyTimingStats {
+            timed_keystrokes: 50,
+            average_dwell: std::time::Duration::from_millis(140),
+            average_flight: std::time::Duration::from_millis(90),
+            flight_variation: 0.2,
+            rollovers: 3,
+        };
+        assert_eq!(
+            Analysis::new().rhythm_insights(&timing),
+            vec!["Keys stay down for 140ms on average; release them sooner"]
+        );
+
+        // Too few timed keys to judge
+        let sparse = KeyTimingStats {
+            timed_keystrokes: 5,
+            flight_variation: 0.9,
+            ..timing
+        };
+        assert!(Analysis::new().rhythm_insights(&sparse).is_empty());
+    }
+}
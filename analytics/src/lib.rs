@@ -7,10 +7,11 @@ pub mod export;
 pub mod metrics;
 pub mod trends;
 
+use analysis::Analysis;
 use centotype_core::types::*;
 
 pub struct AnalyticsEngine {
-    // Analytics implementation
+    analysis: Analysis,
 }
 
 impl Default for AnalyticsEngine {
@@ -21,15 +22,36 @@ impl Default for AnalyticsEngine {
 
 impl AnalyticsEngine {
     pub fn new() -> Self {
-        Self {}
+        Self {
+            analysis: Analysis::new(),
+        }
     }
 
     pub fn analyze_session(&self, result: &SessionResult) -> AnalysisReport {
+        let layout_errors = &result.metrics.layout_errors;
+        let finger_usage = &result.metrics.finger_usage;
+        let mut improvement_areas = self.analysis.layout_insights(layout_errors);
+        improvement_areas.extend(self.analysis.finger_insights(finger_usage));
+        let key_timing = &result.metrics.key_timing;
+        improvement_areas.extend(self.analysis.rhythm_insights(key_timing));
+
+        let mut strengths = vec![];
+        if layout_errors.total() == 0 {
+            strengths.push("Clean key targeting with no layout-related slips".to_string());
+        }
+        if key_timing.rollover_rate() >= 0.1 {
+            strengths.push("Rolling between keys instead of typing one at a time".to_string());
+        }
+
         AnalysisReport {
             session_id: result.session_id,
             performance_score: result.skill_index,
-            improvement_areas: vec![],
-            strengths: vec![],
+            improvement_areas,
+            strengths,
+            finger_stats: finger_usage.fingers.clone(),
+            same_finger_bigram_rate: finger_usage.same_finger_bigram_rate(),
+            left_hand_share: finger_usage.left_hand_share(),
+            key_timing: key_timing.is_measured().then(|| key_timing.clone()),
         }
     }
 }
@@ -40,4 +62,11 @@ pub struct AnalysisReport {
     pub performance_score: f64,
     pub improvement_areas: Vec<String>,
     pub strengths: Vec<String>,
+    pub finger_stats: Vec<FingerStats>,
+    /// Share of bigrams typed with one finger on two keys (0.0-1.0)
+    pub same_finger_bigram_rate: f64,
+    /// Share of hand keystrokes typed by the left hand (0.0-1.0)
+    pub left_hand_share: f64,
+    /// Dwell and flight times, when the terminal reported key releases
+    pub key_timing: Option<KeyTimingStats>,
 }
@@ -1,2 +1,294 @@
-//! trends stub implementation
+//! Trends across the session history: activity per day, speed per tier,
+//! grades and personal records
+use centotype_core::types::*;
+use chrono::{Duration, NaiveDate};
+use serde::Serialize;
+
+/// Practice on one calendar day (UTC)
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DayActivity {
+    pub date: NaiveDate,
+    pub sessions: u32,
+    pub seconds: f64,
+}
+
+/// Arcade speed within one tier
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TierSpeed {
+    pub tier: Tier,
+    pub sessions: u32,
+    pub best_wpm: f64,
+    pub average_wpm: f64,
+}
+
+/// Sessions that earned each grade, best grade first
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct GradeCounts {
+    pub s: u32,
+    pub a: u32,
+    pub b: u32,
+    pub c: u32,
+    pub d: u32,
+}
+
+impl GradeCounts {
+    pub fn get(&self, grade: Grade) -> u32 {
+        match grade {
+            Grade::S => self.s,
+            Grade::A => self.a,
+            Grade::B => self.b,
+            Grade::C => self.c,
+            Grade::D => self.d,
+        }
+    }
+
+    fn add(&mut self, grade: Grade) {
+        match grade {
+            Grade::S => self.s += 1,
+            Grade::A => self.a += 1,
+            Grade::B => self.b += 1,
+            Grade::C => self.c += 1,
+            Grade::D => self.d += 1,
+        }
+    }
+}
+
+/// The session that set a record
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Record {
+    pub value: f64,
+    pub session_id: uuid::Uuid,
+    pub mode: TrainingMode,
+    pub completed_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl Record {
+    fn of(value: f64, result: &SessionResult) -> Self {
+        Self {
+            value,
+            session_id: result.session_id,
+            mode: result.mode,
+            completed_at: result.completed_at,
+        }
+    }
+}
+
+/// Best values over every verified session
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct PersonalRecords {
+    pub effective_wpm: Option<Record>,
+    pub accuracy: Option<Record>,
+    /// Longest run of correct characters
+    pub streak: Option<Record>,
+    pub skill_index: Option<Record>,
+}
+
+/// Trend calculations over a session history, oldest session first.
+/// Sessions with pasted or injected input count towards activity but not
+/// towards speed, grades or records.
 pub struct Trends {}
+
+impl Default for Trends {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Trends {
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    /// Activity for each of the `days` days up to and including `today`,
+    /// oldest first; days without practice are included with zeros
+    pub fn daily_activity(
+        &self,
+        history: &[SessionResult],
+        today: NaiveDate,
+        days: u32,
+    ) -> Vec<DayActivity> {
+        let first = today - Duration::days(days as i64 - 1);
+        let mut activity: Vec<DayActivity> = (0..days as i64)
+            .map(|offset| DayActivity {
+                date: first + Duration::days(offset),
+                sessions: 0,
+                seconds: 0.0,
+            })
+            .collect();
+
+        for result in history {
+            let date = result.completed_at.date_naive();
+            if date < first || date > today {
+                continue;
+            }
+            let day = &mut activity[(date - first).num_days() as usize];
+            day.sessions += 1;
+            day.seconds += result.duration_seconds;
+        }
+        activity
+    }
+
+    /// Best and average effective WPM of arcade sessions in each tier
+    /// played, lowest tier first
+    pub fn tier_speeds(&self, history: &[SessionResult]) -> Vec<TierSpeed> {
+        let mut speeds: Vec<TierSpeed> = Vec::new();
+        for result in verified(history) {
+            let TrainingMode::Arcade { level } = result.mode else {
+                continue;
+            };
+            let wpm = result.metrics.effective_wpm;
+            match speeds.iter_mut().find(|speed| speed.tier == level.tier()) {
+                Some(speed) => {
+                    speed.best_wpm = speed.best_wpm.max(wpm);
+                    speed.average_wpm += wpm;
+                    speed.sessions += 1;
+                }
+                None => speeds.push(TierSpeed {
+                    tier: level.tier(),
+                    sessions: 1,
+                    best_wpm: wpm,
+                    average_wpm: wpm,
+                }),
+            }
+        }
+
+        for speed in &mut speeds {
+            speed.average_wpm /= speed.sessions as f64;
+        }
+        speeds.sort_by_key(|speed| speed.tier);
+        speeds
+    }
+
+    pub fn grade_counts(&self, history: &[SessionResult]) -> GradeCounts {
+        let mut counts = GradeCounts::default();
+        for result in verified(history) {
+            counts.add(result.grade);
+        }
+        counts
+    }
+
+    /// The earliest session to reach each best value
+    pub fn personal_records(&self, history: &[SessionResult]) -> PersonalRecords {
+        let mut records = PersonalRecords::default();
+        for result in verified(history) {
+            let metrics = &result.metrics;
+            improve(&mut records.effective_wpm, metrics.effective_wpm, result);
+            improve(&mut records.accuracy, metrics.accuracy, result);
+            improve(&mut records.streak, metrics.longest_streak as f64, result);
+            improve(&mut records.skill_index, result.skill_index, result);
+        }
+        records
+    }
+
+    /// Change in average effective WPM from the `window` sessions before
+    /// the last `window` to the last `window`; None until there are enough
+    pub fn wpm_change(&self, history: &[SessionResult], window: usize) -> Option<f64> {
+        let speeds: Vec<f64> = verified(history)
+            .map(|result| result.metrics.effective_wpm)
+            .collect();
+        if window == 0 || speeds.len() < window * 2 {
+            return None;
+        }
+        let average = |speeds: &[f64]| speeds.iter().sum::<f64>() / speeds.len() as f64;
+        let recent = &speeds[speeds.len() - window..];
+        let before = &speeds[speeds.len() - window * 2..speeds.len() - window];
+        Some(average(recent) - average(before))
+    }
+}
+
+fn verified(history: &[SessionResult]) -> impl Iterator<Item = &SessionResult> {
+    history.iter().filter(|result| !result.unverified)
+}
+
+fn improve(record: &mut Option<Record>, value: f64, result: &SessionResult) {
+    if record.as_ref().is_none_or(|best| value > best.value) {
+        *record = Some(Record::of(value, result));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+
+    fn session(level: u8, wpm: f64, grade: Grade, day: u32) -> SessionResult {
+        SessionResult {
+            session_id: uuid::Uuid::new_v4(),
+            mode: TrainingMode::Arcade {
+                level: LevelId(level),
+            },
+            completed_at: Utc.with_ymd_and_hms(2026, 3, day, 12, 0, 0).unwrap(),
+            duration_seconds: 60.0,
+            metrics: FinalMetrics {
+                raw_wpm: wpm,
+                effective_wpm: wpm,
+                accuracy: 95.0,
+                consistency: 90.0,
+                longest_streak: 40,
+                errors: ErrorStats::default(),
+                latency_p99: std::time::Duration::from_millis(100),
+                keystroke_accuracy: 95.0,
+                corrections: CorrectionStats::default(),
+                slowest_words: Vec::new(),
+                layout_errors: LayoutErrorStats::default(),
+                finger_usage: FingerUsageStats::default(),
+                key_timing: KeyTimingStats::default(),
+            },
+            skill_index: wpm * 10.0,
+            grade,
+            stars: 2,
+            unverified: false,
+        }
+    }
+
+    #[test]
+    fn test_daily_activity_fills_gaps() {
+        let history = vec![
+            session(1, 40.0, Grade::B, 1),
+            session(2, 45.0, Grade::A, 3),
+            session(2, 50.0, Grade::A, 3),
+        ];
+        let today = NaiveDate::from_ymd_opt(2026, 3, 4).unwrap();
+        let activity = Trends::new().daily_activity(&history, today, 3);
+
+        let sessions: Vec<u32> = activity.iter().map(|day| day.sessions).collect();
+        assert_eq!(sessions, [0, 2, 0]);
+        assert_eq!(
+            activity[0].date,
+            NaiveDate::from_ymd_opt(2026, 3, 2).unwrap()
+        );
+        assert_eq!(activity[1].seconds, 120.0);
+    }
+
+    #[test]
+    fn test_speed_grades_and_records() {
+        let mut cheated = session(12, 200.0, Grade::S, 2);
+        cheated.unverified = true;
+        let history = vec![
+            session(1, 40.0, Grade::B, 1),
+            session(3, 50.0, Grade::A, 1),
+            session(15, 30.0, Grade::C, 2),
+            cheated,
+            session(11, 34.0, Grade::B, 3),
+        ];
+        let trends = Trends::new();
+
+        let speeds = trends.tier_speeds(&history);
+        assert_eq!(speeds.len(), 2);
+        assert_eq!(speeds[0].tier, Tier(1));
+        assert_eq!((speeds[0].best_wpm, speeds[0].average_wpm), (50.0, 45.0));
+        assert_eq!((speeds[1].sessions, speeds[1].best_wpm), (2, 34.0));
+
+        let grades = trends.grade_counts(&history);
+        assert_eq!((grades.s, grades.a, grades.b, grades.c), (0, 1, 2, 1));
+
+        let records = trends.personal_records(&history);
+        let fastest = records.effective_wpm.unwrap();
+        assert_eq!(fastest.value, 50.0);
+        assert_eq!(fastest.mode, TrainingMode::Arcade { level: LevelId(3) });
+
+        // Last two average 32, the two before 45
+        assert_eq!(trends.wpm_change(&history, 2), Some(-13.0));
+        assert_eq!(trends.wpm_change(&history, 3), None);
+    }
+}
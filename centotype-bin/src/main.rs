@@ -2,21 +2,35 @@
 //!
 //! A high-performance typing trainer designed for developers and competitive typists.
 
-use centotype_cli::{Cli, CliManager};
-use centotype_content::ContentManager;
+use centotype_cli::interface::{Interface, MenuStyles};
+use centotype_cli::menus::{MenuSelection, Menus};
+use centotype_cli::{Cli, CliManager, DEFAULT_DRILL_MINUTES};
+use centotype_content::{
+    slow_word_text, weak_key_text, LayoutCurriculum, SLOW_WORD_DRILL_LENGTH, WEAK_KEY_DRILL_LENGTH,
+};
+use centotype_core::types::{Config, DrillCategory, LevelId, TrainingMode, UserProgress};
 use centotype_core::CentotypeCore;
-use centotype_engine::CentotypeEngine;
+use centotype_engine::{CentotypeEngine, LayoutEmulation, ResultsAction, ThemePalette, ThemeRegistry};
 use centotype_persistence::PersistenceManager;
 use centotype_platform::PlatformManager;
 use clap::Parser;
+use std::process::ExitCode;
 use std::sync::Arc;
 use tracing::{error, info};
 
+/// Sessions a word must have been among the slowest in to be drilled
+const SLOW_WORD_MIN_SESSIONS: u32 = 3;
+/// Words in a slow word drill
+const SLOW_WORD_LIMIT: usize = 10;
+
 #[tokio::main]
-async fn main() -> anyhow::Result<()> {
+async fn main() -> anyhow::Result<ExitCode> {
     // Initialize tracing
     tracing_subscriber::fmt::init();
 
+    // Give the terminal back even if a panic aborts the process
+    centotype_engine::signals::install_panic_hook();
+
     info!("Starting Centotype v{}", env!("CARGO_PKG_VERSION"));
 
     // Parse command line arguments
@@ -41,28 +55,96 @@ async fn main() -> anyhow::Result<()> {
     // Apply platform optimizations
     platform_manager.apply_optimizations()?;
 
-    // Initialize core components
-    let core = Arc::new(CentotypeCore::new());
-    let content_manager = Arc::new(ContentManager::new().await?);
+    // Resolve configuration from the files, CENTOTYPE_* variables and --set
+    // flags before the core so scoring uses the right layout
     let persistence_manager = Arc::new(PersistenceManager::new()?);
+    let config = persistence_manager.resolve_config(&cli.overrides)?.config;
+    let layouts = persistence_manager.load_layouts()?;
+    let geometry = config
+        .custom_layout
+        .as_deref()
+        .and_then(|id| {
+            let geometry = layouts.geometry(id);
+            if geometry.is_none() {
+                tracing::warn!("Unknown custom layout '{}', using {:?}", id, config.layout);
+            }
+            geometry
+        })
+        .or_else(|| layouts.geometry(config.layout.id()))
+        .ok_or_else(|| anyhow::anyhow!("No geometry for layout {:?}", config.layout))?;
+
+    // Initialize core components
+    let core = Arc::new(CentotypeCore::with_geometry(Arc::clone(&geometry)));
 
     // Initialize engine
     let mut engine = CentotypeEngine::new(Arc::clone(&core), Arc::clone(&platform_manager)).await?;
 
+    engine.set_keyboard(Arc::clone(&geometry));
+    match layouts.get(geometry.id()) {
+        Some(definition) => engine.set_curriculum(LayoutCurriculum::new(definition)),
+        None => tracing::warn!("No definition for layout '{}' to drill", geometry.id()),
+    }
+
+    // Practise another layout on the physical keyboard when they differ
+    if let Some(physical) = config.physical_layout {
+        if let Some(physical_geometry) = layouts.geometry(physical.id()) {
+            if physical_geometry.id() != geometry.id() {
+                engine.set_layout_emulation(Some(LayoutEmulation::new(
+                    &physical_geometry,
+                    &geometry,
+                )));
+            }
+        }
+    }
+    let mut themes = ThemeRegistry::new();
+    themes.load_dir(&persistence_manager.themes_dir())?;
+    let palette = apply_display_settings(&engine, &config, &themes)?;
+
     // Initialize CLI manager
-    let cli_manager = CliManager::new();
+    let color_support = platform_manager.terminal_capabilities().color_support;
+    let cli_manager = CliManager::new(
+        Arc::clone(&persistence_manager),
+        color_support,
+        cli.overrides.clone(),
+    );
 
-    // Load user configuration and profile
-    let config = persistence_manager.load_config()?;
-    let profile = persistence_manager.load_profile()?;
+    // Load user profile
+    let mut profile = persistence_manager.load_profile()?;
 
     info!(
         "User profile loaded: {} total sessions",
         profile.total_sessions
     );
 
-    // Run the CLI command
-    match cli_manager.run(cli) {
+    // A session cut short by a signal last time counts like any other
+    let recovered = record_interrupted_session(&core, &persistence_manager, &mut profile)?;
+
+    // Run the CLI command, or the menus when none was given
+    let outcome = match cli.command {
+        // A failed command never touched the terminal; say what was wrong
+        // and leave the session recovery below out of it
+        Some(command) => {
+            if let Err(e) = cli_manager.run(command) {
+                eprintln!("centotype: {}", e);
+                return Ok(ExitCode::FAILURE);
+            }
+            Ok(())
+        }
+        None => {
+            let styles = MenuStyles::resolve(&palette, color_support);
+            run_menus(
+                &mut engine,
+                &cli_manager,
+                &persistence_manager,
+                &themes,
+                &mut profile,
+                styles,
+                recovered,
+            )
+            .await
+        }
+    };
+    match outcome {
         Ok(_) => {
             info!("Centotype session completed successfully");
         }
@@ -80,5 +162,147 @@ async fn main() -> anyhow::Result<()> {
     persistence_manager.save_profile(&profile)?;
 
     info!("Centotype shutdown complete");
-    Ok(())
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Apply the settings that can change while running: theme, text display,
+/// key timing and key bindings. Returns the palette in use.
+fn apply_display_settings(
+    engine: &CentotypeEngine,
+    config: &Config,
+    themes: &ThemeRegistry,
+) -> anyhow::Result<ThemePalette> {
+    // A user theme replaces the preset when it loaded and passed validation
+    let palette = match config.custom_theme.as_deref() {
+        Some(id) => match themes.get(id) {
+            Some(palette) => palette.clone(),
+            None => {
+                tracing::warn!("Unknown custom theme '{}', using {:?}", id, config.theme);
+                ThemePalette::for_theme(config.theme)
+            }
+        },
+        None => ThemePalette::for_theme(config.theme),
+    };
+    engine.set_palette(palette.clone());
+    engine.set_text_display(config.text_display);
+    engine.set_key_timing(config.key_timing);
+    engine.set_keybindings(config.keybindings.clone())?;
+    Ok(palette)
+}
+
+/// Score and record the session a termination signal cut short last time,
+/// if there is one. Returns a notice saying so.
+fn record_interrupted_session(
+    core: &CentotypeCore,
+    persistence: &PersistenceManager,
+    profile: &mut UserProgress,
+) -> anyhow::Result<Option<String>> {
+    let state = match persistence.take_interrupted_session() {
+        Ok(Some(state)) => state,
+        Ok(None) => return Ok(None),
+        Err(e) => {
+            tracing::warn!("Discarding the interrupted session: {}", e);
+            return Ok(None);
+        }
+    };
+    // Nothing was typed; there is nothing to record
+    if state.keystrokes.is_empty() {
+        return Ok(None);
+    }
+
+    let result = core.score_interrupted_session(&state)?;
+    persistence.save_session_result(&result)?;
+    profile.update_with_result(result);
+    persistence.save_profile(profile)?;
+    info!("Recorded interrupted session {}", state.session_id);
+    Ok(Some("The session interrupted last time was recorded".to_string()))
+}
+
+/// Show the menus until the player quits, playing each session picked and
+/// folding its result into the profile. `notice` is shown on the first menu.
+async fn run_menus(
+    engine: &mut CentotypeEngine,
+    cli_manager: &CliManager,
+    persistence: &PersistenceManager,
+    themes: &ThemeRegistry,
+    profile: &mut UserProgress,
+    styles: MenuStyles,
+    mut notice: Option<String>,
+) -> anyhow::Result<()> {
+    let mut interface = Interface::new(styles);
+    loop {
+        // Rebuilt each time so grades and unlocks follow the last session
+        let mut menus = Menus::new(profile);
+        if let Some(notice) = notice.take() {
+            menus.set_notice(notice);
+        }
+        match interface.run(&mut menus)? {
+            MenuSelection::Start(mode) => {
+                notice = play(engine, persistence, profile, mode).await?
+            }
+            MenuSelection::Stats => interface.run_stats(&cli_manager.stats_report(profile)?)?,
+            MenuSelection::Settings => {
+                // Layout changes need a restart; the rest apply now
+                let config = cli_manager.edit_settings(&mut interface)?;
+                apply_display_settings(engine, &config, themes)?;
+            }
+            MenuSelection::Quit => return Ok(()),
+        }
+    }
+}
+
+/// Play sessions from `mode` for as long as the results screen asks for
+/// another one, saving the profile after each. Returns a notice for the
+/// menus when a mode cannot be played.
+async fn play(
+    engine: &mut CentotypeEngine,
+    persistence: &PersistenceManager,
+    profile: &mut UserProgress,
+    mut mode: TrainingMode,
+) -> anyhow::Result<Option<String>> {
+    // Empty for the mode's own content; a retry types the same text again
+    let mut text = String::new();
+    loop {
+        // A key bound to a session command that the mode needs typed is
+        // fixed in Settings, not worth ending the program over
+        if let Err(e) = engine.check_keybindings(mode) {
+            return Ok(Some(format!("{} (change the keys in Settings)", e)));
+        }
+
+        let slow_words = profile.repeatedly_slow_words(SLOW_WORD_MIN_SESSIONS, SLOW_WORD_LIMIT);
+        engine.set_slow_words(slow_words.into_iter().map(str::to_string).collect());
+        let furthest = profile.best_results.keys().max().copied();
+        engine.set_furthest_level(furthest.unwrap_or(LevelId(1)));
+
+        let outcome = engine.run(mode, text.clone()).await?;
+        profile.update_with_result(outcome.report.result.clone());
+        // Saved now so a later error cannot lose the session
+        persistence.save_profile(profile)?;
+
+        match outcome.action {
+            ResultsAction::Retry => text = outcome.report.target_text.clone(),
+            ResultsAction::NextLevel(level) => {
+                mode = TrainingMode::Arcade { level };
+                text.clear();
+            }
+            ResultsAction::DrillWeakKeys(keys) => {
+                mode = TrainingMode::Drill {
+                    category: DrillCategory::WeakKeys,
+                    duration_secs: DEFAULT_DRILL_MINUTES * 60,
+                };
+                let seed = profile.total_sessions as u64;
+                text = weak_key_text(&keys, seed, WEAK_KEY_DRILL_LENGTH);
+            }
+            ResultsAction::DrillSlowWords(words) => {
+                mode = TrainingMode::Drill {
+                    category: DrillCategory::SlowWords,
+                    duration_secs: DEFAULT_DRILL_MINUTES * 60,
+                };
+                let words: Vec<&str> = words.iter().map(String::as_str).collect();
+                let seed = profile.total_sessions as u64;
+                text = slow_word_text(&words, seed, SLOW_WORD_DRILL_LENGTH);
+            }
+            ResultsAction::Quit => return Ok(None),
+        }
+    }
 }
@@ -60,7 +60,7 @@ async fn test_e2e_complete_level_1_workflow() {
     }
 
     // 4. Complete the session
-    let session_result = env.core.complete_session().expect("Failed to complete session");
+    let session_result = env.core.complete_session(false).expect("Failed to complete session");
 
     // 5. Verify session results
     assert!(session_result.metrics.accuracy > 95.0, "Perfect typing should have high accuracy");
@@ -121,7 +121,7 @@ async fn test_e2e_level_progression() {
         }
 
         // Complete session
-        let session_result = env.core.complete_session().expect("Failed to complete session");
+        let session_result = env.core.complete_session(false).expect("Failed to complete session");
 
         // Verify basic metrics
         assert!(session_result.metrics.accuracy > 80.0,
@@ -175,13 +175,16 @@ async fn test_e2e_error_handling() {
         char_typed: Some('a'),
         is_correction: false,
         cursor_pos: 0,
+        physical_char: None,
+        dwell: None,
+        flight: None,
     };
 
     let result = env.core.add_keystroke(fake_session_id, keystroke);
     assert!(result.is_err(), "Adding keystroke to fake session should fail");
 
     // Complete the real session
-    let _session_result = env.core.complete_session().expect("Failed to complete session");
+    let _session_result = env.core.complete_session(false).expect("Failed to complete session");
 }
 
 #[tokio::test]
@@ -247,7 +250,7 @@ async fn test_e2e_performance_tracking() {
                 .expect("Failed to add keystroke");
         }
 
-        let session_result = env.core.complete_session().expect("Failed to complete session");
+        let session_result = env.core.complete_session(false).expect("Failed to complete session");
         wpm_scores.push(session_result.metrics.raw_wpm);
 
         env.persistence.save_session_result(&session_result)
@@ -269,6 +272,76 @@ async fn test_e2e_performance_tracking() {
     // assert!(last_wpm >= first_wpm * 0.9, "Performance should generally improve or stay similar");
 }
 
+#[test]
+fn test_e2e_command_error_is_a_plain_message() {
+    let home = TempDir::new().unwrap();
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_centotype"))
+        .args(["config", "set", "theme", "bogus"])
+        .env("HOME", home.path())
+        .env("XDG_CONFIG_HOME", home.path().join("config"))
+        .env("XDG_DATA_HOME", home.path().join("data"))
+        .env("RUST_BACKTRACE", "1")
+        .output()
+        .expect("Failed to run centotype");
+
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(
+        stderr.trim_end(),
+        "centotype: Configuration error: Invalid value 'bogus' for theme; expected one of: \
+         default, high-contrast, mono, deuteranopia, protanopia, tritanopia"
+    );
+    // No terminal recovery escapes for a command that never took the terminal
+    assert!(output.stdout.is_empty());
+}
+
+#[test]
+fn test_e2e_interrupted_session_is_recorded_on_next_start() {
+    let home = TempDir::new().unwrap();
+    let data_dir = home.path().join("data/centotype");
+    std::fs::create_dir_all(&data_dir).unwrap();
+
+    // What a SIGTERM during a session leaves behind
+    let started_at = chrono::Utc::now() - chrono::Duration::seconds(10);
+    let keystrokes = simulate_perfect_typing("the qu");
+    let state = SessionState {
+        session_id: uuid::Uuid::new_v4(),
+        mode: TrainingMode::Arcade { level: LevelId(1) },
+        target_text: "the quick fox".to_string(),
+        typed_text: "the qu".to_string(),
+        cursor_position: 6,
+        started_at,
+        paused_duration: std::time::Duration::ZERO,
+        is_paused: false,
+        is_completed: false,
+        keystrokes,
+    };
+    std::fs::write(
+        data_dir.join("interrupted_session.json"),
+        serde_json::to_string(&state).unwrap(),
+    )
+    .unwrap();
+
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_centotype"))
+        .args(["stats", "--json"])
+        .env("HOME", home.path())
+        .env("XDG_CONFIG_HOME", home.path().join("config"))
+        .env("XDG_DATA_HOME", home.path().join("data"))
+        .output()
+        .expect("Failed to run centotype");
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+
+    assert!(!data_dir.join("interrupted_session.json").exists());
+    let history = std::fs::read_to_string(data_dir.join("sessions.jsonl")).unwrap();
+    let result: SessionResult = serde_json::from_str(history.trim()).unwrap();
+    assert_eq!(result.session_id, state.session_id);
+    assert!(result.unverified);
+    let profile: UserProgress =
+        serde_json::from_str(&std::fs::read_to_string(data_dir.join("profile.json")).unwrap())
+            .unwrap();
+    assert_eq!(profile.total_sessions, 1);
+}
+
 // Helper function to simulate perfect typing
 fn simulate_perfect_typing(content: &str) -> Vec<Keystroke> {
     let mut keystrokes = Vec::new();
@@ -280,6 +353,9 @@ fn simulate_perfect_typing(content: &str) -> Vec<Keystroke> {
             char_typed: Some(ch),
             is_correction: false,
             cursor_pos: i,
+            physical_char: None,
+            dwell: None,
+            flight: None,
         };
         keystrokes.push(keystroke);
     }
@@ -302,6 +378,9 @@ fn simulate_typing_with_errors(content: &str, accuracy: f64) -> Vec<Keystroke> {
                 char_typed: Some('x'), // Wrong character
                 is_correction: false,
                 cursor_pos: i,
+                physical_char: None,
+                dwell: None,
+                flight: None,
             };
             keystrokes.push(wrong_keystroke);
             time_offset += 100;
@@ -312,6 +391,9 @@ fn simulate_typing_with_errors(content: &str, accuracy: f64) -> Vec<Keystroke> {
                 char_typed: Some(ch), // Correct character
                 is_correction: true,
                 cursor_pos: i,
+                physical_char: None,
+                dwell: None,
+                flight: None,
             };
             keystrokes.push(correction_keystroke);
         } else {
@@ -321,6 +403,9 @@ fn simulate_typing_with_errors(content: &str, accuracy: f64) -> Vec<Keystroke> {
                 char_typed: Some(ch),
                 is_correction: false,
                 cursor_pos: i,
+                physical_char: None,
+                dwell: None,
+                flight: None,
             };
             keystrokes.push(keystroke);
         }
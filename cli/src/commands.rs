@@ -1,2 +1,179 @@
-//! commands stub implementation
-pub struct Commands {}
+//! Subcommands that run without the full-screen interface
+use crate::settings::check_available;
+use crate::ConfigAction;
+use centotype_core::types::*;
+use centotype_core::Setting;
+use centotype_engine::ThemeRegistry;
+use centotype_persistence::PersistenceManager;
+
+/// Width of the name column in `config show`
+const NAME_WIDTH: usize = 28;
+/// Width of the value column in `config show --origin`
+const VALUE_WIDTH: usize = 16;
+
+/// Run `centotype config show|get|set|reset`, with `flags` the `--set`
+/// overrides of this run. Returns the lines to print; invalid keys and
+/// values are errors that say what is accepted. `set` and `reset` change
+/// the user file only.
+pub fn run_config(
+    action: ConfigAction,
+    persistence: &PersistenceManager,
+    flags: &[String],
+) -> Result<Vec<String>> {
+    match action {
+        ConfigAction::Show { origin } => {
+            let resolved = persistence.resolve_config(flags)?;
+            Ok(Setting::all()
+                .into_iter()
+                .map(|setting| {
+                    let value = resolved.config.get(setting);
+                    if origin {
+                        format!(
+                            "{:<name$} = {:<value$} {}",
+                            setting.name(),
+                            value,
+                            resolved.origin(setting),
+                            name = NAME_WIDTH,
+                            value = VALUE_WIDTH
+                        )
+                    } else {
+                        format!("{:<name$} = {}", setting.name(), value, name = NAME_WIDTH)
+                    }
+                })
+                .collect())
+        }
+        ConfigAction::Get { key } => {
+            let setting: Setting = key.parse()?;
+            Ok(vec![persistence.resolve_config(flags)?.config.get(setting)])
+        }
+        ConfigAction::Set { key, value } => {
+            let setting: Setting = key.parse()?;
+            let mut config = persistence.load_config()?;
+            config.set(setting, &value)?;
+
+            let mut themes = ThemeRegistry::new();
+            themes.load_dir(&persistence.themes_dir())?;
+            check_available(setting, &config, &themes, &persistence.load_layouts()?)?;
+
+            persistence.save_config(&config)?;
+            Ok(Vec::new())
+        }
+        ConfigAction::Reset { key } => {
+            let setting: Setting = key.parse()?;
+            let mut config = persistence.load_config()?;
+            config.set(setting, &persistence.inherited_config()?.get(setting))?;
+            persistence.save_config(&config)?;
+            Ok(Vec::new())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get(key: &str) -> ConfigAction {
+        ConfigAction::Get {
+            key: key.to_string(),
+        }
+    }
+
+    fn set(key: &str, value: &str) -> ConfigAction {
+        ConfigAction::Set {
+            key: key.to_string(),
+            value: value.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_config_get_set_reset() {
+        let dir = tempfile::tempdir().unwrap();
+        let persistence = PersistenceManager::new_with_path(dir.path()).unwrap();
+
+        assert_eq!(
+            run_config(get("theme"), &persistence, &[]).unwrap(),
+            ["default"]
+        );
+        run_config(set("theme", "tritanopia"), &persistence, &[]).unwrap();
+        run_config(set("keybindings.pause", "f5 ctrl+p"), &persistence, &[]).unwrap();
+        assert_eq!(persistence.load_config().unwrap().theme, Theme::Tritanopia);
+        assert_eq!(
+            run_config(get("keybindings.pause"), &persistence, &[]).unwrap(),
+            ["f5 ctrl+p"]
+        );
+
+        // Invalid input says what is accepted and leaves the file alone
+        let err = run_config(set("theme", "neon"), &persistence, &[]).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("expected one of: default, high-contrast"));
+        let err = run_config(set("custom_theme", "neon"), &persistence, &[]).unwrap_err();
+        assert!(err.to_string().contains("No theme named 'neon'"));
+        assert!(run_config(get("volume"), &persistence, &[]).is_err());
+        assert_eq!(persistence.load_config().unwrap().custom_theme, None);
+
+        let reset = ConfigAction::Reset {
+            key: "theme".to_string(),
+        };
+        run_config(reset, &persistence, &[]).unwrap();
+        assert_eq!(persistence.load_config().unwrap().theme, Theme::Default);
+    }
+
+    #[test]
+    fn test_config_show_origin() {
+        let dir = tempfile::tempdir().unwrap();
+        let system = dir.path().join("system.toml");
+        std::fs::write(&system, "theme = \"Mono\"\nlayout = \"Dvorak\"\n").unwrap();
+        let persistence = PersistenceManager::new_with_path(dir.path())
+            .unwrap()
+            .with_system_config(system.clone());
+
+        // The user file keeps only its own change over the system file
+        run_config(set("layout", "colemak"), &persistence, &[]).unwrap();
+        let user_file = std::fs::read_to_string(persistence.config_path()).unwrap();
+        assert_eq!(user_file.trim(), "layout = \"Colemak\"");
+
+        let flags = ["log_level=debug".to_string()];
+        let show = ConfigAction::Show { origin: true };
+        let lines = run_config(show, &persistence, &flags).unwrap();
+        assert_eq!(lines.len(), Setting::all().len());
+        let line = |name: &str| {
+            lines
+                .iter()
+                .find(|line| line.starts_with(&format!("{} ", name)))
+                .unwrap()
+                .clone()
+        };
+        assert_eq!(
+            line("layout"),
+            format!(
+                "{:<28} = colemak          user file {}",
+                "layout",
+                persistence.config_path().display()
+            )
+        );
+        assert_eq!(
+            line("theme"),
+            format!(
+                "{:<28} = mono             system file {}",
+                "theme",
+                system.display()
+            )
+        );
+        assert_eq!(
+            line("log_level"),
+            format!("{:<28} = debug            --set flag", "log_level")
+        );
+        assert!(line("language").ends_with("english          default"));
+
+        // Reset drops back to the system value
+        let reset = ConfigAction::Reset {
+            key: "layout".to_string(),
+        };
+        run_config(reset, &persistence, &[]).unwrap();
+        assert_eq!(
+            persistence.load_config().unwrap().layout,
+            KeyboardLayout::Dvorak
+        );
+    }
+}
@@ -1,2 +1,848 @@
-//! interface stub implementation
-pub struct Interface {}
+//! Full-screen drawing and the event loop for the menus
+use crate::menus::{
+    drill_label, LevelTile, MainItem, MenuScreen, MenuSelection, Menus, GRID_COLUMNS,
+};
+use crate::navigation::NavKey;
+use crate::settings::{FormAction, SettingsForm};
+use crate::stats::{format_duration, StatsReport, TierSummary, HEATMAP_WEEKS};
+use centotype_core::settings::SettingKind;
+use centotype_core::types::*;
+use centotype_core::Setting;
+use centotype_engine::{ColorResolver, ThemePalette, TtyManager, TypingModeGuard};
+use centotype_platform::ColorSupport;
+use chrono::Datelike;
+use crossterm::event::{self, Event};
+use ratatui::{
+    backend::CrosstermBackend,
+    buffer::Buffer,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Paragraph, Widget},
+    Terminal,
+};
+use std::io;
+
+/// Width of one level tile: right-aligned number, space, grade, gap
+const TILE_WIDTH: u16 = 6;
+/// Width of the tier label in front of each grid row
+const TIER_LABEL_WIDTH: u16 = 4;
+/// Width of the label column in the main menu and drill lists
+const LABEL_WIDTH: usize = 14;
+const FOOTER: &str = "↑↓←→/hjkl move  Enter select  Esc back  q quit";
+const SETTINGS_FOOTER: &str = "↑↓ move  ←→ change  Enter edit  r reset  s save  Esc back";
+const EDIT_FOOTER: &str = "Enter apply  Esc cancel";
+const STATS_FOOTER: &str = "Esc back  q quit";
+/// Width of the name column in the settings form
+const SETTING_NAME_WIDTH: usize = 28;
+/// Typing pane sample for the theme preview: typed, mistyped, caret, pending
+const PREVIEW_SAMPLE: [&str; 4] = ["fn main() { let ", "y", "x", " = 42; }"];
+/// Width of the activity and grades column of the stats dashboard
+const STATS_LEFT_WIDTH: u16 = 32;
+const WEEKDAYS: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+
+/// Styles for the parts of the menus
+#[derive(Debug, Clone, Copy)]
+pub struct MenuStyles {
+    pub title: Style,
+    pub text: Style,
+    pub dimmed: Style,
+    /// Added to the entry or tile under the cursor
+    pub selected: Modifier,
+    pub notice: Style,
+    pub grade_s: Style,
+    pub grade_a: Style,
+    pub grade_b: Style,
+    pub grade_c: Style,
+    pub grade_d: Style,
+    /// Unlocked levels not played yet
+    pub unplayed: Style,
+    /// Levels that cannot be played yet
+    pub locked: Style,
+}
+
+impl MenuStyles {
+    /// Resolve a theme for the terminal. Grades run from the success colour
+    /// down to the incorrect-text colour; on monochrome terminals they are
+    /// told apart by the letter alone.
+    pub fn resolve(palette: &ThemePalette, support: ColorSupport) -> Self {
+        if palette.monochrome || support == ColorSupport::Monochrome {
+            return Self::monochrome();
+        }
+
+        let mut resolver = ColorResolver::new(support);
+        let background = [palette.background];
+        let mut fg = |role, rgb| Style::default().fg(resolver.foreground(role, rgb, &background));
+        let success = fg("success", palette.success);
+        let dimmed = fg("dimmed_text", palette.dimmed_text);
+        Self {
+            title: fg("accent", palette.accent).add_modifier(palette.modifiers.header),
+            text: fg("normal_text", palette.normal_text),
+            dimmed,
+            selected: Modifier::REVERSED,
+            notice: fg("warning", palette.warning),
+            grade_s: success.add_modifier(Modifier::BOLD),
+            grade_a: success,
+            grade_b: fg("accent", palette.accent),
+            grade_c: fg("warning", palette.warning),
+            grade_d: fg("incorrect_text", palette.incorrect_text),
+            unplayed: fg("pending_text", palette.pending_text),
+            locked: dimmed,
+        }
+    }
+
+    /// Bold and dim in place of colour
+    fn monochrome() -> Self {
+        let plain = Style::default();
+        Self {
+            title: plain.add_modifier(Modifier::BOLD),
+            text: plain,
+            dimmed: plain.add_modifier(Modifier::DIM),
+            selected: Modifier::REVERSED,
+            notice: plain.add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
+            grade_s: plain.add_modifier(Modifier::BOLD),
+            grade_a: plain.add_modifier(Modifier::BOLD),
+            grade_b: plain,
+            grade_c: plain,
+            grade_d: plain,
+            unplayed: plain,
+            locked: plain.add_modifier(Modifier::DIM),
+        }
+    }
+
+    pub fn grade(&self, grade: Grade) -> Style {
+        match grade {
+            Grade::S => self.grade_s,
+            Grade::A => self.grade_a,
+            Grade::B => self.grade_b,
+            Grade::C => self.grade_c,
+            Grade::D => self.grade_d,
+        }
+    }
+
+    fn tile(&self, tile: &LevelTile) -> Style {
+        match (&tile.best, tile.unlocked) {
+            (Some(best), _) => self.grade(best.grade),
+            (None, true) => self.unplayed,
+            (None, false) => self.locked,
+        }
+    }
+}
+
+/// Typing pane styles for the theme preview in the settings form
+#[derive(Debug, Clone, Copy)]
+pub struct PreviewStyles {
+    pub correct: Style,
+    pub incorrect: Style,
+    pub cursor: Style,
+    pub pending: Style,
+}
+
+impl PreviewStyles {
+    pub fn resolve(palette: &ThemePalette, support: ColorSupport) -> Self {
+        if palette.monochrome || support == ColorSupport::Monochrome {
+            let plain = Style::default();
+            return Self {
+                correct: plain,
+                incorrect: plain.add_modifier(Modifier::UNDERLINED | Modifier::BOLD),
+                cursor: plain.add_modifier(Modifier::BOLD | Modifier::REVERSED),
+                pending: plain,
+            };
+        }
+
+        let mut resolver = ColorResolver::new(support);
+        let (error_bg, error_rgb) = resolver.background(palette.error_bg);
+        let (cursor_bg, cursor_rgb) = resolver.background(palette.cursor_bg);
+        let background = palette.background;
+        let modifiers = palette.modifiers;
+        Self {
+            correct: Style::default()
+                .fg(resolver.foreground("correct_text", palette.correct_text, &[background]))
+                .add_modifier(modifiers.correct),
+            incorrect: Style::default()
+                .fg(resolver.foreground(
+                    "incorrect_text",
+                    palette.incorrect_text,
+                    &[background, error_rgb],
+                ))
+                .bg(error_bg)
+                .add_modifier(modifiers.incorrect),
+            cursor: Style::default()
+                .fg(resolver.foreground("cursor", palette.cursor, &[cursor_rgb]))
+                .bg(cursor_bg)
+                .add_modifier(modifiers.caret),
+            pending: Style::default()
+                .fg(resolver.foreground("pending_text", palette.pending_text, &[background]))
+                .add_modifier(modifiers.pending),
+        }
+    }
+}
+
+impl Default for MenuStyles {
+    fn default() -> Self {
+        Self::resolve(&ThemePalette::default(), ColorSupport::TrueColor)
+    }
+}
+
+/// Runs the menus full screen until the player picks something
+pub struct Interface {
+    styles: MenuStyles,
+}
+
+impl Interface {
+    pub fn new(styles: MenuStyles) -> Self {
+        Self { styles }
+    }
+
+    /// Show the menus and wait for a selection. The terminal is restored
+    /// before returning, so the caller can start a session straight away.
+    pub fn run(&mut self, menus: &mut Menus) -> Result<MenuSelection> {
+        let mut tty = TtyManager::new()?;
+        let _guard = TypingModeGuard::new(&mut tty)?;
+        let mut terminal = Terminal::new(CrosstermBackend::new(io::stdout()))?;
+
+        loop {
+            terminal.draw(|frame| {
+                let area = frame.size();
+                draw(menus, &self.styles, area, frame.buffer_mut());
+            })?;
+
+            // Resizes redraw on the next pass
+            if let Event::Key(key) = event::read()? {
+                if let Some(selection) = NavKey::from_event(&key).and_then(|nav| menus.handle(nav))
+                {
+                    return Ok(selection);
+                }
+            }
+        }
+    }
+
+    /// Show the settings form until it is closed, passing each save to
+    /// `save`. The form is drawn in the theme it has selected, so theme
+    /// changes show as they are made.
+    pub fn run_settings(
+        &mut self,
+        form: &mut SettingsForm,
+        support: ColorSupport,
+        mut save: impl FnMut(&Config) -> Result<()>,
+    ) -> Result<()> {
+        let mut tty = TtyManager::new()?;
+        let _guard = TypingModeGuard::new(&mut tty)?;
+        let mut terminal = Terminal::new(CrosstermBackend::new(io::stdout()))?;
+        let mut saved_palette = form.palette();
+
+        loop {
+            let palette = form.palette();
+            self.styles = MenuStyles::resolve(&palette, support);
+            let preview = PreviewStyles::resolve(&palette, support);
+            terminal.draw(|frame| {
+                let area = frame.size();
+                draw_settings(form, &self.styles, &preview, area, frame.buffer_mut());
+            })?;
+
+            if let Event::Key(key) = event::read()? {
+                match form.handle(&key) {
+                    Some(FormAction::Save) => match save(form.config()) {
+                        Ok(()) => {
+                            form.mark_saved();
+                            saved_palette = palette;
+                        }
+                        Err(e) => form.mark_save_failed(&e),
+                    },
+                    Some(FormAction::Close) => break,
+                    None => {}
+                }
+            }
+        }
+
+        // Discarded theme changes leave the menus as they were
+        self.styles = MenuStyles::resolve(&saved_palette, support);
+        Ok(())
+    }
+
+    /// Show the stats dashboard until the player goes back
+    pub fn run_stats(&mut self, report: &StatsReport) -> Result<()> {
+        let mut tty = TtyManager::new()?;
+        let _guard = TypingModeGuard::new(&mut tty)?;
+        let mut terminal = Terminal::new(CrosstermBackend::new(io::stdout()))?;
+
+        loop {
+            terminal.draw(|frame| {
+                let area = frame.size();
+                draw_stats(report, &self.styles, area, frame.buffer_mut());
+            })?;
+
+            if let Event::Key(key) = event::read()? {
+                if matches!(
+                    NavKey::from_event(&key),
+                    Some(NavKey::Back | NavKey::Quit | NavKey::Select)
+                ) {
+                    return Ok(());
+                }
+            }
+        }
+    }
+}
+
+impl Default for Interface {
+    fn default() -> Self {
+        Self::new(MenuStyles::default())
+    }
+}
+
+/// Draw the current menu screen: title, body, notice and key help
+pub fn draw(menus: &Menus, styles: &MenuStyles, area: Rect, buf: &mut Buffer) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(2),
+            Constraint::Min(0),
+            Constraint::Length(1),
+            Constraint::Length(1),
+        ])
+        .split(area);
+
+    let title = match menus.screen() {
+        MenuScreen::Main => "Centotype",
+        MenuScreen::Levels => "Centotype · Arcade",
+        MenuScreen::Drills => "Centotype · Drills",
+    };
+    Paragraph::new(Span::styled(title, styles.title)).render(chunks[0], buf);
+
+    match menus.screen() {
+        MenuScreen::Main => {
+            let rows = MainItem::ALL.iter().map(|item| {
+                (
+                    item.label(),
+                    item.description(),
+                    *item == menus.selected_item(),
+                )
+            });
+            draw_list(rows, styles, chunks[1], buf);
+        }
+        MenuScreen::Levels => draw_levels(menus, styles, chunks[1], buf),
+        MenuScreen::Drills => {
+            let rows = DrillCategory::ALL.iter().map(|category| {
+                let (label, description) = drill_label(*category);
+                (
+                    label,
+                    description.to_string(),
+                    *category == menus.selected_drill(),
+                )
+            });
+            draw_list(rows, styles, chunks[1], buf);
+        }
+    }
+
+    if let Some(notice) = menus.notice() {
+        Paragraph::new(Span::styled(notice, styles.notice)).render(chunks[2], buf);
+    }
+    Paragraph::new(Span::styled(FOOTER, styles.dimmed)).render(chunks[3], buf);
+}
+
+/// One entry per line: label, then description; the selected entry is
+/// marked and highlighted
+fn draw_list<'a>(
+    rows: impl Iterator<Item = (&'a str, String, bool)>,
+    styles: &MenuStyles,
+    area: Rect,
+    buf: &mut Buffer,
+) {
+    let lines: Vec<Line> = rows
+        .map(|(label, description, selected)| {
+            let (marker, label_style) = if selected {
+                ("› ", styles.text.add_modifier(styles.selected))
+            } else {
+                ("  ", styles.text)
+            };
+            Line::from(vec![
+                Span::styled(marker, styles.title),
+                Span::styled(
+                    format!("{:<width$}", label, width = LABEL_WIDTH),
+                    label_style,
+                ),
+                Span::styled(format!("  {}", description), styles.dimmed),
+            ])
+        })
+        .collect();
+    Paragraph::new(lines).render(area, buf);
+}
+
+/// The 10x10 level grid, one tier per row, then a line about the level
+/// under the cursor. Each row gets a second line of stars when there is
+/// room for it.
+fn draw_levels(menus: &Menus, styles: &MenuStyles, area: Rect, buf: &mut Buffer) {
+    let rows = menus.tiles().len().div_ceil(GRID_COLUMNS) as u16;
+    let with_stars = area.height >= rows * 2 + 2;
+    let row_height = if with_stars { 2 } else { 1 };
+    let selected = menus.selected_tile().level;
+
+    for (row, tiles) in menus.tiles().chunks(GRID_COLUMNS).enumerate() {
+        let y = area.y + row as u16 * row_height;
+        if y >= area.bottom() {
+            break;
+        }
+        buf.set_string(area.x, y, format!("T{:<2}", row + 1), styles.dimmed);
+
+        for (column, tile) in tiles.iter().enumerate() {
+            let x = area.x + TIER_LABEL_WIDTH + column as u16 * TILE_WIDTH;
+            if x + TILE_WIDTH > area.right() {
+                break;
+            }
+            let mut style = styles.tile(tile);
+            if tile.level == selected {
+                style = style.add_modifier(styles.selected);
+            }
+            buf.set_string(x, y, tile_label(tile), style);
+
+            if with_stars && y + 1 < area.bottom() {
+                if let Some(best) = &tile.best {
+                    buf.set_string(x + 1, y + 1, stars(best.stars), styles.grade(best.grade));
+                }
+            }
+        }
+    }
+
+    let detail_y = area.y + rows * row_height + 1;
+    if detail_y < area.bottom() {
+        let detail = Rect::new(area.x, detail_y, area.width, 1);
+        Paragraph::new(level_detail(menus.selected_tile(), styles)).render(detail, buf);
+    }
+}
+
+/// Level number and best grade; `·` for not played, blank for locked
+fn tile_label(tile: &LevelTile) -> String {
+    let grade = match (&tile.best, tile.unlocked) {
+        (Some(best), _) => format!("{:?}", best.grade),
+        (None, true) => "·".to_string(),
+        (None, false) => " ".to_string(),
+    };
+    format!("{:>3} {}", tile.level.0, grade)
+}
+
+fn stars(count: u8) -> String {
+    format!(
+        "{}{}",
+        "★".repeat(count as usize),
+        "☆".repeat(3usize.saturating_sub(count as usize))
+    )
+}
+
+fn level_detail<'a>(tile: &LevelTile, styles: &MenuStyles) -> Line<'a> {
+    let heading = Span::styled(
+        format!("Level {} · Tier {}  ", tile.level.0, tile.level.tier().0),
+        styles.text,
+    );
+    let rest = match (&tile.best, tile.unlocked) {
+        (Some(best), _) => Span::styled(
+            format!(
+                "Best {:?} {}  {:.0} WPM  {:.1}%  Skill index {:.0}",
+                best.grade,
+                stars(best.stars),
+                best.metrics.effective_wpm,
+                best.metrics.accuracy,
+                best.skill_index
+            ),
+            styles.grade(best.grade),
+        ),
+        (None, true) => Span::styled("Not played yet", styles.unplayed),
+        (None, false) => Span::styled("Locked", styles.locked),
+    };
+    Line::from(vec![heading, rest])
+}
+
+/// Draw the settings form: the settings with their values, help for the
+/// selected one, a preview of the typing pane in the selected theme, the
+/// notice and key help
+pub fn draw_settings(
+    form: &SettingsForm,
+    styles: &MenuStyles,
+    preview: &PreviewStyles,
+    area: Rect,
+    buf: &mut Buffer,
+) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(2),
+            Constraint::Min(0),
+            Constraint::Length(3),
+            Constraint::Length(2),
+            Constraint::Length(1),
+            Constraint::Length(1),
+        ])
+        .split(area);
+
+    let title = if form.has_unsaved_changes() {
+        "Centotype · Settings (unsaved)"
+    } else {
+        "Centotype · Settings"
+    };
+    Paragraph::new(Span::styled(title, styles.title)).render(chunks[0], buf);
+
+    draw_setting_rows(form, styles, chunks[1], buf);
+
+    let selected = form.selected();
+    let mut help = vec![Line::from(Span::styled(
+        selected.description(),
+        styles.text,
+    ))];
+    help.push(match selected.kind() {
+        SettingKind::Choice(choices) => {
+            Line::from(Span::styled(choices.join(" · "), styles.dimmed))
+        }
+        SettingKind::Toggle => Line::from(Span::styled("true · false", styles.dimmed)),
+        SettingKind::Text => Line::from(Span::styled(
+            match selected {
+                Setting::KeyBinding(_) => "Keys separated by spaces, e.g. ctrl+c esc",
+                _ => "An installed id, or none",
+            },
+            styles.dimmed,
+        )),
+    });
+    Paragraph::new(help).render(chunks[2], buf);
+
+    let sample = Line::from(vec![
+        Span::styled("Preview  ", styles.dimmed),
+        Span::styled(PREVIEW_SAMPLE[0], preview.correct),
+        Span::styled(PREVIEW_SAMPLE[1], preview.incorrect),
+        Span::styled(PREVIEW_SAMPLE[2], preview.cursor),
+        Span::styled(PREVIEW_SAMPLE[3], preview.pending),
+    ]);
+    Paragraph::new(sample).render(chunks[3], buf);
+
+    if let Some(notice) = form.notice() {
+        Paragraph::new(Span::styled(notice, styles.notice)).render(chunks[4], buf);
+    }
+    let footer = if form.editing().is_some() {
+        EDIT_FOOTER
+    } else {
+        SETTINGS_FOOTER
+    };
+    Paragraph::new(Span::styled(footer, styles.dimmed)).render(chunks[5], buf);
+}
+
+/// One setting per line, scrolled so the selected one stays in view
+fn draw_setting_rows(form: &SettingsForm, styles: &MenuStyles, area: Rect, buf: &mut Buffer) {
+    let selected = form.selected();
+    let index = form
+        .settings()
+        .iter()
+        .position(|setting| *setting == selected)
+        .unwrap_or(0);
+    let skip = (index + 1).saturating_sub(area.height as usize);
+
+    let lines: Vec<Line> = form
+        .settings()
+        .iter()
+        .skip(skip)
+        .map(|&setting| {
+            let is_selected = setting == selected;
+            let (marker, name_style) = if is_selected {
+                ("› ", styles.text.add_modifier(styles.selected))
+            } else {
+                ("  ", styles.text)
+            };
+            let value = match form.editing() {
+                Some(text) if is_selected => Span::styled(format!("{}▏", text), styles.title),
+                _ => {
+                    let value = form.config().get(setting);
+                    if value.is_empty() {
+                        Span::styled("unbound", styles.dimmed)
+                    } else {
+                        Span::styled(value, styles.text)
+                    }
+                }
+            };
+            Line::from(vec![
+                Span::styled(marker, styles.title),
+                Span::styled(
+                    format!("{:<width$}", setting.name(), width = SETTING_NAME_WIDTH),
+                    name_style,
+                ),
+                Span::raw("  "),
+                value,
+            ])
+        })
+        .collect();
+    Paragraph::new(lines).render(area, buf);
+}
+
+/// Draw the stats dashboard: totals and records, the activity heatmap and
+/// grades beside the tier table, then the most recent sessions
+pub fn draw_stats(report: &StatsReport, styles: &MenuStyles, area: Rect, buf: &mut Buffer) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(2),
+            Constraint::Length(3),
+            Constraint::Length(12),
+            Constraint::Min(0),
+            Constraint::Length(1),
+        ])
+        .split(area);
+    Paragraph::new(Span::styled("Centotype · Stats", styles.title)).render(chunks[0], buf);
+
+    let mut totals = format!(
+        "Sessions {}  Time {}  Levels {}/100  Skill index {:.0}",
+        report.total_sessions,
+        format_duration(report.total_time_seconds),
+        report.levels_completed,
+        report.overall_skill_index
+    );
+    if let Some(change) = report.wpm_change {
+        totals.push_str(&format!("  Trend {:+.1} WPM", change));
+    }
+    let records = &report.records;
+    let summary = vec![
+        Line::from(Span::styled(totals, styles.text)),
+        Line::from(vec![
+            Span::styled("Records  ", styles.dimmed),
+            Span::styled(
+                match (&records.effective_wpm, &records.accuracy, &records.streak) {
+                    (Some(wpm), Some(accuracy), Some(streak)) => format!(
+                        "{:.0} WPM · {:.1}% accuracy · {:.0} streak",
+                        wpm.value, accuracy.value, streak.value
+                    ),
+                    _ => "None yet".to_string(),
+                },
+                styles.text,
+            ),
+        ]),
+    ];
+    Paragraph::new(summary).render(chunks[1], buf);
+
+    let middle = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Length(STATS_LEFT_WIDTH), Constraint::Min(0)])
+        .split(chunks[2]);
+    draw_activity(report, styles, middle[0], buf);
+    draw_tiers(&report.tiers, styles, middle[1], buf);
+
+    let mut recent = vec![Line::from(Span::styled("Recent sessions", styles.title))];
+    recent.extend(report.recent.iter().map(|session| {
+        Line::from(vec![
+            Span::styled(
+                format!(
+                    "{}  {:<18} {:>4.0} WPM {:>6.1}%  ",
+                    session.completed_at.format("%m-%d %H:%M"),
+                    session.label,
+                    session.effective_wpm,
+                    session.accuracy
+                ),
+                styles.text,
+            ),
+            Span::styled(format!("{:?}", session.grade), styles.grade(session.grade)),
+            Span::styled(
+                if session.unverified {
+                    "  unverified"
+                } else {
+                    ""
+                },
+                styles.dimmed,
+            ),
+        ])
+    }));
+    if report.recent.is_empty() {
+        recent.push(Line::from(Span::styled("No sessions yet", styles.dimmed)));
+    }
+    Paragraph::new(recent).render(chunks[3], buf);
+
+    Paragraph::new(Span::styled(STATS_FOOTER, styles.dimmed)).render(chunks[4], buf);
+}
+
+/// Sessions per day as a weekday-by-week grid, then the grade counts
+fn draw_activity(report: &StatsReport, styles: &MenuStyles, area: Rect, buf: &mut Buffer) {
+    let heading = format!("Activity · {} weeks", HEATMAP_WEEKS);
+    buf.set_string(area.x, area.y, heading, styles.title);
+    for (row, weekday) in WEEKDAYS.iter().enumerate() {
+        buf.set_string(area.x, area.y + 1 + row as u16, weekday, styles.dimmed);
+    }
+
+    // Columns are Monday-first weeks, so the first may start part way down
+    let offset = report
+        .activity
+        .first()
+        .map_or(0, |day| day.date.weekday().num_days_from_monday() as usize);
+    for (index, day) in report.activity.iter().enumerate() {
+        let (column, row) = ((index + offset) / 7, (index + offset) % 7);
+        let x = area.x + 4 + column as u16 * 2;
+        let y = area.y + 1 + row as u16;
+        if x >= area.right() || y >= area.bottom() {
+            continue;
+        }
+        let (cell, style) = match day.sessions {
+            0 => ("·", styles.dimmed),
+            1 => ("░", styles.grade_a),
+            2 => ("▒", styles.grade_a),
+            3 | 4 => ("▓", styles.grade_a),
+            _ => ("█", styles.grade_a),
+        };
+        buf.set_string(x, y, cell, style);
+    }
+
+    let grades_y = area.y + 9;
+    if grades_y + 1 < area.bottom() {
+        buf.set_string(area.x, grades_y, "Grades", styles.title);
+        let mut spans = Vec::new();
+        for grade in [Grade::S, Grade::A, Grade::B, Grade::C, Grade::D] {
+            spans.push(Span::styled(format!("{:?}", grade), styles.grade(grade)));
+            spans.push(Span::styled(
+                format!(" {:<4}", report.grades.get(grade)),
+                styles.text,
+            ));
+        }
+        let line = Rect::new(area.x, grades_y + 1, area.width, 1);
+        Paragraph::new(Line::from(spans)).render(line, buf);
+    }
+}
+
+/// Completion, average grade and speed for each tier; locked tiers dimmed
+fn draw_tiers(tiers: &[TierSummary], styles: &MenuStyles, area: Rect, buf: &mut Buffer) {
+    let mut lines = vec![Line::from(Span::styled(
+        "Tier  Levels  Grade  Best WPM  Avg WPM",
+        styles.title,
+    ))];
+    lines.extend(tiers.iter().map(|tier| {
+        let style = if tier.unlocked {
+            styles.text
+        } else {
+            styles.locked
+        };
+        let wpm = |value: Option<f64>| value.map_or("-".to_string(), |v| format!("{:.0}", v));
+        let grade = match tier.average_grade {
+            Some(grade) => Span::styled(
+                format!("{:<7}", format!("{:?}", grade)),
+                styles.grade(grade),
+            ),
+            None => Span::styled(format!("{:<7}", "-"), style),
+        };
+        Line::from(vec![
+            Span::styled(
+                format!(
+                    "T{:<4} {:>2}/{:<4} ",
+                    tier.tier.0, tier.completed_levels, tier.total_levels
+                ),
+                style,
+            ),
+            grade,
+            Span::styled(
+                format!("{:>8}  {:>7}", wpm(tier.best_wpm), wpm(tier.average_wpm)),
+                style,
+            ),
+        ])
+    }));
+    Paragraph::new(lines).render(area, buf);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(buf: &Buffer, y: u16) -> String {
+        (0..buf.area.width)
+            .map(|x| buf.get(x, y).symbol.as_str())
+            .collect::<String>()
+            .trim_end()
+            .to_string()
+    }
+
+    #[test]
+    fn test_level_grid_layout() {
+        let mut menus = Menus::new(&UserProgress::default());
+        menus.handle(NavKey::Select);
+
+        let area = Rect::new(0, 0, 80, 24);
+        let mut buf = Buffer::empty(area);
+        draw(&menus, &MenuStyles::default(), area, &mut buf);
+
+        assert_eq!(row(&buf, 0), "Centotype · Arcade");
+        // Level 1 is unlocked and unplayed, the rest locked
+        assert_eq!(
+            row(&buf, 2),
+            "T1    1 ·   2     3     4     5     6     7     8     9    10"
+        );
+        // Only one line per row fits at 24 lines
+        assert_eq!(
+            row(&buf, 11),
+            "T10  91    92    93    94    95    96    97    98    99   100"
+        );
+        assert_eq!(row(&buf, 13), "Level 1 · Tier 1  Not played yet");
+        assert!(buf.get(5, 2).modifier.contains(Modifier::REVERSED));
+        assert_eq!(row(&buf, 23), FOOTER);
+    }
+
+    #[test]
+    fn test_settings_form_layout() {
+        use centotype_core::LayoutRegistry;
+        use centotype_engine::ThemeRegistry;
+
+        let form = SettingsForm::new(
+            Config::default(),
+            Config::default(),
+            ThemeRegistry::new(),
+            LayoutRegistry::new(),
+        );
+        let area = Rect::new(0, 0, 80, 24);
+        let mut buf = Buffer::empty(area);
+        let palette = form.palette();
+        draw_settings(
+            &form,
+            &MenuStyles::resolve(&palette, ColorSupport::TrueColor),
+            &PreviewStyles::resolve(&palette, ColorSupport::TrueColor),
+            area,
+            &mut buf,
+        );
+
+        assert_eq!(row(&buf, 0), "Centotype · Settings");
+        assert_eq!(row(&buf, 2), format!("› {:<28}  qwerty", "layout"));
+        assert_eq!(row(&buf, 3), format!("  {:<28}  none", "custom_layout"));
+        // More settings than lines: the list stops short of the help
+        assert_eq!(row(&buf, 16), format!("  {:<28}  f1", "keybindings.help"));
+        assert_eq!(row(&buf, 17), "Keyboard layout to practise");
+        assert_eq!(row(&buf, 20), "Preview  fn main() { let yx = 42; }");
+        assert_eq!(
+            buf.get(25, 20).bg,
+            PreviewStyles::resolve(&palette, ColorSupport::TrueColor)
+                .incorrect
+                .bg
+                .unwrap()
+        );
+        assert_eq!(row(&buf, 23), SETTINGS_FOOTER);
+    }
+
+    #[test]
+    fn test_stats_dashboard_layout() {
+        let today = chrono::NaiveDate::from_ymd_opt(2026, 3, 5).unwrap();
+        let report = StatsReport::new(&UserProgress::default(), &[], today);
+        let area = Rect::new(0, 0, 80, 24);
+        let mut buf = Buffer::empty(area);
+        draw_stats(&report, &MenuStyles::default(), area, &mut buf);
+
+        assert_eq!(row(&buf, 0), "Centotype · Stats");
+        assert_eq!(
+            row(&buf, 2),
+            "Sessions 0  Time 0m  Levels 0/100  Skill index 0"
+        );
+        assert_eq!(row(&buf, 3), "Records  None yet");
+        // The heatmap ends on today, a Thursday, in the last column
+        assert_eq!(
+            row(&buf, 9),
+            format!(
+                "Thu   {:<26}T4     0/10   -             -        -",
+                "· ".repeat(12)
+            )
+        );
+        assert!(row(&buf, 10).starts_with("Fri · "));
+        // Tier 1 is open, the rest locked
+        let styles = MenuStyles::default();
+        assert_eq!(buf.get(32, 6).fg, styles.text.fg.unwrap());
+        assert_eq!(buf.get(32, 7).fg, styles.locked.fg.unwrap());
+        assert_eq!(
+            row(&buf, 15),
+            "S 0   A 0   B 0   C 0   D 0     T10    0/10   -             -        -"
+        );
+        assert_eq!(row(&buf, 18), "No sessions yet");
+        assert_eq!(row(&buf, 23), STATS_FOOTER);
+    }
+}
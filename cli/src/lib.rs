@@ -6,16 +6,34 @@ pub mod commands;
 pub mod interface;
 pub mod menus;
 pub mod navigation;
+pub mod settings;
+pub mod stats;
 
 use centotype_core::types::*;
+use centotype_engine::ThemeRegistry;
+use centotype_persistence::PersistenceManager;
+use centotype_platform::ColorSupport;
 use clap::{Parser, Subcommand};
+use interface::Interface;
+use settings::SettingsForm;
+use stats::StatsReport;
+use std::sync::Arc;
+
+/// Length of a drill session when none is given
+pub const DEFAULT_DRILL_MINUTES: u32 = 5;
+/// Length of an endurance session when none is given
+pub const DEFAULT_ENDURANCE_MINUTES: u32 = 15;
 
 #[derive(Parser)]
 #[command(name = "centotype")]
 #[command(about = "CLI-based typing trainer with 100 progressive difficulty levels")]
 pub struct Cli {
+    /// Override a setting for this run, e.g. `--set theme=mono`; repeatable
+    #[arg(long = "set", value_name = "KEY=VALUE", global = true)]
+    pub overrides: Vec<String>,
+    /// Without a subcommand the interactive menu opens
     #[command(subcommand)]
-    pub command: Commands,
+    pub command: Option<Commands>,
 }
 
 #[derive(Subcommand)]
@@ -32,38 +50,110 @@ pub enum Commands {
         #[arg(short, long)]
         category: String,
         /// Duration in minutes
-        #[arg(short, long, default_value_t = 5)]
+        #[arg(short, long, default_value_t = DEFAULT_DRILL_MINUTES)]
         duration: u32,
     },
     /// Endurance training session
     Endurance {
         /// Duration in minutes
-        #[arg(short, long, default_value_t = 15)]
+        #[arg(short, long, default_value_t = DEFAULT_ENDURANCE_MINUTES)]
         duration: u32,
     },
     /// View statistics and progress
-    Stats,
-    /// Configure application settings
-    Config,
+    Stats {
+        /// Print the statistics as JSON instead of opening the dashboard
+        #[arg(long)]
+        json: bool,
+    },
+    /// Configure application settings; opens the settings form when no
+    /// action is given
+    Config {
+        #[command(subcommand)]
+        action: Option<ConfigAction>,
+    },
 }
 
-pub struct CliManager {
-    // CLI implementation
+/// Settings access for scripts
+#[derive(Subcommand)]
+pub enum ConfigAction {
+    /// Print every effective setting
+    Show {
+        /// Also print where each value came from: default, system file,
+        /// user file, environment or flag
+        #[arg(long)]
+        origin: bool,
+    },
+    /// Print the effective value of a setting
+    Get {
+        /// Setting name, e.g. `layout` or `keybindings.quit`
+        key: String,
+    },
+    /// Change a setting
+    Set {
+        /// Setting name, e.g. `layout` or `keybindings.quit`
+        key: String,
+        /// New value; key bindings take a space-separated list of keys
+        value: String,
+    },
+    /// Drop a setting from the user file, back to the system or built-in
+    /// value
+    Reset {
+        /// Setting name, e.g. `layout` or `keybindings.quit`
+        key: String,
+    },
 }
 
-impl Default for CliManager {
-    fn default() -> Self {
-        Self::new()
-    }
+pub struct CliManager {
+    persistence: Arc<PersistenceManager>,
+    /// Colours the terminal can show, for the settings form preview
+    color_support: ColorSupport,
+    /// `--set` flags given for this run
+    overrides: Vec<String>,
 }
 
 impl CliManager {
-    pub fn new() -> Self {
-        Self {}
+    pub fn new(
+        persistence: Arc<PersistenceManager>,
+        color_support: ColorSupport,
+        overrides: Vec<String>,
+    ) -> Self {
+        Self {
+            persistence,
+            color_support,
+            overrides,
+        }
     }
 
-    pub fn run(&self, cli: Cli) -> Result<()> {
-        match cli.command {
+    /// Open the settings form on the config files, saving to the user file
+    /// when asked. Returns the effective config afterwards.
+    pub fn edit_settings(&self, interface: &mut Interface) -> Result<Config> {
+        let mut themes = ThemeRegistry::new();
+        themes.load_dir(&self.persistence.themes_dir())?;
+        let mut form = SettingsForm::new(
+            self.persistence.load_config()?,
+            self.persistence.inherited_config()?,
+            themes,
+            self.persistence.load_layouts()?,
+        );
+
+        interface.run_settings(&mut form, self.color_support, |config| {
+            self.persistence.save_config(config)
+        })?;
+        Ok(self.persistence.resolve_config(&self.overrides)?.config)
+    }
+
+    /// Statistics from the session history and `progress`
+    pub fn stats_report(&self, progress: &UserProgress) -> Result<StatsReport> {
+        let history = self.persistence.load_session_results()?;
+        Ok(StatsReport::new(
+            progress,
+            &history,
+            chrono::Utc::now().date_naive(),
+        ))
+    }
+
+    pub fn run(&self, command: Commands) -> Result<()> {
+        match command {
             Commands::Play { level } => {
                 println!("Starting arcade mode, level: {:?}", level);
             }
@@ -73,11 +163,23 @@ impl CliManager {
             Commands::Endurance { duration } => {
                 println!("Starting endurance mode for {} minutes", duration);
             }
-            Commands::Stats => {
-                println!("Displaying statistics");
+            Commands::Stats { json } => {
+                let report = self.stats_report(&self.persistence.load_profile()?)?;
+                if json {
+                    println!("{}", report.to_json()?);
+                } else {
+                    Interface::default().run_stats(&report)?;
+                }
+            }
+            Commands::Config { action: None } => {
+                self.edit_settings(&mut Interface::default())?;
             }
-            Commands::Config => {
-                println!("Opening configuration");
+            Commands::Config {
+                action: Some(action),
+            } => {
+                for line in commands::run_config(action, &self.persistence, &self.overrides)? {
+                    println!("{}", line);
+                }
             }
         }
         Ok(())
@@ -1,2 +1,356 @@
-//! menus stub implementation
-pub struct Menus {}
+//! Menu screens shown when `centotype` runs without a subcommand
+//!
+//! The main menu picks a mode. Arcade opens a 10x10 grid of levels showing
+//! the best grade and stars from the player's progress, with locked levels
+//! left unplayable; Drills opens a list of categories. Stats and Settings
+//! leave the menus for their own screens.
+use crate::navigation::{GridCursor, NavKey};
+use crate::{DEFAULT_DRILL_MINUTES, DEFAULT_ENDURANCE_MINUTES};
+use centotype_core::types::*;
+use centotype_core::LevelManager;
+
+/// Levels per row of the level grid; one row per tier
+pub const GRID_COLUMNS: usize = 10;
+
+/// Entries of the main menu
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MainItem {
+    Arcade,
+    Drills,
+    Endurance,
+    Stats,
+    Settings,
+    Quit,
+}
+
+impl MainItem {
+    pub const ALL: [MainItem; 6] = [
+        MainItem::Arcade,
+        MainItem::Drills,
+        MainItem::Endurance,
+        MainItem::Stats,
+        MainItem::Settings,
+        MainItem::Quit,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            MainItem::Arcade => "Arcade",
+            MainItem::Drills => "Drills",
+            MainItem::Endurance => "Endurance",
+            MainItem::Stats => "Stats",
+            MainItem::Settings => "Settings",
+            MainItem::Quit => "Quit",
+        }
+    }
+
+    pub fn description(self) -> String {
+        match self {
+            MainItem::Arcade => "Work through the 100 levels".to_string(),
+            MainItem::Drills => "Practise one kind of character".to_string(),
+            MainItem::Endurance => format!("Type for {} minutes", DEFAULT_ENDURANCE_MINUTES),
+            MainItem::Stats => "Progress and session history".to_string(),
+            MainItem::Settings => "Layout, theme and keys".to_string(),
+            MainItem::Quit => "Leave Centotype".to_string(),
+        }
+    }
+}
+
+/// Name and description of a drill category in the picker
+pub fn drill_label(category: DrillCategory) -> (&'static str, &'static str) {
+    match category {
+        DrillCategory::LayoutKeys => ("Layout keys", "Your layout's keys, home row outwards"),
+        DrillCategory::Numbers => ("Numbers", "Digits, versions and numeric literals"),
+        DrillCategory::Punctuation => ("Punctuation", "Commas, quotes and brackets in prose"),
+        DrillCategory::Symbols => ("Symbols", "Programming symbols such as @ # $ & |"),
+        DrillCategory::CamelCase => ("camelCase", "Identifiers with capitalised words"),
+        DrillCategory::SnakeCase => ("snake_case", "Identifiers joined by underscores"),
+        DrillCategory::Operators => ("Operators", "Arithmetic, comparison and logic operators"),
+        DrillCategory::WeakKeys => ("Weak keys", "The keys missed most in the last session"),
+        DrillCategory::SlowWords => ("Slow words", "The words slow in several sessions"),
+    }
+}
+
+/// Screen the menus are on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MenuScreen {
+    Main,
+    Levels,
+    Drills,
+}
+
+/// What the player picked in the menus
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MenuSelection {
+    /// Start a session in this mode
+    Start(TrainingMode),
+    Stats,
+    Settings,
+    Quit,
+}
+
+/// One cell of the level grid
+#[derive(Debug, Clone)]
+pub struct LevelTile {
+    pub level: LevelId,
+    pub unlocked: bool,
+    /// Best verified result on the level, if it was played
+    pub best: Option<SessionResult>,
+}
+
+impl LevelTile {
+    /// Tiles for every level, in order, from the player's progress
+    pub fn all(progress: &UserProgress) -> Vec<LevelTile> {
+        let mut levels = LevelManager::new();
+        (LevelId::MIN..=LevelId::MAX)
+            .filter_map(|id| LevelId::new(id).ok())
+            .map(|level| LevelTile {
+                level,
+                unlocked: levels.is_unlocked(level, progress),
+                best: progress.best_results.get(&level).cloned(),
+            })
+            .collect()
+    }
+}
+
+/// State of the menus between key presses
+#[derive(Debug, Clone)]
+pub struct Menus {
+    screen: MenuScreen,
+    main: GridCursor,
+    levels: GridCursor,
+    drills: GridCursor,
+    tiles: Vec<LevelTile>,
+    /// Shown until the next key press, e.g. why a level cannot be played
+    notice: Option<String>,
+}
+
+impl Menus {
+    /// Menus on the main screen, with the level grid opening on the level
+    /// to play next
+    pub fn new(progress: &UserProgress) -> Self {
+        let tiles = LevelTile::all(progress);
+        let mut levels = GridCursor::new(tiles.len(), GRID_COLUMNS);
+        if let Ok(next) = LevelManager::new().get_next_level(progress) {
+            levels.set_index(usize::from(next.0 - LevelId::MIN));
+        }
+
+        Self {
+            screen: MenuScreen::Main,
+            main: GridCursor::list(MainItem::ALL.len()),
+            levels,
+            drills: GridCursor::list(DrillCategory::ALL.len()),
+            tiles,
+            notice: None,
+        }
+    }
+
+    pub fn screen(&self) -> MenuScreen {
+        self.screen
+    }
+
+    pub fn tiles(&self) -> &[LevelTile] {
+        &self.tiles
+    }
+
+    pub fn notice(&self) -> Option<&str> {
+        self.notice.as_deref()
+    }
+
+    /// Show `notice` until the next key press
+    pub fn set_notice(&mut self, notice: impl Into<String>) {
+        self.notice = Some(notice.into());
+    }
+
+    pub fn selected_item(&self) -> MainItem {
+        MainItem::ALL[self.main.index()]
+    }
+
+    pub fn selected_tile(&self) -> &LevelTile {
+        &self.tiles[self.levels.index()]
+    }
+
+    pub fn selected_drill(&self) -> DrillCategory {
+        DrillCategory::ALL[self.drills.index()]
+    }
+
+    /// Apply a key. Returns a selection once the player has picked
+    /// something that leaves the menus.
+    pub fn handle(&mut self, key: NavKey) -> Option<MenuSelection> {
+        self.notice = None;
+        if key == NavKey::Quit {
+            return Some(MenuSelection::Quit);
+        }
+
+        match self.screen {
+            MenuScreen::Main => match key {
+                NavKey::Select => self.select_item(),
+                // Esc quits from the top level
+                NavKey::Back => Some(MenuSelection::Quit),
+                _ => {
+                    self.main.apply(key);
+                    None
+                }
+            },
+            MenuScreen::Levels => match key {
+                NavKey::Select => self.select_level(),
+                NavKey::Back => {
+                    self.screen = MenuScreen::Main;
+                    None
+                }
+                _ => {
+                    self.levels.apply(key);
+                    None
+                }
+            },
+            MenuScreen::Drills => match key {
+                NavKey::Select => Some(MenuSelection::Start(TrainingMode::Drill {
+                    category: self.selected_drill(),
+                    duration_secs: DEFAULT_DRILL_MINUTES * 60,
+                })),
+                NavKey::Back => {
+                    self.screen = MenuScreen::Main;
+                    None
+                }
+                _ => {
+                    self.drills.apply(key);
+                    None
+                }
+            },
+        }
+    }
+
+    fn select_item(&mut self) -> Option<MenuSelection> {
+        match self.selected_item() {
+            MainItem::Arcade => {
+                self.screen = MenuScreen::Levels;
+                None
+            }
+            MainItem::Drills => {
+                self.screen = MenuScreen::Drills;
+                None
+            }
+            MainItem::Endurance => Some(MenuSelection::Start(TrainingMode::Endurance {
+                duration_secs: DEFAULT_ENDURANCE_MINUTES * 60,
+            })),
+            MainItem::Stats => Some(MenuSelection::Stats),
+            MainItem::Settings => Some(MenuSelection::Settings),
+            MainItem::Quit => Some(MenuSelection::Quit),
+        }
+    }
+
+    fn select_level(&mut self) -> Option<MenuSelection> {
+        let tile = self.selected_tile();
+        if tile.unlocked {
+            return Some(MenuSelection::Start(TrainingMode::Arcade {
+                level: tile.level,
+            }));
+        }
+
+        self.notice = Some(format!(
+            "Level {} is locked: finish level {} with grade {:?} or better",
+            tile.level.0,
+            tile.level.0 - 1,
+            Grade::min_for_progression()
+        ));
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(level: u8, grade: Grade) -> SessionResult {
+        SessionResult {
+            session_id: uuid::Uuid::nil(),
+            mode: TrainingMode::Arcade {
+                level: LevelId::new(level).unwrap(),
+            },
+            completed_at: chrono::DateTime::default(),
+            duration_seconds: 60.0,
+            metrics: FinalMetrics {
+                raw_wpm: 40.0,
+                effective_wpm: 38.0,
+                accuracy: 96.0,
+                consistency: 80.0,
+                longest_streak: 20,
+                errors: ErrorStats::default(),
+                latency_p99: std::time::Duration::from_millis(20),
+                keystroke_accuracy: 95.0,
+                corrections: CorrectionStats::default(),
+                slowest_words: Vec::new(),
+                layout_errors: LayoutErrorStats::default(),
+                finger_usage: FingerUsageStats::default(),
+                key_timing: KeyTimingStats::default(),
+            },
+            skill_index: 650.0,
+            grade,
+            stars: 2,
+            unverified: false,
+        }
+    }
+
+    #[test]
+    fn test_level_grid_from_progress() {
+        let mut progress = UserProgress::default();
+        progress
+            .best_results
+            .insert(LevelId::new(1).unwrap(), result(1, Grade::C));
+
+        let tiles = LevelTile::all(&progress);
+        assert_eq!(tiles.len(), 100);
+        assert_eq!(
+            tiles[0].best.as_ref().map(|best| best.grade),
+            Some(Grade::C)
+        );
+        assert!(tiles[0].unlocked);
+        assert!(!tiles[99].unlocked);
+        assert!(tiles
+            .iter()
+            .skip(2)
+            .all(|tile| !tile.unlocked && tile.best.is_none()));
+    }
+
+    #[test]
+    fn test_menu_flow() {
+        let mut menus = Menus::new(&UserProgress::default());
+        assert_eq!(menus.screen(), MenuScreen::Main);
+
+        // Arcade opens the grid on level 1, which is always unlocked
+        assert_eq!(menus.handle(NavKey::Select), None);
+        assert_eq!(menus.screen(), MenuScreen::Levels);
+        assert_eq!(menus.selected_tile().level.0, 1);
+        assert_eq!(
+            menus.handle(NavKey::Select),
+            Some(MenuSelection::Start(TrainingMode::Arcade {
+                level: LevelId::new(1).unwrap()
+            }))
+        );
+
+        // A locked level explains itself instead of starting
+        menus.handle(NavKey::Down);
+        assert_eq!(menus.selected_tile().level.0, 11);
+        assert_eq!(menus.handle(NavKey::Select), None);
+        assert!(menus.notice().unwrap().contains("Level 11 is locked"));
+        menus.handle(NavKey::Right);
+        assert_eq!(menus.notice(), None);
+
+        // Back to the main menu, down to the drills
+        menus.handle(NavKey::Back);
+        menus.handle(NavKey::Down);
+        assert_eq!(menus.selected_item(), MainItem::Drills);
+        menus.handle(NavKey::Select);
+        menus.handle(NavKey::Last);
+        assert_eq!(
+            menus.handle(NavKey::Select),
+            Some(MenuSelection::Start(TrainingMode::Drill {
+                category: DrillCategory::Operators,
+                duration_secs: DEFAULT_DRILL_MINUTES * 60,
+            }))
+        );
+
+        menus.handle(NavKey::Back);
+        assert_eq!(menus.handle(NavKey::Back), Some(MenuSelection::Quit));
+    }
+}
@@ -1,2 +1,155 @@
-//! navigation stub implementation
-pub struct Navigation {}
+//! Menu navigation with arrow keys or vim keys
+//!
+//! Key events are turned into `NavKey`s first, so every menu handles the
+//! same moves whichever keys produced them: arrows or `hjkl` to move, `g` and
+//! `G` for the first and last entry, Enter or space to select, Esc or
+//! Backspace to go back, and `q` or Ctrl+C to quit.
+use crossterm::event::{KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+
+/// A menu move, whichever key it came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NavKey {
+    Up,
+    Down,
+    Left,
+    Right,
+    First,
+    Last,
+    Select,
+    Back,
+    Quit,
+}
+
+impl NavKey {
+    /// The move a key press stands for; releases and other keys are `None`
+    pub fn from_event(event: &KeyEvent) -> Option<Self> {
+        if event.kind == KeyEventKind::Release {
+            return None;
+        }
+        if event.modifiers.contains(KeyModifiers::CONTROL) {
+            return match event.code {
+                KeyCode::Char('c') => Some(NavKey::Quit),
+                _ => None,
+            };
+        }
+
+        match event.code {
+            KeyCode::Up | KeyCode::Char('k') => Some(NavKey::Up),
+            KeyCode::Down | KeyCode::Char('j') => Some(NavKey::Down),
+            KeyCode::Left | KeyCode::Char('h') => Some(NavKey::Left),
+            KeyCode::Right | KeyCode::Char('l') => Some(NavKey::Right),
+            KeyCode::Home | KeyCode::Char('g') => Some(NavKey::First),
+            KeyCode::End | KeyCode::Char('G') => Some(NavKey::Last),
+            KeyCode::Enter | KeyCode::Char(' ') => Some(NavKey::Select),
+            KeyCode::Esc | KeyCode::Backspace => Some(NavKey::Back),
+            KeyCode::Char('q') => Some(NavKey::Quit),
+            _ => None,
+        }
+    }
+}
+
+/// Selection in a list or a grid filled row by row. A list is a grid with
+/// one column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GridCursor {
+    index: usize,
+    len: usize,
+    columns: usize,
+}
+
+impl GridCursor {
+    pub fn new(len: usize, columns: usize) -> Self {
+        Self {
+            index: 0,
+            len,
+            columns: columns.max(1),
+        }
+    }
+
+    /// Cursor over a single column
+    pub fn list(len: usize) -> Self {
+        Self::new(len, 1)
+    }
+
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    pub fn set_index(&mut self, index: usize) {
+        self.index = index.min(self.len.saturating_sub(1));
+    }
+
+    /// Row and column of the selection
+    pub fn position(&self) -> (usize, usize) {
+        (self.index / self.columns, self.index % self.columns)
+    }
+
+    /// Apply a move. Up and down keep to the column and stop at the edges;
+    /// left and right run on across rows. Returns whether the selection
+    /// changed.
+    pub fn apply(&mut self, key: NavKey) -> bool {
+        let last = self.len.saturating_sub(1);
+        let index = match key {
+            NavKey::Up => self.index.checked_sub(self.columns).unwrap_or(self.index),
+            NavKey::Down if self.index + self.columns <= last => self.index + self.columns,
+            NavKey::Left if self.columns > 1 => self.index.saturating_sub(1),
+            NavKey::Right if self.columns > 1 => (self.index + 1).min(last),
+            NavKey::First => 0,
+            NavKey::Last => last,
+            _ => self.index,
+        };
+        let changed = index != self.index;
+        self.index = index;
+        changed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_arrow_and_vim_keys() {
+        let key = |code| NavKey::from_event(&KeyEvent::new(code, KeyModifiers::NONE));
+        assert_eq!(key(KeyCode::Up), key(KeyCode::Char('k')));
+        assert_eq!(key(KeyCode::Down), key(KeyCode::Char('j')));
+        assert_eq!(key(KeyCode::Left), key(KeyCode::Char('h')));
+        assert_eq!(key(KeyCode::Right), Some(NavKey::Right));
+        assert_eq!(key(KeyCode::Char('l')), Some(NavKey::Right));
+        assert_eq!(key(KeyCode::Esc), Some(NavKey::Back));
+        assert_eq!(key(KeyCode::Char('q')), Some(NavKey::Quit));
+        assert_eq!(key(KeyCode::Char('x')), None);
+
+        let ctrl_c = KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL);
+        assert_eq!(NavKey::from_event(&ctrl_c), Some(NavKey::Quit));
+        let ctrl_j = KeyEvent::new(KeyCode::Char('j'), KeyModifiers::CONTROL);
+        assert_eq!(NavKey::from_event(&ctrl_j), None);
+    }
+
+    #[test]
+    fn test_grid_moves() {
+        let mut grid = GridCursor::new(100, 10);
+        assert!(!grid.apply(NavKey::Up));
+        assert!(grid.apply(NavKey::Down));
+        assert_eq!(grid.position(), (1, 0));
+
+        // Left and right wrap onto the neighbouring row
+        grid.apply(NavKey::Left);
+        assert_eq!(grid.index(), 9);
+        grid.apply(NavKey::Right);
+        assert_eq!(grid.position(), (1, 0));
+
+        grid.apply(NavKey::Last);
+        assert!(!grid.apply(NavKey::Down));
+        assert!(!grid.apply(NavKey::Right));
+        assert_eq!(grid.index(), 99);
+
+        // A list ignores sideways moves
+        let mut list = GridCursor::list(3);
+        assert!(!list.apply(NavKey::Right));
+        list.apply(NavKey::Down);
+        list.apply(NavKey::Down);
+        assert!(!list.apply(NavKey::Down));
+        assert_eq!(list.index(), 2);
+    }
+}
@@ -0,0 +1,371 @@
+//! The settings form behind `centotype config`
+//!
+//! Every setting is listed with its value. Fixed choices cycle with the
+//! arrow keys, toggles flip with Enter and the rest are typed in. Changes are
+//! checked as they are made and only written when the player saves.
+use crate::navigation::{GridCursor, NavKey};
+use centotype_core::settings::{SettingKind, UNSET};
+use centotype_core::types::*;
+use centotype_core::{LayoutRegistry, Setting};
+use centotype_engine::{ThemePalette, ThemeRegistry};
+use crossterm::event::{KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+
+/// Check that a user layout or theme named by `setting` is installed
+pub fn check_available(
+    setting: Setting,
+    config: &Config,
+    themes: &ThemeRegistry,
+    layouts: &LayoutRegistry,
+) -> Result<()> {
+    let (id, ids, kind): (_, Vec<&str>, _) = match setting {
+        Setting::CustomTheme => (
+            config.custom_theme.as_deref(),
+            themes.ids().collect(),
+            "theme",
+        ),
+        Setting::CustomLayout => (
+            config.custom_layout.as_deref(),
+            layouts.ids().collect(),
+            "layout",
+        ),
+        _ => return Ok(()),
+    };
+
+    match id {
+        Some(id) if !ids.contains(&id) => Err(CentotypeError::Config(format!(
+            "No {} named '{}'; installed: {}",
+            kind,
+            id,
+            ids.join(", ")
+        ))),
+        _ => Ok(()),
+    }
+}
+
+/// What the form asks of whoever runs it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FormAction {
+    /// Write the form's config; the form stays open
+    Save,
+    Close,
+}
+
+/// State of the settings form between key presses
+#[derive(Debug, Clone)]
+pub struct SettingsForm {
+    /// Config with the changes made so far
+    config: Config,
+    /// Values below the user file, which `r` goes back to
+    inherited: Config,
+    settings: Vec<Setting>,
+    cursor: GridCursor,
+    themes: ThemeRegistry,
+    layouts: LayoutRegistry,
+    /// Text typed for the selected setting, while one is being typed in
+    editing: Option<String>,
+    /// Shown until the next key press, e.g. why a value was refused
+    notice: Option<String>,
+    unsaved: bool,
+    /// Esc was pressed once with unsaved changes
+    confirm_discard: bool,
+}
+
+impl SettingsForm {
+    pub fn new(
+        config: Config,
+        inherited: Config,
+        themes: ThemeRegistry,
+        layouts: LayoutRegistry,
+    ) -> Self {
+        let settings = Setting::all();
+        Self {
+            config,
+            inherited,
+            cursor: GridCursor::list(settings.len()),
+            settings,
+            themes,
+            layouts,
+            editing: None,
+            notice: None,
+            unsaved: false,
+            confirm_discard: false,
+        }
+    }
+
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    pub fn settings(&self) -> &[Setting] {
+        &self.settings
+    }
+
+    pub fn selected(&self) -> Setting {
+        self.settings[self.cursor.index()]
+    }
+
+    pub fn editing(&self) -> Option<&str> {
+        self.editing.as_deref()
+    }
+
+    pub fn notice(&self) -> Option<&str> {
+        self.notice.as_deref()
+    }
+
+    pub fn has_unsaved_changes(&self) -> bool {
+        self.unsaved
+    }
+
+    /// Palette the form's theme settings pick, for the live preview
+    pub fn palette(&self) -> ThemePalette {
+        self.config
+            .custom_theme
+            .as_deref()
+            .and_then(|id| self.themes.get(id))
+            .cloned()
+            .unwrap_or_else(|| ThemePalette::for_theme(self.config.theme))
+    }
+
+    /// Record that the config was written
+    pub fn mark_saved(&mut self) {
+        self.unsaved = false;
+        self.notice = Some("Settings saved".to_string());
+    }
+
+    /// Explain why the config could not be written; the changes are kept
+    pub fn mark_save_failed(&mut self, error: &CentotypeError) {
+        self.notice = Some(format!("Could not save settings: {}", error));
+    }
+
+    /// Apply a key. `s` saves and `r` puts the selected setting back to the
+    /// value it inherits from the defaults and system file; while a value is
+    /// typed in, keys go to the text instead.
+    pub fn handle(&mut self, event: &KeyEvent) -> Option<FormAction> {
+        if event.kind == KeyEventKind::Release {
+            return None;
+        }
+        let confirm_discard = std::mem::take(&mut self.confirm_discard);
+        self.notice = None;
+
+        if event.modifiers.contains(KeyModifiers::CONTROL) {
+            return (event.code == KeyCode::Char('c')).then_some(FormAction::Close);
+        }
+        if self.editing.is_some() {
+            self.handle_edit(event);
+            return None;
+        }
+
+        match event.code {
+            KeyCode::Char('s') => return Some(FormAction::Save),
+            KeyCode::Char('r') => {
+                let setting = self.selected();
+                let mut config = self.config.clone();
+                if let Err(e) = config
+                    .set(setting, &self.inherited.get(setting))
+                    .and_then(|()| self.accept(setting, config))
+                {
+                    self.notice = Some(e.to_string());
+                }
+                return None;
+            }
+            _ => {}
+        }
+
+        match NavKey::from_event(event)? {
+            NavKey::Back | NavKey::Quit => {
+                if self.unsaved && !confirm_discard {
+                    self.confirm_discard = true;
+                    self.notice =
+                        Some("Unsaved changes: s to save, Esc again to discard".to_string());
+                    None
+                } else {
+                    Some(FormAction::Close)
+                }
+            }
+            NavKey::Left => {
+                self.step(-1);
+                None
+            }
+            NavKey::Right => {
+                self.step(1);
+                None
+            }
+            NavKey::Select => {
+                match self.selected().kind() {
+                    SettingKind::Choice(_) | SettingKind::Toggle => self.step(1),
+                    SettingKind::Text => {
+                        let value = self.config.get(self.selected());
+                        self.editing = Some(if value == UNSET { String::new() } else { value });
+                    }
+                }
+                None
+            }
+            key => {
+                self.cursor.apply(key);
+                None
+            }
+        }
+    }
+
+    fn handle_edit(&mut self, event: &KeyEvent) {
+        let Some(text) = self.editing.as_mut() else {
+            return;
+        };
+        match event.code {
+            KeyCode::Char(ch) => text.push(ch),
+            KeyCode::Backspace => {
+                text.pop();
+            }
+            KeyCode::Esc => self.editing = None,
+            KeyCode::Enter => {
+                let value = self.editing.take().unwrap_or_default();
+                let setting = self.selected();
+                let mut config = self.config.clone();
+                let accepted = config
+                    .set(setting, &value)
+                    .and_then(|()| self.accept(setting, config));
+                if let Err(e) = accepted {
+                    // Keep the text so it can be corrected
+                    self.notice = Some(e.to_string());
+                    self.editing = Some(value);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Move a choice `delta` places along its list, or flip a toggle
+    fn step(&mut self, delta: isize) {
+        let setting = self.selected();
+        let value = match setting.kind() {
+            SettingKind::Choice(choices) => {
+                let current = self.config.get(setting);
+                let index = choices.iter().position(|c| *c == current).unwrap_or(0);
+                let len = choices.len() as isize;
+                choices[(index as isize + delta).rem_euclid(len) as usize].to_string()
+            }
+            SettingKind::Toggle => (self.config.get(setting) != "true").to_string(),
+            SettingKind::Text => return,
+        };
+
+        let mut config = self.config.clone();
+        if let Err(e) = config
+            .set(setting, &value)
+            .and_then(|()| self.accept(setting, config))
+        {
+            self.notice = Some(e.to_string());
+        }
+    }
+
+    /// Take `config` if the layouts and themes it names are installed
+    fn accept(&mut self, setting: Setting, config: Config) -> Result<()> {
+        check_available(setting, &config, &self.themes, &self.layouts)?;
+        self.config = config;
+        self.unsaved = true;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    /// Form over the defaults, with a system file that picks the mono theme
+    fn form() -> SettingsForm {
+        let inherited = Config {
+            theme: Theme::Mono,
+            ..Config::default()
+        };
+        SettingsForm::new(
+            Config::default(),
+            inherited,
+            ThemeRegistry::new(),
+            LayoutRegistry::new(),
+        )
+    }
+
+    #[test]
+    fn test_cycle_and_toggle() {
+        let mut form = form();
+        assert_eq!(form.selected(), Setting::Layout);
+
+        form.handle(&key(KeyCode::Right));
+        assert_eq!(form.config().layout, KeyboardLayout::Qwertz);
+        form.handle(&key(KeyCode::Left));
+        form.handle(&key(KeyCode::Left));
+        assert_eq!(form.config().layout, KeyboardLayout::Workman);
+
+        // Theme changes show up in the preview palette straight away
+        while form.selected() != Setting::Theme {
+            form.handle(&key(KeyCode::Down));
+        }
+        form.handle(&key(KeyCode::Enter));
+        assert_eq!(form.config().theme, Theme::HighContrast);
+        assert_eq!(form.palette(), ThemePalette::for_theme(Theme::HighContrast));
+
+        // Reset goes back to the system file's theme, not the built-in one
+        form.handle(&key(KeyCode::Char('r')));
+        assert_eq!(form.config().theme, Theme::Mono);
+        assert!(form.has_unsaved_changes());
+    }
+
+    #[test]
+    fn test_text_values_are_checked() {
+        let mut form = form();
+        form.handle(&key(KeyCode::Down));
+        assert_eq!(form.selected(), Setting::CustomLayout);
+
+        // An unknown layout is refused with the installed ones listed
+        form.handle(&key(KeyCode::Enter));
+        assert_eq!(form.editing(), Some(""));
+        for ch in "bepo".chars() {
+            form.handle(&key(KeyCode::Char(ch)));
+        }
+        form.handle(&key(KeyCode::Enter));
+        assert!(form.notice().unwrap().contains("No layout named 'bepo'"));
+        assert_eq!(form.config().custom_layout, None);
+
+        // The text stays for correcting
+        assert_eq!(form.editing(), Some("bepo"));
+        for _ in 0..4 {
+            form.handle(&key(KeyCode::Backspace));
+        }
+        for ch in "dvorak".chars() {
+            form.handle(&key(KeyCode::Char(ch)));
+        }
+        form.handle(&key(KeyCode::Enter));
+        assert_eq!(form.editing(), None);
+        assert_eq!(form.config().custom_layout.as_deref(), Some("dvorak"));
+    }
+
+    #[test]
+    fn test_save_and_discard() {
+        let mut form = form();
+        form.handle(&key(KeyCode::Right));
+
+        // The first Esc warns about the change, the second discards it
+        assert!(form.handle(&key(KeyCode::Esc)).is_none());
+        assert!(form.notice().unwrap().contains("Unsaved changes"));
+        assert!(matches!(
+            form.handle(&key(KeyCode::Esc)),
+            Some(FormAction::Close)
+        ));
+
+        let mut form = self::form();
+        form.handle(&key(KeyCode::Right));
+        assert!(matches!(
+            form.handle(&key(KeyCode::Char('s'))),
+            Some(FormAction::Save)
+        ));
+        assert_eq!(form.config().layout, KeyboardLayout::Qwertz);
+        form.mark_saved();
+        assert!(matches!(
+            form.handle(&key(KeyCode::Esc)),
+            Some(FormAction::Close)
+        ));
+    }
+}
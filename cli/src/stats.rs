@@ -0,0 +1,238 @@
+//! The data behind `centotype stats`
+//!
+//! Level completion comes from the profile's best results, everything else
+//! from the session history. The dashboard and `--json` show the same report.
+use crate::menus::drill_label;
+use centotype_analytics::trends::{DayActivity, GradeCounts, PersonalRecords, Trends};
+use centotype_core::level::Level;
+use centotype_core::types::*;
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::Serialize;
+
+/// Weeks of activity in the heatmap
+pub const HEATMAP_WEEKS: u32 = 12;
+/// Sessions listed under recent sessions
+pub const RECENT_SESSIONS: usize = 10;
+/// Sessions averaged on each side of the WPM trend
+pub const TREND_WINDOW: usize = 10;
+
+/// Completion and speed for one tier
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TierSummary {
+    pub tier: Tier,
+    pub unlocked: bool,
+    pub completed_levels: u8,
+    pub total_levels: u8,
+    /// Average grade of the completed levels
+    pub average_grade: Option<Grade>,
+    pub sessions: u32,
+    pub best_wpm: Option<f64>,
+    pub average_wpm: Option<f64>,
+}
+
+/// One line of the recent sessions list
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RecentSession {
+    pub completed_at: DateTime<Utc>,
+    pub mode: TrainingMode,
+    pub label: String,
+    pub duration_seconds: f64,
+    pub effective_wpm: f64,
+    pub accuracy: f64,
+    pub grade: Grade,
+    pub unverified: bool,
+}
+
+/// Everything the stats dashboard shows
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StatsReport {
+    pub total_sessions: u32,
+    pub total_time_seconds: f64,
+    pub overall_skill_index: f64,
+    pub levels_completed: u32,
+    /// One entry per day of the heatmap, oldest first
+    pub activity: Vec<DayActivity>,
+    pub tiers: Vec<TierSummary>,
+    pub grades: GradeCounts,
+    pub records: PersonalRecords,
+    /// Average WPM of the last `TREND_WINDOW` sessions against the ones
+    /// before them
+    pub wpm_change: Option<f64>,
+    /// Newest first
+    pub recent: Vec<RecentSession>,
+}
+
+impl StatsReport {
+    /// Build the report from the profile and the session history, oldest
+    /// session first, as of `today`
+    pub fn new(progress: &UserProgress, history: &[SessionResult], today: NaiveDate) -> Self {
+        let trends = Trends::new();
+        let tier_progress = Level::new().get_tier_progress(progress);
+        let speeds = trends.tier_speeds(history);
+
+        let tiers: Vec<TierSummary> = (1..=10)
+            .filter_map(|number| {
+                let tier = Tier(number);
+                let stats = tier_progress.tier_stats.get(&tier)?;
+                let speed = speeds.iter().find(|speed| speed.tier == tier);
+                Some(TierSummary {
+                    tier,
+                    unlocked: stats.is_unlocked,
+                    completed_levels: stats.completed_levels,
+                    total_levels: stats.total_levels,
+                    average_grade: stats.average_grade,
+                    sessions: speed.map_or(0, |speed| speed.sessions),
+                    best_wpm: speed.map(|speed| speed.best_wpm),
+                    average_wpm: speed.map(|speed| speed.average_wpm),
+                })
+            })
+            .collect();
+
+        Self {
+            total_sessions: progress.total_sessions,
+            total_time_seconds: progress.total_time_seconds,
+            overall_skill_index: progress.overall_skill_index,
+            levels_completed: tiers.iter().map(|t| t.completed_levels as u32).sum(),
+            activity: trends.daily_activity(history, today, HEATMAP_WEEKS * 7),
+            tiers,
+            grades: trends.grade_counts(history),
+            records: trends.personal_records(history),
+            wpm_change: trends.wpm_change(history, TREND_WINDOW),
+            recent: history
+                .iter()
+                .rev()
+                .take(RECENT_SESSIONS)
+                .map(|result| RecentSession {
+                    completed_at: result.completed_at,
+                    mode: result.mode,
+                    label: mode_label(result.mode),
+                    duration_seconds: result.duration_seconds,
+                    effective_wpm: result.metrics.effective_wpm,
+                    accuracy: result.metrics.accuracy,
+                    grade: result.grade,
+                    unverified: result.unverified,
+                })
+                .collect(),
+        }
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+}
+
+/// Short name of a mode, e.g. `Level 12` or `Drill · Numbers`
+pub fn mode_label(mode: TrainingMode) -> String {
+    match mode {
+        TrainingMode::Arcade { level } => format!("Level {}", level.0),
+        TrainingMode::Drill { category, .. } => format!("Drill · {}", drill_label(category).0),
+        TrainingMode::Endurance { duration_secs } => {
+            format!("Endurance {} min", duration_secs / 60)
+        }
+    }
+}
+
+/// Whole hours and minutes, e.g. `3h 05m`, or minutes alone under an hour
+pub fn format_duration(seconds: f64) -> String {
+    let minutes = (seconds / 60.0).round() as u64;
+    if minutes < 60 {
+        format!("{}m", minutes)
+    } else {
+        format!("{}h {:02}m", minutes / 60, minutes % 60)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn session(level: u8, wpm: f64, grade: Grade, day: u32) -> SessionResult {
+        SessionResult {
+            session_id: uuid::Uuid::new_v4(),
+            mode: TrainingMode::Arcade {
+                level: LevelId(level),
+            },
+            completed_at: Utc.with_ymd_and_hms(2026, 3, day, 9, 30, 0).unwrap(),
+            duration_seconds: 90.0,
+            metrics: FinalMetrics {
+                raw_wpm: wpm,
+                effective_wpm: wpm,
+                accuracy: 96.5,
+                consistency: 88.0,
+                longest_streak: 50,
+                errors: ErrorStats::default(),
+                latency_p99: std::time::Duration::from_millis(120),
+                keystroke_accuracy: 95.0,
+                corrections: CorrectionStats::default(),
+                slowest_words: Vec::new(),
+                layout_errors: LayoutErrorStats::default(),
+                finger_usage: FingerUsageStats::default(),
+                key_timing: KeyTimingStats::default(),
+            },
+            skill_index: wpm * 10.0,
+            grade,
+            stars: 2,
+            unverified: false,
+        }
+    }
+
+    #[test]
+    fn test_report_from_history() {
+        let history = vec![
+            session(1, 40.0, Grade::B, 2),
+            session(2, 48.0, Grade::A, 2),
+            session(2, 30.0, Grade::D, 4),
+        ];
+        let mut progress = UserProgress::default();
+        for result in &history {
+            progress.update_with_result(result.clone());
+        }
+        let today = NaiveDate::from_ymd_opt(2026, 3, 5).unwrap();
+        let report = StatsReport::new(&progress, &history, today);
+
+        assert_eq!(report.total_sessions, 3);
+        assert_eq!(format_duration(report.total_time_seconds), "5m");
+        // Completion is the level manager's view of the best results
+        let tier_progress = Level::new().get_tier_progress(&progress);
+        let tier_1 = &tier_progress.tier_stats[&Tier(1)];
+        assert_eq!(report.levels_completed, tier_1.completed_levels as u32);
+        assert_eq!(report.tiers.len(), 10);
+        assert_eq!(report.tiers[0].completed_levels, tier_1.completed_levels);
+        assert_eq!(report.tiers[0].average_grade, tier_1.average_grade);
+        assert_eq!(report.tiers[0].best_wpm, Some(48.0));
+        assert_eq!(report.tiers[0].sessions, 3);
+        assert!(!report.tiers[1].unlocked);
+        assert_eq!(report.tiers[1].best_wpm, None);
+
+        assert_eq!(report.activity.len(), 84);
+        assert_eq!(report.activity[80].sessions, 2);
+        assert_eq!(report.grades.get(Grade::D), 1);
+        assert_eq!(report.records.effective_wpm.as_ref().unwrap().value, 48.0);
+        assert_eq!(report.wpm_change, None);
+        assert_eq!(report.recent[0].label, "Level 2");
+        assert_eq!(report.recent[0].grade, Grade::D);
+
+        let json: serde_json::Value = serde_json::from_str(&report.to_json().unwrap()).unwrap();
+        assert_eq!(json["total_sessions"], 3);
+        assert_eq!(json["tiers"][0]["tier"], 1);
+        assert_eq!(json["activity"][80]["date"], "2026-03-02");
+        assert_eq!(json["recent"][0]["grade"], "D");
+    }
+
+    #[test]
+    fn test_labels() {
+        assert_eq!(
+            mode_label(TrainingMode::Drill {
+                category: DrillCategory::SnakeCase,
+                duration_secs: 300
+            }),
+            "Drill · snake_case"
+        );
+        assert_eq!(
+            mode_label(TrainingMode::Endurance { duration_secs: 900 }),
+            "Endurance 15 min"
+        );
+        assert_eq!(format_duration(11_100.0), "3h 05m");
+    }
+}
@@ -0,0 +1,448 @@
+//! Home-row-first practice text for a keyboard layout
+//!
+//! A `LayoutCurriculum` unlocks keys stage by stage, following the layout's
+//! `CurriculumStage`s, and generates deterministic drill text that only uses
+//! keys unlocked so far while favouring the keys new to the current stage.
+//! A `LetterProgression` spreads the layout's letters over the arcade levels
+//! of the first two tiers in the same way, `weak_key_text` drills the keys
+//! a session went wrong on and `slow_word_text` the words that keep being
+//! slow.
+
+use centotype_core::layout::{CurriculumStage, LayoutDefinition};
+use centotype_core::KeyboardGeometry;
+use centotype_core::types::*;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use std::collections::BTreeSet;
+
+/// Common English words; each stage uses those made only of unlocked keys
+const DRILL_WORDS: &[&str] = &[
+    "a", "able", "about", "add", "after", "again", "age", "ago", "air", "all", "also", "and",
+    "animal", "answer", "any", "are", "area", "arm", "art", "as", "ask", "at", "away", "back",
+    "bad", "ball", "bank", "base", "be", "bed", "been", "before", "began", "best", "big", "bird",
+    "black", "blue", "boat", "body", "book", "both", "box", "boy", "bring", "build", "but", "by",
+    "call", "came", "can", "car", "care", "carry", "case", "cat", "cause", "change", "child",
+    "city", "class", "clear", "close", "cold", "come", "could", "count", "cut", "dad", "dark",
+    "day", "dead", "deal", "dear", "deep", "desk", "did", "do", "does", "dog", "done", "door",
+    "down", "draw", "dream", "dress", "drink", "drive", "each", "ear", "early", "earth", "east",
+    "easy", "eat", "egg", "else", "end", "enough", "even", "ever", "every", "eye", "face",
+    "fact", "fade", "fail", "fall", "far", "fast", "father", "feel", "feet", "few", "field",
+    "fill", "find", "fine", "fire", "first", "fish", "five", "flag", "flask", "floor", "fly",
+    "follow", "food", "foot", "for", "form", "found", "four", "free", "friend", "from", "full",
+    "game", "gas", "gave", "get", "girl", "give", "glad", "glass", "go", "gold", "good", "got",
+    "great", "green", "ground", "group", "grow", "had", "half", "hall", "hand", "hard", "has",
+    "hat", "have", "he", "head", "hear", "heat", "held", "help", "her", "here", "high", "hill",
+    "him", "his", "hold", "home", "horse", "hot", "hour", "house", "how", "idea", "if", "in",
+    "into", "is", "island", "it", "its", "job", "join", "joke", "just", "keep", "key", "kid",
+    "kind", "king", "knew", "know", "lad", "lake", "land", "large", "last", "late", "lead",
+    "learn", "least", "leave", "left", "less", "let", "letter", "life", "light", "like", "line",
+    "list", "little", "live", "long", "look", "lost", "lot", "love", "low", "made", "make",
+    "man", "many", "map", "mark", "may", "me", "mean", "men", "might", "mind", "miss", "more",
+    "most", "mother", "move", "much", "must", "my", "name", "near", "need", "never", "new",
+    "next", "night", "no", "north", "not", "note", "now", "number", "of", "off", "often", "oil",
+    "old", "on", "once", "one", "only", "open", "or", "order", "other", "our", "out", "over",
+    "own", "page", "paper", "part", "pass", "past", "people", "pick", "place", "plain", "plan",
+    "play", "point", "poor", "press", "pull", "put", "quick", "quiet", "quite", "rain", "ran",
+    "reach", "read", "ready", "real", "red", "rest", "rich", "ride", "right", "ring", "rise",
+    "road", "rock", "room", "round", "rule", "run", "sad", "safe", "said", "sail", "salad",
+    "same", "sat", "save", "saw", "say", "sea", "seat", "see", "seed", "seem", "self", "sell",
+    "send", "set", "she", "ship", "shoe", "short", "should", "show", "side", "sign", "simple",
+    "since", "sit", "six", "size", "sky", "sleep", "slow", "small", "snow", "so", "some",
+    "song", "soon", "sound", "south", "space", "speak", "stand", "star", "start", "state",
+    "stay", "step", "still", "stone", "stop", "story", "street", "strong", "such", "sun",
+    "sure", "table", "tail", "take", "talk", "tall", "task", "tea", "teach", "tell", "ten",
+    "test", "than", "that", "the", "their", "them", "then", "there", "these", "they", "thing",
+    "think", "this", "those", "three", "tie", "time", "tire", "to", "told", "too", "took",
+    "top", "town", "tree", "true", "try", "turn", "two", "under", "until", "up", "us", "use",
+    "very", "voice", "wait", "walk", "wall", "want", "war", "warm", "was", "watch", "water",
+    "way", "we", "week", "well", "went", "were", "west", "what", "when", "where", "which",
+    "while", "white", "who", "whole", "why", "will", "wind", "with", "without", "woman",
+    "wood", "word", "work", "world", "would", "write", "year", "yes", "yet", "you", "young",
+    "your", "zero", "zone",
+];
+
+/// Below this many usable words, pseudo-words fill in
+const MIN_USABLE_WORDS: usize = 8;
+
+/// Share of tokens that drill the current stage's new keys directly
+const NEW_KEY_DRILL_RATIO: f64 = 0.3;
+
+/// Share of words picked for containing a new key, when any do
+const NEW_KEY_WORD_RATIO: f64 = 0.6;
+
+/// Arcade levels that introduce letters before numbers and symbols
+pub const LETTER_LEVELS: u8 = 20;
+
+/// Characters of practice text in a weak key drill
+pub const WEAK_KEY_DRILL_LENGTH: usize = 300;
+
+/// Characters of practice text in a slow word drill
+pub const SLOW_WORD_DRILL_LENGTH: usize = 300;
+
+/// Characters of practice text in a layout key drill
+pub const LAYOUT_DRILL_LENGTH: usize = 300;
+
+/// Staged practice text for one keyboard layout
+#[derive(Debug, Clone)]
+pub struct LayoutCurriculum {
+    layout_id: String,
+    stages: Vec<CurriculumStage>,
+}
+
+impl LayoutCurriculum {
+    pub fn new(layout: &LayoutDefinition) -> Self {
+        Self {
+            layout_id: layout.id.clone(),
+            stages: layout.curriculum_stages(),
+        }
+    }
+
+    pub fn layout_id(&self) -> &str {
+        &self.layout_id
+    }
+
+    pub fn stages(&self) -> &[CurriculumStage] {
+        &self.stages
+    }
+
+    /// Every key introduced up to and including `stage`
+    pub fn unlocked_keys(&self, stage: usize) -> BTreeSet<char> {
+        self.stages
+            .iter()
+            .take(stage + 1)
+            .flat_map(|stage| stage.keys.iter().copied())
+            .collect()
+    }
+
+    /// Stage that keeps pace with arcade `level`: the letter levels are
+    /// spread over the stages and every later level has them all
+    pub fn stage_for_level(&self, level: LevelId) -> usize {
+        let last = self.stages.len().saturating_sub(1);
+        let level = level.0.clamp(1, LETTER_LEVELS) as usize;
+        last * (level - 1) / (LETTER_LEVELS - 1) as usize
+    }
+
+    /// Generate `length` characters of space-separated practice text for
+    /// `stage`. The same stage and seed always give the same text.
+    pub fn generate(&self, stage: usize, seed: u64, length: usize) -> Result<String> {
+        let current = self.stages.get(stage).ok_or_else(|| {
+            CentotypeError::Content(format!(
+                "Layout '{}' has {} curriculum stages, requested stage {}",
+                self.layout_id,
+                self.stages.len(),
+                stage
+            ))
+        })?;
+
+        Ok(drill_text(
+            &self.unlocked_keys(stage),
+            &current.keys,
+            DRILL_WORDS,
+            seed,
+            length,
+        ))
+    }
+}
+
+/// Letters unlocked at each of the first `LETTER_LEVELS` arcade levels:
+/// level 1 starts on the home-row anchors and level 20 covers a-z
+#[derive(Debug, Clone)]
+pub struct LetterProgression {
+    layout_id: String,
+    order: Vec<char>,
+    first_level_letters: usize,
+}
+
+impl LetterProgression {
+    pub fn new(geometry: &KeyboardGeometry) -> Self {
+        let order = geometry.letter_order();
+        let first_level_letters = geometry
+            .home_row_anchors()
+            .iter()
+            .filter(|ch| order.contains(ch))
+            .count()
+            .max(1);
+
+        Self {
+            layout_id: geometry.id().to_string(),
+            order,
+            first_level_letters,
+        }
+    }
+
+    pub fn layout_id(&self) -> &str {
+        &self.layout_id
+    }
+
+    /// Every letter unlocked at `level`; `None` past the letter levels
+    pub fn letters(&self, level: LevelId) -> Option<&[char]> {
+        self.letter_count(level).map(|count| &self.order[..count])
+    }
+
+    /// Letters introduced at `level` itself
+    pub fn new_letters(&self, level: LevelId) -> Option<&[char]> {
+        let count = self.letter_count(level)?;
+        let previous = match LevelId::new(level.0 - 1) {
+            Ok(previous) => self.letter_count(previous).unwrap_or(0),
+            Err(_) => 0,
+        };
+        Some(&self.order[previous..count])
+    }
+
+    /// Generate `length` characters of practice text for a letter level
+    pub fn generate(&self, level: LevelId, seed: u64, length: usize) -> Result<String> {
+        self.generate_with_words(level, DRILL_WORDS, seed, length)
+    }
+
+    /// Like `generate`, picking words from `words` instead of the English
+    /// drill words
+    pub fn generate_with_words(
+        &self,
+        level: LevelId,
+        words: &[&str],
+        seed: u64,
+        length: usize,
+    ) -> Result<String> {
+        let (letters, new_letters) = self
+            .letters(level)
+            .zip(self.new_letters(level))
+            .ok_or_else(|| {
+                CentotypeError::Content(format!(
+                    "Level {} is past the {} letter levels",
+                    level.0, LETTER_LEVELS
+                ))
+            })?;
+
+        let mut unlocked: BTreeSet<char> = letters.iter().copied().collect();
+        unlocked.insert(' ');
+        Ok(drill_text(&unlocked, new_letters, words, seed, length))
+    }
+
+    fn letter_count(&self, level: LevelId) -> Option<usize> {
+        if level.0 > LETTER_LEVELS {
+            return None;
+        }
+
+        let first = self.first_level_letters.min(self.order.len());
+        let remaining = self.order.len() - first;
+        let steps = (LETTER_LEVELS - 1) as usize;
+        Some(first + (remaining * (level.0 as usize - 1)).div_ceil(steps))
+    }
+}
+
+/// Drill text for `keys`: the keys on their own and words that use them,
+/// among words made of any letters
+pub fn weak_key_text(keys: &[char], seed: u64, length: usize) -> String {
+    let mut unlocked: BTreeSet<char> = ('a'..='z').chain(keys.iter().copied()).collect();
+    unlocked.insert(' ');
+    drill_text(&unlocked, keys, DRILL_WORDS, seed, length)
+}
+
+/// Drill text repeating `words`, shuffled anew each round, of at most
+/// `length` characters without cutting a word short
+pub fn slow_word_text(words: &[&str], seed: u64, length: usize) -> String {
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+    let mut round: Vec<&str> = words.iter().copied().filter(|word| !word.is_empty()).collect();
+    let mut text = String::with_capacity(length);
+    let mut chars = 0;
+
+    'rounds: while !round.is_empty() {
+        round.shuffle(&mut rng);
+        for word in &round {
+            let needed = word.chars().count() + usize::from(chars > 0);
+            if chars + needed > length {
+                break 'rounds;
+            }
+            if chars > 0 {
+                text.push(' ');
+            }
+            text.push_str(word);
+            chars += needed;
+        }
+    }
+    text
+}
+
+/// Space-separated drill text of at most `length` characters using only
+/// `unlocked` keys, weighted towards `new_keys`, with the `vocabulary`
+/// words those keys can type
+fn drill_text(
+    unlocked: &BTreeSet<char>,
+    new_keys: &[char],
+    vocabulary: &[&str],
+    seed: u64,
+    length: usize,
+) -> String {
+    let letters: Vec<char> = unlocked
+        .iter()
+        .copied()
+        .filter(|ch| ch.is_alphabetic())
+        .collect();
+    let words: Vec<&str> = vocabulary
+        .iter()
+        .copied()
+        .filter(|word| word.chars().all(|ch| unlocked.contains(&ch)))
+        .collect();
+    let new_key_words: Vec<&str> = words
+        .iter()
+        .copied()
+        .filter(|word| word.chars().any(|ch| new_keys.contains(&ch)))
+        .collect();
+    let drill_keys: Vec<char> = new_keys
+        .iter()
+        .copied()
+        .filter(|ch| !ch.is_whitespace())
+        .collect();
+
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+    let mut text = String::with_capacity(length + 16);
+
+    while text.chars().count() < length {
+        if !text.is_empty() {
+            text.push(' ');
+        }
+
+        if !drill_keys.is_empty() && (letters.is_empty() || rng.gen_bool(NEW_KEY_DRILL_RATIO)) {
+            let size = rng.gen_range(2..=4);
+            text.extend((0..size).map(|_| *drill_keys.choose(&mut rng).unwrap()));
+        } else if words.len() >= MIN_USABLE_WORDS {
+            let pool = if !new_key_words.is_empty() && rng.gen_bool(NEW_KEY_WORD_RATIO) {
+                &new_key_words
+            } else {
+                &words
+            };
+            text.push_str(pool.choose(&mut rng).unwrap());
+        } else {
+            let size = rng.gen_range(3..=5);
+            text.extend((0..size).map(|_| *letters.choose(&mut rng).unwrap()));
+        }
+    }
+
+    let text: String = text.chars().take(length).collect();
+    text.trim_end().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use centotype_core::LayoutRegistry;
+
+    #[test]
+    fn test_text_uses_only_unlocked_keys() {
+        let registry = LayoutRegistry::new();
+        for layout in KeyboardLayout::ALL {
+            let curriculum = LayoutCurriculum::new(registry.get(layout.id()).unwrap());
+
+            for stage in 0..curriculum.stages().len() {
+                let unlocked = curriculum.unlocked_keys(stage);
+                let text = curriculum.generate(stage, 42, 300).unwrap();
+
+                assert!(!text.is_empty());
+                assert!(text.chars().count() <= 300);
+                for ch in text.chars().filter(|&ch| ch != ' ') {
+                    assert!(
+                        unlocked.contains(&ch),
+                        "{:?} stage {} used locked key '{}'",
+                        layout,
+                        stage,
+                        ch
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_home_row_stage_practises_new_keys() {
+        let curriculum = LayoutCurriculum::new(LayoutRegistry::new().get("dvorak").unwrap());
+        let anchors = curriculum.generate(0, 7, 200).unwrap();
+        assert!(anchors.chars().all(|ch| "aoeuhtns ".contains(ch)));
+
+        // The stretch keys of the second stage show up in its text
+        let home_row = curriculum.generate(1, 7, 300).unwrap();
+        assert!(home_row.contains('i') && home_row.contains('d'));
+    }
+
+    #[test]
+    fn test_generation_is_deterministic() {
+        let curriculum = LayoutCurriculum::new(LayoutRegistry::new().get("colemak-dh").unwrap());
+
+        assert_eq!(
+            curriculum.generate(2, 99, 250).unwrap(),
+            curriculum.generate(2, 99, 250).unwrap()
+        );
+        assert_ne!(
+            curriculum.generate(2, 99, 250).unwrap(),
+            curriculum.generate(2, 100, 250).unwrap()
+        );
+        assert!(curriculum.generate(curriculum.stages().len(), 1, 100).is_err());
+    }
+
+    #[test]
+    fn test_stage_keeps_pace_with_arcade_level() {
+        let curriculum = LayoutCurriculum::new(LayoutRegistry::new().get("workman").unwrap());
+        let last = curriculum.stages().len() - 1;
+
+        assert_eq!(curriculum.stage_for_level(LevelId::new(1).unwrap()), 0);
+        assert_eq!(
+            curriculum.stage_for_level(LevelId::new(LETTER_LEVELS).unwrap()),
+            last
+        );
+        assert_eq!(curriculum.stage_for_level(LevelId::new(60).unwrap()), last);
+
+        let stages: Vec<usize> = (1..=LETTER_LEVELS)
+            .map(|level| curriculum.stage_for_level(LevelId::new(level).unwrap()))
+            .collect();
+        assert!(stages.windows(2).all(|pair| pair[0] <= pair[1]));
+    }
+
+    #[test]
+    fn test_letter_levels_follow_layout() {
+        let level = |n| LevelId::new(n).unwrap();
+        let qwerty = LetterProgression::new(KeyboardGeometry::for_layout(KeyboardLayout::Qwerty));
+        let azerty = LetterProgression::new(KeyboardGeometry::for_layout(KeyboardLayout::Azerty));
+
+        assert_eq!(qwerty.letters(level(1)).unwrap(), &['f', 'j', 'd', 'k', 's', 'l', 'a']);
+        assert_eq!(azerty.letters(level(1)).unwrap(), &['f', 'j', 'd', 'k', 's', 'l', 'q', 'm']);
+        assert_eq!(qwerty.letters(level(20)).unwrap().len(), 26);
+        assert!(qwerty.letters(level(21)).is_none());
+
+        // Each level adds at least one letter and never drops one
+        for n in 2..=LETTER_LEVELS {
+            let previous = qwerty.letters(level(n - 1)).unwrap();
+            let current = qwerty.letters(level(n)).unwrap();
+            assert!(current.starts_with(previous));
+            assert!(!qwerty.new_letters(level(n)).unwrap().is_empty());
+        }
+
+        let text = azerty.generate(level(1), 5, 300).unwrap();
+        assert!(text.chars().all(|ch| "fjdkslqm ".contains(ch)));
+        assert!(azerty.generate(level(21), 5, 300).is_err());
+    }
+
+    #[test]
+    fn test_weak_key_text_drills_the_keys() {
+        let text = weak_key_text(&['q', ';'], 3, WEAK_KEY_DRILL_LENGTH);
+
+        assert!(text.chars().count() <= WEAK_KEY_DRILL_LENGTH);
+        assert!(text.contains(';'));
+        assert!(text.split(' ').filter(|word| word.contains('q')).count() >= 5);
+        assert!(text.chars().all(|ch| ch.is_ascii_lowercase() || " ;".contains(ch)));
+    }
+
+    #[test]
+    fn test_slow_word_text_repeats_the_words() {
+        let words = ["through", "rhythm", "façade"];
+        let text = slow_word_text(&words, 9, SLOW_WORD_DRILL_LENGTH);
+
+        assert!(text.chars().count() <= SLOW_WORD_DRILL_LENGTH);
+        assert!(text.chars().count() > SLOW_WORD_DRILL_LENGTH - "through ".len());
+        assert!(text.split(' ').all(|word| words.contains(&word)));
+        for word in words {
+            assert!(text.split(' ').filter(|&typed| typed == word).count() >= 10);
+        }
+        assert_eq!(text, slow_word_text(&words, 9, SLOW_WORD_DRILL_LENGTH));
+        assert!(slow_word_text(&[], 9, SLOW_WORD_DRILL_LENGTH).is_empty());
+    }
+}
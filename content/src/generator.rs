@@ -3,8 +3,10 @@
 //! This module implements the ContentGenerator trait that creates deterministic,
 //! progressively difficult content for each level with proper security validation.
 
+use crate::curriculum::LetterProgression;
 use crate::validation::ContentValidator;
 use centotype_core::types::*;
+use centotype_core::KeyboardGeometry;
 use rand::{Rng, SeedableRng};
 use rand_chacha::ChaCha8Rng;
 use std::collections::HashMap;
@@ -15,6 +17,7 @@ use tracing::{debug, instrument};
 pub struct CentotypeContentGenerator {
     validator: Arc<ContentValidator>,
     corpus_data: CorpusData,
+    letters: LetterProgression,
 }
 
 /// Content generation parameters for a specific level
@@ -275,9 +278,15 @@ impl Default for CorpusData {
 impl CentotypeContentGenerator {
     /// Create new content generator with validation
     pub fn new(validator: Arc<ContentValidator>) -> Self {
+        Self::with_keyboard(validator, KeyboardGeometry::for_layout(KeyboardLayout::Qwerty))
+    }
+
+    /// Create a generator whose letter levels follow `geometry`
+    pub fn with_keyboard(validator: Arc<ContentValidator>, geometry: &KeyboardGeometry) -> Self {
         Self {
             validator,
             corpus_data: CorpusData::default(),
+            letters: LetterProgression::new(geometry),
         }
     }
 
@@ -294,7 +303,7 @@ impl CentotypeContentGenerator {
         );
 
         let content = match params.tier.0 {
-            1..=2 => self.generate_foundation_content(&mut rng, &difficulty)?,
+            1..=2 => self.generate_foundation_content(params.level_id, &mut rng, &difficulty)?,
             3..=4 => self.generate_programming_basics_content(&mut rng, &difficulty)?,
             5..=6 => self.generate_intermediate_content(&mut rng, &difficulty)?,
             7..=8 => self.generate_advanced_content(&mut rng, &difficulty)?,
@@ -313,13 +322,21 @@ impl CentotypeContentGenerator {
         Ok(content)
     }
 
-    /// Generate foundation level content (Tier 1-2, Levels 1-20)
+    /// Generate foundation level content (Tier 1-2, Levels 1-20): letter
+    /// drills that follow the layout's home-row-first letter order, with
+    /// English and Indonesian words taking turns every `switch_freq` chars
     fn generate_foundation_content(
         &self,
+        level_id: LevelId,
         rng: &mut ChaCha8Rng,
         difficulty: &DifficultyParams,
     ) -> Result<String> {
-        let mut content = String::with_capacity(difficulty.content_length + 100);
+        let indonesian: Vec<&str> = self.corpus_data.basic_words[&Language::Indonesian]
+            .iter()
+            .chain(&self.corpus_data.tech_terms[&Language::Indonesian])
+            .map(String::as_str)
+            .collect();
+        let mut content = String::with_capacity(difficulty.content_length);
         let mut current_lang = if rng.gen_bool(0.5) {
             Language::English
         } else {
@@ -328,50 +345,34 @@ impl CentotypeContentGenerator {
         let mut chars_written = 0;
 
         while chars_written < difficulty.content_length {
-            // Alternate languages based on switch frequency
-            if chars_written > 0 && chars_written % difficulty.switch_freq == 0 {
-                current_lang = match current_lang {
-                    Language::English => Language::Indonesian,
-                    Language::Indonesian => Language::English,
-                };
-                content.push_str("\n");
+            if chars_written > 0 {
+                content.push(' ');
                 chars_written += 1;
             }
 
-            // Add language indicator
-            let lang_prefix = match current_lang {
-                Language::English => "EN: ",
-                Language::Indonesian => "ID: ",
+            let length = difficulty
+                .switch_freq
+                .min(difficulty.content_length - chars_written);
+            let segment = match current_lang {
+                Language::English => self.letters.generate(level_id, rng.gen(), length)?,
+                Language::Indonesian => {
+                    self.letters
+                        .generate_with_words(level_id, &indonesian, rng.gen(), length)?
+                }
             };
-            content.push_str(lang_prefix);
-            chars_written += lang_prefix.len();
-
-            // Generate sentence with basic words
-            let sentence = self.generate_basic_sentence(rng, current_lang, difficulty)?;
-            content.push_str(&sentence);
-            chars_written += sentence.len();
-
-            // Add punctuation
-            let punct = if rng.gen_bool(0.7) { ". " } else { "! " };
-            content.push_str(punct);
-            chars_written += punct.len();
-
-            // Inject numbers based on number_ratio
-            if rng.gen_bool(difficulty.number_ratio) {
-                let number = &self.corpus_data.common_numbers
-                    [rng.gen_range(0..self.corpus_data.common_numbers.len())];
-                content.push(' ');
-                content.push_str(number);
-                chars_written += number.len() + 1;
+            if segment.is_empty() {
+                break;
             }
+            chars_written += segment.chars().count();
+            content.push_str(&segment);
 
-            content.push(' ');
-            chars_written += 1;
+            current_lang = match current_lang {
+                Language::English => Language::Indonesian,
+                Language::Indonesian => Language::English,
+            };
         }
 
-        // Trim to exact length
-        content.truncate(difficulty.content_length);
-        Ok(content)
+        Ok(content.trim_end().to_string())
     }
 
     /// Generate programming basics content (Tier 3-4, Levels 21-40)
@@ -778,6 +779,26 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_foundation_content_takes_turns_in_both_languages() {
+        let validator = Arc::new(ContentValidator::new().unwrap());
+        let generator = CentotypeContentGenerator::new(validator);
+        let level = LevelId::new(20).unwrap();
+        let difficulty = DifficultyParams::calculate(level);
+        let mut rng = ChaCha8Rng::seed_from_u64(7);
+
+        let text = generator
+            .generate_foundation_content(level, &mut rng, &difficulty)
+            .unwrap();
+        let words: Vec<&str> = text.split(' ').collect();
+        assert!(text.chars().count() <= difficulty.content_length);
+        let indonesian = ["yang", "juga", "oleh", "tidak", "untuk", "dengan", "dapat"];
+        let english = ["the", "and", "good", "over", "would", "these", "where"];
+        assert!(words.iter().any(|word| indonesian.contains(word)));
+        assert!(words.iter().any(|word| english.contains(word)));
+        assert!(text.chars().all(|ch| ch.is_ascii_lowercase() || ch == ' '));
+    }
+
     #[test]
     fn test_cache_key_generation() {
         let level = LevelId::new(42).unwrap();
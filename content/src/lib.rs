@@ -7,12 +7,14 @@
 //! - Dynamic content generation with deterministic seeding
 //! - Difficulty analysis and validation
 //! - Multi-language content support
+//! - Home-row-first curricula for each keyboard layout
 //! - Character class distribution analysis
 //! - Performance-optimized LRU caching
 //! - Security validation for generated content
 
 pub mod cache;
 pub mod corpus;
+pub mod curriculum;
 pub mod difficulty;
 // pub mod fs_security; // TODO: Add dirs dependency and fix error types
 pub mod generator;
@@ -20,6 +22,10 @@ pub mod validation;
 
 // Re-export main types for public API
 pub use cache::{CacheConfig, CacheManager, CacheMetrics, ContentCache, PreloadStrategy};
+pub use curriculum::{
+    slow_word_text, weak_key_text, LayoutCurriculum, LetterProgression, LAYOUT_DRILL_LENGTH,
+    LETTER_LEVELS, SLOW_WORD_DRILL_LENGTH, WEAK_KEY_DRILL_LENGTH,
+};
 pub use difficulty::{
     DifficultyAnalyzer, DifficultyConfig, DifficultyScore, ProgressionReport, TierRequirements,
 };
@@ -29,6 +35,7 @@ pub use generator::{
 pub use validation::{verify_difficulty_progression, ContentValidator, ValidationResult};
 
 use centotype_core::types::*;
+use centotype_core::KeyboardGeometry;
 use parking_lot::RwLock;
 use std::sync::Arc;
 use tracing::{debug, info, warn};
@@ -62,6 +69,9 @@ pub struct ContentConfig {
     pub enable_validation: bool,
     /// Seed for deterministic content generation
     pub default_seed: Option<u64>,
+    /// Layout whose letter order drives levels 1-20; read when the
+    /// manager is created
+    pub keyboard: Arc<KeyboardGeometry>,
 }
 
 impl Default for ContentConfig {
@@ -73,6 +83,7 @@ impl Default for ContentConfig {
             difficulty_config: DifficultyConfig::default(),
             enable_validation: true,
             default_seed: None, // Use random seeds by default
+            keyboard: KeyboardGeometry::shared(KeyboardLayout::Qwerty),
         }
     }
 }
@@ -94,7 +105,10 @@ impl ContentManager {
             })?);
 
         // Initialize content generator
-        let generator = Arc::new(generator::CentotypeContentGenerator::new(validator));
+        let generator = Arc::new(generator::CentotypeContentGenerator::with_keyboard(
+            validator,
+            &config.keyboard,
+        ));
 
         // Initialize cache manager
         let cache_manager = Arc::new(CacheManager::with_config(
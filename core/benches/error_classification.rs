@@ -0,0 +1,109 @@
+//! Error classification benchmarks at level 100 text length
+//!
+//! `process_keystroke` has a P99 budget of 5ms. These benchmarks compare the
+//! incremental classifier used on the keystroke path against the batch
+//! Damerau-Levenshtein classifier for a 3000-grapheme target.
+
+use centotype_core::error::Error;
+use centotype_core::IncrementalClassifier;
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use std::time::{Duration, Instant};
+
+/// Level 100 content length in graphemes
+const LEVEL_100_LENGTH: usize = 3000;
+
+fn level_100_text() -> String {
+    "async fn process<T: Send + 'static>() -> Result<Vec<T>, Box<dyn Error>> { 0x1F3F } "
+        .chars()
+        .cycle()
+        .take(LEVEL_100_LENGTH)
+        .collect()
+}
+
+fn incremental_keystroke(c: &mut Criterion) {
+    let target = level_100_text();
+    let mut group = c.benchmark_group("incremental_classifier");
+
+    for typed_len in [100, 1500, LEVEL_100_LENGTH - 1] {
+        let mut classifier = IncrementalClassifier::new(&target);
+        for ch in target.chars().take(typed_len) {
+            classifier.push_char(ch);
+        }
+
+        group.bench_with_input(
+            BenchmarkId::new("push_and_analyze", typed_len),
+            &typed_len,
+            |b, _| {
+                b.iter(|| {
+                    classifier.push_char(black_box('x'));
+                    let analysis = classifier.analysis();
+                    classifier.backspace();
+                    analysis
+                })
+            },
+        );
+    }
+
+    group.finish();
+}
+
+fn batch_keystroke(c: &mut Criterion) {
+    let target = level_100_text();
+    let typed: String = target.chars().take(LEVEL_100_LENGTH / 10).collect();
+    let mut group = c.benchmark_group("batch_classifier");
+    group.sample_size(10);
+
+    group.bench_function("classify_errors_300_typed", |b| {
+        b.iter(|| {
+            let mut classifier = Error::new();
+            classifier.classify_errors(black_box(&target), black_box(&typed))
+        })
+    });
+
+    group.finish();
+}
+
+/// Type the whole level with periodic mistakes and report keystroke P99
+fn keystroke_p99(c: &mut Criterion) {
+    let target = level_100_text();
+
+    c.bench_function("incremental_full_session", |b| {
+        b.iter_custom(|iterations| {
+            let mut total = Duration::ZERO;
+            let mut latencies = Vec::with_capacity(LEVEL_100_LENGTH * 2);
+
+            for _ in 0..iterations {
+                let mut classifier = IncrementalClassifier::new(&target);
+                latencies.clear();
+
+                for (i, ch) in target.chars().enumerate() {
+                    let start = Instant::now();
+                    if i % 25 == 0 {
+                        classifier.push_char('#');
+                        black_box(classifier.analysis());
+                        classifier.backspace();
+                    }
+                    classifier.push_char(ch);
+                    black_box(classifier.analysis());
+                    let elapsed = start.elapsed();
+
+                    latencies.push(elapsed);
+                    total += elapsed;
+                }
+
+                latencies.sort();
+                let p99 = latencies[(latencies.len() - 1) * 99 / 100];
+                assert!(
+                    p99 < Duration::from_millis(5),
+                    "P99 keystroke classification {:?} exceeds 5ms budget",
+                    p99
+                );
+            }
+
+            total
+        })
+    });
+}
+
+criterion_group!(benches, incremental_keystroke, batch_keystroke, keystroke_p99);
+criterion_main!(benches);
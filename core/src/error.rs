@@ -1,16 +1,27 @@
 //! Error detection and classification using Damerau-Levenshtein algorithm
+use crate::keyboard::KeyboardGeometry;
 use crate::types::*;
 use std::cmp::min;
 use std::collections::HashMap;
+use std::hash::{DefaultHasher, Hash, Hasher};
 use tracing::debug;
 use unicode_segmentation::UnicodeSegmentation;
 
 /// Error classifier implementing Damerau-Levenshtein distance algorithm
 pub struct Error {
-    cache: HashMap<(String, String), ErrorAnalysis>,
+    /// Analyses keyed by a hash of the target and typed text, so a lookup
+    /// does not copy either string; the text is kept to confirm a hit
+    cache: HashMap<u64, CachedAnalysis>,
     cache_max_size: usize,
 }
 
+/// A cached analysis with the text it was made from
+struct CachedAnalysis {
+    target: String,
+    typed: String,
+    analysis: ErrorAnalysis,
+}
+
 impl Error {
     pub fn new() -> Self {
         Self {
@@ -24,8 +35,8 @@ impl Error {
         let start_time = std::time::Instant::now();
 
         // Check cache first
-        let cache_key = (target.to_string(), typed.to_string());
-        if let Some(cached) = self.cache.get(&cache_key) {
+        let cache_key = Self::cache_key(target, typed);
+        if let Some(cached) = self.cached(cache_key, target, typed) {
             return cached.stats.clone();
         }
 
@@ -35,7 +46,7 @@ impl Error {
 
         // Cache result if we have space
         if self.cache.len() < self.cache_max_size {
-            self.cache.insert(cache_key, analysis);
+            self.cache_insert(cache_key, target, typed, analysis);
         } else if self.cache.len() >= self.cache_max_size {
             // Clear cache when it gets too large
             self.cache.clear();
@@ -56,8 +67,8 @@ impl Error {
 
     /// Get detailed error analysis with position information
     pub fn analyze_errors(&mut self, target: &str, typed: &str) -> ErrorAnalysis {
-        let cache_key = (target.to_string(), typed.to_string());
-        if let Some(cached) = self.cache.get(&cache_key) {
+        let cache_key = Self::cache_key(target, typed);
+        if let Some(cached) = self.cached(cache_key, target, typed) {
             return cached.clone();
         }
 
@@ -65,7 +76,7 @@ impl Error {
 
         // Cache result
         if self.cache.len() < self.cache_max_size {
-            self.cache.insert(cache_key, analysis.clone());
+            self.cache_insert(cache_key, target, typed, analysis.clone());
         }
 
         analysis
@@ -105,6 +116,31 @@ impl Error {
 
     // Private implementation methods
 
+    fn cache_key(target: &str, typed: &str) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        (target, typed).hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// The cached analysis of exactly this text; a hash collision is a miss
+    fn cached(&self, key: u64, target: &str, typed: &str) -> Option<&ErrorAnalysis> {
+        self.cache
+            .get(&key)
+            .filter(|cached| cached.target == target && cached.typed == typed)
+            .map(|cached| &cached.analysis)
+    }
+
+    fn cache_insert(&mut self, key: u64, target: &str, typed: &str, analysis: ErrorAnalysis) {
+        self.cache.insert(
+            key,
+            CachedAnalysis {
+                target: target.to_string(),
+                typed: typed.to_string(),
+                analysis,
+            },
+        );
+    }
+
     fn analyze_text_differences(&self, target: &str, typed: &str) -> ErrorAnalysis {
         // Use grapheme clusters for proper Unicode support
         let target_graphemes: Vec<&str> = target.graphemes(true).collect();
@@ -115,79 +151,7 @@ impl Error {
 
         let total_distance = distance_matrix[target_graphemes.len()][typed_graphemes.len()];
 
-        let mut stats = ErrorStats::default();
-        let mut error_positions = Vec::new();
-
-        // Analyze operations to classify error types
-        for operation in &operations {
-            match operation {
-                EditOperation::Substitution {
-                    position,
-                    expected,
-                    actual,
-                } => {
-                    stats.substitution += 1;
-                    error_positions.push(ErrorPosition {
-                        position: *position,
-                        error_type: ErrorType::Substitution,
-                        expected_char: Some(*expected),
-                        actual_char: Some(*actual),
-                    });
-                }
-                EditOperation::Insertion {
-                    position,
-                    char_inserted,
-                } => {
-                    stats.insertion += 1;
-                    error_positions.push(ErrorPosition {
-                        position: *position,
-                        error_type: ErrorType::Insertion,
-                        expected_char: None,
-                        actual_char: Some(*char_inserted),
-                    });
-                }
-                EditOperation::Deletion {
-                    position,
-                    char_deleted,
-                } => {
-                    stats.deletion += 1;
-                    error_positions.push(ErrorPosition {
-                        position: *position,
-                        error_type: ErrorType::Deletion,
-                        expected_char: Some(*char_deleted),
-                        actual_char: None,
-                    });
-                }
-                EditOperation::Transposition {
-                    position,
-                    char1,
-                    char2,
-                } => {
-                    stats.transposition += 1;
-                    error_positions.push(ErrorPosition {
-                        position: *position,
-                        error_type: ErrorType::Transposition,
-                        expected_char: Some(*char1),
-                        actual_char: Some(*char2),
-                    });
-                }
-                EditOperation::Match { .. } => {
-                    // No error for matches
-                }
-            }
-        }
-
-        ErrorAnalysis {
-            stats,
-            total_distance,
-            error_positions,
-            accuracy: if target_graphemes.is_empty() {
-                100.0
-            } else {
-                let correct_chars = target_graphemes.len() - total_distance;
-                (correct_chars as f64 / target_graphemes.len() as f64) * 100.0
-            },
-        }
+        build_analysis(&operations, total_distance, target_graphemes.len())
     }
 
     fn compute_damerau_levenshtein_with_operations(
@@ -234,77 +198,12 @@ impl Error {
         }
 
         // Trace back to find operations
-        let operations = self.trace_back_operations(source, target, &matrix);
+        let operations =
+            trace_back_operations(source, target, (source_len, target_len), |i, j| matrix[i][j]);
 
         (matrix, operations)
     }
 
-    fn trace_back_operations(
-        &self,
-        source: &[&str],
-        target: &[&str],
-        matrix: &[Vec<usize>],
-    ) -> Vec<EditOperation> {
-        let mut operations = Vec::new();
-        let mut i = source.len();
-        let mut j = target.len();
-
-        while i > 0 || j > 0 {
-            if i > 0 && j > 0 && source[i - 1] == target[j - 1] {
-                // Match
-                operations.push(EditOperation::Match {
-                    position: i - 1,
-                    character: source[i - 1].chars().next().unwrap_or('\0'),
-                });
-                i -= 1;
-                j -= 1;
-            } else if i > 0 && j > 0 && matrix[i][j] == matrix[i - 1][j - 1] + 1 {
-                // Substitution
-                operations.push(EditOperation::Substitution {
-                    position: i - 1,
-                    expected: source[i - 1].chars().next().unwrap_or('\0'),
-                    actual: target[j - 1].chars().next().unwrap_or('\0'),
-                });
-                i -= 1;
-                j -= 1;
-            } else if i > 1
-                && j > 1
-                && source[i - 1] == target[j - 2]
-                && source[i - 2] == target[j - 1]
-                && matrix[i][j] == matrix[i - 2][j - 2] + 1
-            {
-                // Transposition
-                operations.push(EditOperation::Transposition {
-                    position: i - 2,
-                    char1: source[i - 2].chars().next().unwrap_or('\0'),
-                    char2: source[i - 1].chars().next().unwrap_or('\0'),
-                });
-                i -= 2;
-                j -= 2;
-            } else if i > 0 && matrix[i][j] == matrix[i - 1][j] + 1 {
-                // Deletion
-                operations.push(EditOperation::Deletion {
-                    position: i - 1,
-                    char_deleted: source[i - 1].chars().next().unwrap_or('\0'),
-                });
-                i -= 1;
-            } else if j > 0 && matrix[i][j] == matrix[i][j - 1] + 1 {
-                // Insertion
-                operations.push(EditOperation::Insertion {
-                    position: j - 1,
-                    char_inserted: target[j - 1].chars().next().unwrap_or('\0'),
-                });
-                j -= 1;
-            } else {
-                // This shouldn't happen with a correct algorithm
-                break;
-            }
-        }
-
-        operations.reverse(); // Reverse to get operations in forward order
-        operations
-    }
-
     fn identify_common_mistakes(&self, _session_results: &[SessionResult]) -> Vec<CommonMistake> {
         // This is a simplified implementation
         // In practice, you'd analyze patterns across many sessions
@@ -335,6 +234,401 @@ impl Default for Error {
     }
 }
 
+/// Walk the distance matrix back from `end` and recover edit operations.
+/// `distance(i, j)` is the cost of aligning `source[..i]` with `target[..j]`.
+fn trace_back_operations<S: AsRef<str>, T: AsRef<str>>(
+    source: &[S],
+    target: &[T],
+    end: (usize, usize),
+    distance: impl Fn(usize, usize) -> usize,
+) -> Vec<EditOperation> {
+    let first_char = |grapheme: &str| grapheme.chars().next().unwrap_or('\0');
+    let source_at = |i: usize| source[i].as_ref();
+    let target_at = |j: usize| target[j].as_ref();
+
+    let mut operations = Vec::new();
+    let (mut i, mut j) = end;
+
+    while i > 0 || j > 0 {
+        if i > 0 && j > 0 && source_at(i - 1) == target_at(j - 1) {
+            // Match
+            operations.push(EditOperation::Match {
+                position: i - 1,
+                character: first_char(source_at(i - 1)),
+            });
+            i -= 1;
+            j -= 1;
+        } else if i > 0 && j > 0 && distance(i, j) == distance(i - 1, j - 1) + 1 {
+            // Substitution
+            operations.push(EditOperation::Substitution {
+                position: i - 1,
+                expected: first_char(source_at(i - 1)),
+                actual: first_char(target_at(j - 1)),
+            });
+            i -= 1;
+            j -= 1;
+        } else if i > 1
+            && j > 1
+            && source_at(i - 1) == target_at(j - 2)
+            && source_at(i - 2) == target_at(j - 1)
+            && distance(i, j) == distance(i - 2, j - 2) + 1
+        {
+            // Transposition
+            operations.push(EditOperation::Transposition {
+                position: i - 2,
+                char1: first_char(source_at(i - 2)),
+                char2: first_char(source_at(i - 1)),
+            });
+            i -= 2;
+            j -= 2;
+        } else if i > 0 && distance(i, j) == distance(i - 1, j) + 1 {
+            // Deletion
+            operations.push(EditOperation::Deletion {
+                position: i - 1,
+                char_deleted: first_char(source_at(i - 1)),
+            });
+            i -= 1;
+        } else if j > 0 && distance(i, j) == distance(i, j - 1) + 1 {
+            // Insertion
+            operations.push(EditOperation::Insertion {
+                position: j - 1,
+                char_inserted: first_char(target_at(j - 1)),
+            });
+            j -= 1;
+        } else {
+            // This shouldn't happen with a correct algorithm
+            break;
+        }
+    }
+
+    operations.reverse(); // Reverse to get operations in forward order
+    operations
+}
+
+/// Turn edit operations into error statistics and positions
+fn build_analysis(
+    operations: &[EditOperation],
+    total_distance: usize,
+    target_len: usize,
+) -> ErrorAnalysis {
+    let mut stats = ErrorStats::default();
+    let mut error_positions = Vec::new();
+
+    // Analyze operations to classify error types
+    for operation in operations {
+        match operation {
+            EditOperation::Substitution {
+                position,
+                expected,
+                actual,
+            } => {
+                stats.substitution += 1;
+                error_positions.push(ErrorPosition {
+                    position: *position,
+                    error_type: ErrorType::Substitution,
+                    expected_char: Some(*expected),
+                    actual_char: Some(*actual),
+                });
+            }
+            EditOperation::Insertion {
+                position,
+                char_inserted,
+            } => {
+                stats.insertion += 1;
+                error_positions.push(ErrorPosition {
+                    position: *position,
+                    error_type: ErrorType::Insertion,
+                    expected_char: None,
+                    actual_char: Some(*char_inserted),
+                });
+            }
+            EditOperation::Deletion {
+                position,
+                char_deleted,
+            } => {
+                stats.deletion += 1;
+                error_positions.push(ErrorPosition {
+                    position: *position,
+                    error_type: ErrorType::Deletion,
+                    expected_char: Some(*char_deleted),
+                    actual_char: None,
+                });
+            }
+            EditOperation::Transposition {
+                position,
+                char1,
+                char2,
+            } => {
+                stats.transposition += 1;
+                error_positions.push(ErrorPosition {
+                    position: *position,
+                    error_type: ErrorType::Transposition,
+                    expected_char: Some(*char1),
+                    actual_char: Some(*char2),
+                });
+            }
+            EditOperation::Match { .. } => {
+                // No error for matches
+            }
+        }
+    }
+
+    ErrorAnalysis {
+        stats,
+        total_distance,
+        error_positions,
+        accuracy: if target_len == 0 {
+            100.0
+        } else {
+            let correct_chars = target_len.saturating_sub(total_distance);
+            (correct_chars as f64 / target_len as f64) * 100.0
+        },
+    }
+}
+
+/// Typed in place of each grapheme passed over by
+/// [`IncrementalClassifier::skip_word`]; never part of a target text
+pub const SKIPPED_CHAR: char = '\u{FFFD}';
+
+/// Default half-width of the diagonal band kept by [`IncrementalClassifier`]
+pub const DEFAULT_CLASSIFIER_BAND: usize = 64;
+
+/// Marker for cells outside the band; small enough that `+ 1` cannot overflow
+const UNREACHABLE: u32 = u32::MAX / 2;
+
+/// Incremental Damerau-Levenshtein classifier for the per-keystroke hot path
+///
+/// Keeps one DP column per typed grapheme, limited to a diagonal band around
+/// the cursor. Typing appends a column and backspace drops it, so a keystroke
+/// costs O(band) instead of rebuilding the full O(n·m) matrix. When the typed
+/// text drifts more than `band` graphemes away from the target, the excess is
+/// scored as plain insertions or deletions.
+#[derive(Debug, Clone)]
+pub struct IncrementalClassifier {
+    target: Vec<Box<str>>,
+    typed: Vec<String>,
+    band: usize,
+    /// Column-major band cells; column `j` stores rows `j - band..=j + band`
+    columns: Vec<u32>,
+    /// Typed length and target row just after a skipped word
+    skip_anchor: Option<(usize, usize)>,
+}
+
+impl IncrementalClassifier {
+    pub fn new(target: &str) -> Self {
+        Self::with_band(target, DEFAULT_CLASSIFIER_BAND)
+    }
+
+    pub fn with_band(target: &str, band: usize) -> Self {
+        let mut classifier = Self {
+            target: target.graphemes(true).map(Box::from).collect(),
+            typed: Vec::new(),
+            band,
+            columns: Vec::new(),
+            skip_anchor: None,
+        };
+        classifier.append_column();
+        classifier
+    }
+
+    /// Number of typed grapheme clusters
+    pub fn typed_len(&self) -> usize {
+        self.typed.len()
+    }
+
+    /// Number of target grapheme clusters
+    pub fn target_len(&self) -> usize {
+        self.target.len()
+    }
+
+    /// Skip the rest of the target word: each skipped grapheme is typed
+    /// with [`SKIPPED_CHAR`] in place of its first character, so it scores
+    /// as missed and the typed text stays lined up with the target, then the
+    /// whitespace that ends the word is typed. Returns the text typed, or
+    /// `None` within the last word.
+    pub fn skip_word(&mut self) -> Option<String> {
+        // Right after a skip the alignment still favours the skipped word
+        let start = match self.skip_anchor {
+            Some((typed_len, row)) if typed_len == self.typed.len() => row,
+            _ => self.best_row(self.typed.len().min(self.target.len() + self.band)),
+        };
+        let row = self
+            .target
+            .iter()
+            .skip(start)
+            .position(|grapheme| grapheme.chars().all(char::is_whitespace))?
+            + start;
+
+        let mut skipped = String::new();
+        for grapheme in &self.target[start..row] {
+            // Combining marks stay, keeping the grapheme and character counts
+            skipped.push(SKIPPED_CHAR);
+            skipped.extend(grapheme.chars().skip(1));
+        }
+        skipped.push_str(&self.target[row]);
+        for ch in skipped.chars() {
+            self.push_char(ch);
+        }
+        self.skip_anchor = Some((self.typed.len(), row + 1));
+        Some(skipped)
+    }
+
+    /// Append a typed character, merging it into the previous grapheme
+    /// cluster when it extends one (e.g. a combining accent)
+    pub fn push_char(&mut self, ch: char) {
+        self.skip_anchor = None;
+        if let Some(last) = self.typed.last() {
+            let mut merged = last.clone();
+            merged.push(ch);
+            if merged.graphemes(true).count() == 1 {
+                self.drop_column();
+                self.typed.pop();
+                self.typed.push(merged);
+                self.append_column();
+                return;
+            }
+        }
+
+        self.typed.push(ch.to_string());
+        self.append_column();
+    }
+
+    /// Remove the last typed character, rewinding the affected column.
+    /// Returns false when there is nothing to delete.
+    pub fn backspace(&mut self) -> bool {
+        self.skip_anchor = None;
+        let Some(mut last) = self.typed.pop() else {
+            return false;
+        };
+        self.drop_column();
+
+        last.pop();
+        if !last.is_empty() {
+            self.typed.push(last);
+            self.append_column();
+        }
+        true
+    }
+
+    /// Error statistics against the part of the target reached so far
+    pub fn stats(&self) -> ErrorStats {
+        self.analysis().stats
+    }
+
+    /// Detailed analysis against the part of the target reached so far.
+    /// Untyped target text is not counted as deletions.
+    pub fn analysis(&self) -> ErrorAnalysis {
+        let end_col = self.typed.len().min(self.target.len() + self.band);
+        let end_row = self.best_row(end_col);
+        self.analysis_from(end_row, end_col, end_row)
+    }
+
+    /// Detailed analysis against the whole target, equivalent to
+    /// [`Error::analyze_errors`] while drift stays within the band
+    pub fn final_analysis(&self) -> ErrorAnalysis {
+        let end_col = self.typed.len().min(self.target.len() + self.band);
+        let end_row = self.target.len().min(end_col + self.band);
+        self.analysis_from(end_row, end_col, self.target.len())
+    }
+
+    // Private implementation methods
+
+    fn width(&self) -> usize {
+        2 * self.band + 1
+    }
+
+    fn cell(&self, row: usize, col: usize) -> u32 {
+        if row > self.target.len() || row + self.band < col || row > col + self.band {
+            return UNREACHABLE;
+        }
+        self.columns[col * self.width() + row + self.band - col]
+    }
+
+    /// Compute the column for the current typed length
+    fn append_column(&mut self) {
+        let col = self.typed.len();
+        for slot in 0..self.width() {
+            let value = match (col + slot).checked_sub(self.band) {
+                Some(row) if row <= self.target.len() => self.compute_cell(row, col),
+                _ => UNREACHABLE,
+            };
+            self.columns.push(value);
+        }
+    }
+
+    fn drop_column(&mut self) {
+        let len = self.columns.len() - self.width();
+        self.columns.truncate(len);
+    }
+
+    fn compute_cell(&self, row: usize, col: usize) -> u32 {
+        if col == 0 {
+            return row as u32;
+        }
+        if row == 0 {
+            return col as u32;
+        }
+
+        let cost = u32::from(*self.target[row - 1] != *self.typed[col - 1]);
+        let mut value = min(
+            min(
+                self.cell(row - 1, col) + 1, // deletion
+                self.cell(row, col - 1) + 1, // insertion
+            ),
+            self.cell(row - 1, col - 1) + cost, // substitution
+        );
+
+        // Transposition
+        if row > 1
+            && col > 1
+            && *self.target[row - 1] == self.typed[col - 2]
+            && *self.target[row - 2] == self.typed[col - 1]
+        {
+            value = min(value, self.cell(row - 2, col - 2) + cost);
+        }
+
+        value
+    }
+
+    /// Row of `col` with the lowest distance, preferring the diagonal on ties
+    fn best_row(&self, col: usize) -> usize {
+        let low = col.saturating_sub(self.band);
+        let high = self.target.len().min(col + self.band);
+        (low..=high)
+            .min_by_key(|&row| (self.cell(row, col), row.abs_diff(col)))
+            .unwrap_or(0)
+    }
+
+    /// Trace back from `(end_row, end_col)`, then account for target rows up
+    /// to `target_end` and typed columns beyond the band as deletions and
+    /// insertions respectively
+    fn analysis_from(&self, end_row: usize, end_col: usize, target_end: usize) -> ErrorAnalysis {
+        let mut operations = trace_back_operations(
+            &self.target,
+            &self.typed,
+            (end_row, end_col),
+            |i, j| self.cell(i, j) as usize,
+        );
+
+        operations.extend((end_row..target_end).map(|position| EditOperation::Deletion {
+            position,
+            char_deleted: self.target[position].chars().next().unwrap_or('\0'),
+        }));
+        operations.extend((end_col..self.typed.len()).map(|position| {
+            EditOperation::Insertion {
+                position,
+                char_inserted: self.typed[position].chars().next().unwrap_or('\0'),
+            }
+        }));
+
+        let total_distance = self.cell(end_row, end_col) as usize
+            + (target_end - end_row)
+            + (self.typed.len() - end_col);
+
+        build_analysis(&operations, total_distance, target_end)
+    }
+}
+
 /// Complete error analysis result
 #[derive(Debug, Clone)]
 pub struct ErrorAnalysis {
@@ -344,6 +638,24 @@ pub struct ErrorAnalysis {
     pub accuracy: f64,
 }
 
+impl ErrorAnalysis {
+    /// Classify substitution errors against a keyboard layout
+    pub fn layout_breakdown(&self, geometry: &KeyboardGeometry) -> LayoutErrorStats {
+        let mut stats = LayoutErrorStats::default();
+
+        for error in &self.error_positions {
+            if let (ErrorType::Substitution, Some(expected), Some(actual)) =
+                (error.error_type, error.expected_char, error.actual_char)
+            {
+                let (kind, drift) = geometry.classify_substitution(expected, actual);
+                stats.record(kind, drift);
+            }
+        }
+
+        stats
+    }
+}
+
 /// Individual error position with context
 #[derive(Debug, Clone)]
 pub struct ErrorPosition {
@@ -524,6 +836,19 @@ mod tests {
         assert_eq!(stats1.insertion, stats2.insertion);
         assert_eq!(stats1.deletion, stats2.deletion);
         assert_eq!(stats1.transposition, stats2.transposition);
+        assert_eq!(classifier.cache.len(), 1);
+
+        // The same strings split differently are a different pair
+        let stats3 = classifier.classify_errors("hell", "ohallo");
+        assert_ne!(stats3, stats1);
+        assert_eq!(classifier.cache.len(), 2);
+
+        // An entry under the same hash for other text is not a hit
+        let key = Error::cache_key("hello", "hallo");
+        let other = classifier.analyze_errors("abc", "abc");
+        classifier.cache_insert(key, "abc", "abc", other);
+        let stats4 = classifier.classify_errors("hello", "hallo");
+        assert_eq!(stats4, stats1);
     }
 
     #[test]
@@ -536,4 +861,165 @@ mod tests {
 
         assert!(transposition_stats.severity_score() > substitution_stats.severity_score());
     }
+
+    fn type_into(classifier: &mut IncrementalClassifier, typed: &str) {
+        for ch in typed.chars() {
+            classifier.push_char(ch);
+        }
+    }
+
+    #[test]
+    fn test_incremental_matches_batch() {
+        let mut batch = Error::new();
+        let cases = [
+            ("hello", "hallo"),
+            ("hello", "ehllo"),
+            ("the", "teh"),
+            ("programming", "progamming"),
+            ("hello", "helloo"),
+            ("hello", ""),
+            ("", "hello"),
+            ("café", "cafe"),
+            ("hello 😊", "hello 😢"),
+        ];
+
+        for (target, typed) in cases {
+            let mut classifier = IncrementalClassifier::new(target);
+            type_into(&mut classifier, typed);
+
+            let expected = batch.analyze_errors(target, typed);
+            let actual = classifier.final_analysis();
+            assert_eq!(actual.stats, expected.stats, "{:?} -> {:?}", target, typed);
+            assert_eq!(actual.total_distance, expected.total_distance);
+            assert_eq!(actual.error_positions.len(), expected.error_positions.len());
+        }
+    }
+
+    #[test]
+    fn test_incremental_backspace_rewinds() {
+        let mut classifier = IncrementalClassifier::new("hello");
+        type_into(&mut classifier, "hx");
+        assert_eq!(classifier.stats().substitution, 1);
+
+        assert!(classifier.backspace());
+        type_into(&mut classifier, "ello");
+        assert_eq!(classifier.final_analysis().stats, ErrorStats::default());
+
+        let mut empty = IncrementalClassifier::new("hello");
+        assert!(!empty.backspace());
+    }
+
+    #[test]
+    fn test_skip_word() {
+        let mut classifier = IncrementalClassifier::new("hello big\nworld");
+        type_into(&mut classifier, "he");
+        assert_eq!(classifier.skip_word().as_deref(), Some("\u{FFFD}\u{FFFD}\u{FFFD} "));
+        // Skipping again moves on to the next word
+        assert_eq!(classifier.skip_word().as_deref(), Some("\u{FFFD}\u{FFFD}\u{FFFD}\n"));
+        assert_eq!(classifier.typed_len(), 10);
+
+        type_into(&mut classifier, "world");
+
+        // The skipped "llo" and "big" score as missed
+        let stats = classifier.final_analysis().stats;
+        assert_eq!((stats.substitution, stats.total_errors()), (6, 6));
+
+        // Nothing to skip to within the last word
+        let mut last_word = IncrementalClassifier::new("end");
+        type_into(&mut last_word, "e");
+        assert_eq!(last_word.skip_word(), None);
+        assert_eq!(last_word.typed_len(), 1);
+    }
+
+    #[test]
+    fn test_incremental_live_positions() {
+        let mut classifier = IncrementalClassifier::new("hello world");
+
+        // Untyped text is not reported while the session is in progress
+        type_into(&mut classifier, "hel");
+        assert_eq!(classifier.stats().total_errors(), 0);
+        assert_eq!(classifier.final_analysis().stats.deletion, 8);
+
+        type_into(&mut classifier, "p");
+        let analysis = classifier.analysis();
+        assert_eq!(analysis.error_positions.len(), 1);
+        assert_eq!(analysis.error_positions[0].position, 3);
+        assert_eq!(analysis.error_positions[0].expected_char, Some('l'));
+        assert_eq!(analysis.error_positions[0].actual_char, Some('p'));
+    }
+
+    #[test]
+    fn test_incremental_combining_characters() {
+        let mut classifier = IncrementalClassifier::new("cafe\u{301}");
+        type_into(&mut classifier, "cafe\u{301}");
+        assert_eq!(classifier.typed_len(), 4);
+        assert_eq!(classifier.stats().total_errors(), 0);
+
+        // Backspace removes the accent but keeps the base letter
+        assert!(classifier.backspace());
+        assert_eq!(classifier.typed_len(), 4);
+        assert_eq!(classifier.stats().substitution, 1);
+    }
+
+    #[test]
+    fn test_incremental_drift_beyond_band() {
+        let mut classifier = IncrementalClassifier::with_band("abc", 2);
+        type_into(&mut classifier, "abcxxxxxx");
+
+        let analysis = classifier.final_analysis();
+        assert_eq!(analysis.stats.insertion, 6);
+        assert_eq!(analysis.total_distance, 6);
+
+        let mut classifier = IncrementalClassifier::with_band("abcdefghij", 2);
+        type_into(&mut classifier, "a");
+        assert_eq!(classifier.final_analysis().stats.deletion, 9);
+    }
+
+    #[test]
+    fn test_incremental_corrections_at_level_100_length() {
+        let target: String = "fn process(&mut self) -> Result<u32> { 0x1F } "
+            .chars()
+            .cycle()
+            .take(3000)
+            .collect();
+        let mut classifier = IncrementalClassifier::new(&target);
+
+        // The 5ms keystroke budget is checked by the error_classification bench
+        for (i, ch) in target.chars().enumerate() {
+            if i % 20 == 0 {
+                classifier.push_char('#');
+                classifier.backspace();
+            }
+            classifier.push_char(ch);
+            let _ = classifier.analysis();
+        }
+
+        assert_eq!(classifier.final_analysis().stats.total_errors(), 0);
+    }
+
+    proptest::proptest! {
+        #[test]
+        fn prop_incremental_matches_batch(target in "[abc ]{0,24}", typed in "[abc ]{0,24}") {
+            let mut classifier = IncrementalClassifier::new(&target);
+            type_into(&mut classifier, &typed);
+            let expected = Error::new().analyze_errors(&target, &typed);
+            proptest::prop_assert_eq!(classifier.final_analysis().stats, expected.stats);
+        }
+    }
+
+    #[test]
+    fn test_layout_breakdown() {
+        let mut classifier = Error::new();
+
+        // f -> g is an adjacent slip, a -> A a case error
+        let analysis = classifier.analyze_errors("fast", "gAst");
+        let breakdown = analysis.layout_breakdown(KeyboardGeometry::for_layout(KeyboardLayout::Qwerty));
+        assert_eq!(breakdown.adjacent_key, 1);
+        assert_eq!(breakdown.case, 1);
+        assert_eq!(breakdown.total(), 2);
+        assert_eq!(
+            breakdown.dominant_drift().map(|d| (d.finger, d.direction)),
+            Some((Finger::LeftIndex, DriftDirection::Right))
+        );
+    }
 }
@@ -109,8 +109,8 @@ pub enum GameEvent {
 
     /// Session completion event
     SessionComplete {
-        /// Final session results
-        result: SessionResult,
+        /// Final session results, boxed to keep the other events small
+        result: Box<SessionResult>,
         /// Milliseconds since session start
         timestamp_ms: u64,
     },
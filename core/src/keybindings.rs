@@ -0,0 +1,389 @@
+//! Key bindings for in-session commands
+//!
+//! Bindings are read from the `[keybindings]` table of the config file, one
+//! list of keys per command, written as `"ctrl+p"`, `"esc"` or `"f1"`. A key
+//! bound to a command is never typed, so bindings are checked against the
+//! characters the current training mode allows.
+use crate::types::*;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// Commands that can be bound to keys during a session
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SessionCommand {
+    Restart,
+    /// Pauses a running session and resumes a paused one
+    PauseResume,
+    Quit,
+    Help,
+    /// Moves past the rest of the current word; skipped characters count as
+    /// missed
+    SkipWord,
+    ToggleMetrics,
+    /// Shows or hides the live WPM and accuracy chart
+    ToggleChart,
+    /// Shows or hides the on-screen keyboard heatmap
+    ToggleKeyboard,
+}
+
+impl SessionCommand {
+    pub const ALL: [SessionCommand; 8] = [
+        SessionCommand::Restart,
+        SessionCommand::PauseResume,
+        SessionCommand::Quit,
+        SessionCommand::Help,
+        SessionCommand::SkipWord,
+        SessionCommand::ToggleMetrics,
+        SessionCommand::ToggleChart,
+        SessionCommand::ToggleKeyboard,
+    ];
+
+    /// Name of the command in the `[keybindings]` table
+    pub fn config_key(self) -> &'static str {
+        match self {
+            SessionCommand::Restart => "restart",
+            SessionCommand::PauseResume => "pause",
+            SessionCommand::Quit => "quit",
+            SessionCommand::Help => "help",
+            SessionCommand::SkipWord => "skip_word",
+            SessionCommand::ToggleMetrics => "toggle_metrics",
+            SessionCommand::ToggleChart => "toggle_chart",
+            SessionCommand::ToggleKeyboard => "toggle_keyboard",
+        }
+    }
+}
+
+/// A key together with the Ctrl and Alt modifiers held with it. Shift is
+/// part of the character, so `"Q"` and `"shift+q"` are the same chord.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct KeyChord {
+    code: KeyCode,
+    modifiers: KeyModifiers,
+}
+
+impl KeyChord {
+    pub fn new(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        let mut code = code;
+        let modifiers = modifiers & (KeyModifiers::CONTROL | KeyModifiers::ALT);
+        // Terminals report Ctrl+Shift+C as Ctrl+C or Ctrl+'C'
+        if let KeyCode::Char(ch) = code {
+            if !modifiers.is_empty() {
+                code = KeyCode::Char(ch.to_ascii_lowercase());
+            }
+        }
+        Self { code, modifiers }
+    }
+
+    pub fn from_event(event: &KeyEvent) -> Self {
+        Self::new(event.code, event.modifiers)
+    }
+
+    /// Character the chord types when no command claims it
+    pub fn typed_char(&self) -> Option<char> {
+        if !self.modifiers.is_empty() {
+            return None;
+        }
+        match self.code {
+            KeyCode::Char(ch) => Some(ch),
+            KeyCode::Tab => Some('\t'),
+            _ => None,
+        }
+    }
+}
+
+impl FromStr for KeyChord {
+    type Err = CentotypeError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = || CentotypeError::Config(format!("Invalid key binding '{}'", s));
+        let lower = s.trim().to_ascii_lowercase();
+        // A trailing "+" after a separator is the plus key itself
+        let (prefix, key) = match lower.strip_suffix("++") {
+            Some(prefix) => (prefix, "+"),
+            None if lower == "+" => ("", "+"),
+            None => lower.rsplit_once('+').unwrap_or(("", lower.as_str())),
+        };
+
+        let mut modifiers = KeyModifiers::NONE;
+        let mut shift = false;
+        for modifier in prefix.split('+').filter(|m| !m.is_empty()) {
+            match modifier {
+                "ctrl" | "control" => modifiers |= KeyModifiers::CONTROL,
+                "alt" | "meta" => modifiers |= KeyModifiers::ALT,
+                "shift" => shift = true,
+                _ => return Err(invalid()),
+            }
+        }
+
+        let code = match key {
+            "esc" | "escape" => KeyCode::Esc,
+            "enter" | "return" => KeyCode::Enter,
+            "tab" => KeyCode::Tab,
+            "backspace" => KeyCode::Backspace,
+            "delete" | "del" => KeyCode::Delete,
+            "space" => KeyCode::Char(' '),
+            "up" => KeyCode::Up,
+            "down" => KeyCode::Down,
+            "left" => KeyCode::Left,
+            "right" => KeyCode::Right,
+            "home" => KeyCode::Home,
+            "end" => KeyCode::End,
+            "pageup" => KeyCode::PageUp,
+            "pagedown" => KeyCode::PageDown,
+            _ => match key.strip_prefix('f').and_then(|n| n.parse::<u8>().ok()) {
+                Some(n @ 1..=12) => KeyCode::F(n),
+                _ => {
+                    // Single characters keep the case they were written in
+                    let original = &s.trim()[s.trim().len() - key.len()..];
+                    let mut chars = original.chars();
+                    match (chars.next(), chars.next()) {
+                        (Some(ch), None) if shift => KeyCode::Char(ch.to_ascii_uppercase()),
+                        (Some(ch), None) => KeyCode::Char(ch),
+                        _ => return Err(invalid()),
+                    }
+                }
+            },
+        };
+
+        Ok(Self::new(code, modifiers))
+    }
+}
+
+impl TryFrom<String> for KeyChord {
+    type Error = CentotypeError;
+
+    fn try_from(s: String) -> Result<Self> {
+        s.parse()
+    }
+}
+
+impl From<KeyChord> for String {
+    fn from(chord: KeyChord) -> Self {
+        chord.to_string()
+    }
+}
+
+impl fmt::Display for KeyChord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            write!(f, "ctrl+")?;
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            write!(f, "alt+")?;
+        }
+        match self.code {
+            KeyCode::Char(' ') => write!(f, "space"),
+            KeyCode::Char(ch) => write!(f, "{}", ch),
+            KeyCode::F(n) => write!(f, "f{}", n),
+            KeyCode::Esc => write!(f, "esc"),
+            KeyCode::Enter => write!(f, "enter"),
+            KeyCode::Tab => write!(f, "tab"),
+            KeyCode::Backspace => write!(f, "backspace"),
+            KeyCode::Delete => write!(f, "delete"),
+            KeyCode::Up => write!(f, "up"),
+            KeyCode::Down => write!(f, "down"),
+            KeyCode::Left => write!(f, "left"),
+            KeyCode::Right => write!(f, "right"),
+            KeyCode::Home => write!(f, "home"),
+            KeyCode::End => write!(f, "end"),
+            KeyCode::PageUp => write!(f, "pageup"),
+            KeyCode::PageDown => write!(f, "pagedown"),
+            other => write!(f, "{:?}", other),
+        }
+    }
+}
+
+/// Keys bound to each session command. Commands left out of the config keep
+/// their default keys; an empty list unbinds a command.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct KeyBindings {
+    pub restart: Vec<KeyChord>,
+    pub pause: Vec<KeyChord>,
+    pub quit: Vec<KeyChord>,
+    pub help: Vec<KeyChord>,
+    pub skip_word: Vec<KeyChord>,
+    pub toggle_metrics: Vec<KeyChord>,
+    pub toggle_chart: Vec<KeyChord>,
+    pub toggle_keyboard: Vec<KeyChord>,
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        let ctrl = |ch| KeyChord::new(KeyCode::Char(ch), KeyModifiers::CONTROL);
+        let key = |code| KeyChord::new(code, KeyModifiers::NONE);
+        Self {
+            restart: vec![ctrl('r')],
+            pause: vec![ctrl('p')],
+            quit: vec![ctrl('c'), key(KeyCode::Esc)],
+            help: vec![key(KeyCode::F(1))],
+            skip_word: vec![ctrl('n')],
+            toggle_metrics: vec![ctrl('t')],
+            toggle_chart: vec![key(KeyCode::F(2))],
+            toggle_keyboard: vec![key(KeyCode::F(3))],
+        }
+    }
+}
+
+impl KeyBindings {
+    pub fn keys(&self, command: SessionCommand) -> &[KeyChord] {
+        match command {
+            SessionCommand::Restart => &self.restart,
+            SessionCommand::PauseResume => &self.pause,
+            SessionCommand::Quit => &self.quit,
+            SessionCommand::Help => &self.help,
+            SessionCommand::SkipWord => &self.skip_word,
+            SessionCommand::ToggleMetrics => &self.toggle_metrics,
+            SessionCommand::ToggleChart => &self.toggle_chart,
+            SessionCommand::ToggleKeyboard => &self.toggle_keyboard,
+        }
+    }
+
+    /// Replace the keys of a command; an empty list unbinds it
+    pub fn set_keys(&mut self, command: SessionCommand, keys: Vec<KeyChord>) {
+        let slot = match command {
+            SessionCommand::Restart => &mut self.restart,
+            SessionCommand::PauseResume => &mut self.pause,
+            SessionCommand::Quit => &mut self.quit,
+            SessionCommand::Help => &mut self.help,
+            SessionCommand::SkipWord => &mut self.skip_word,
+            SessionCommand::ToggleMetrics => &mut self.toggle_metrics,
+            SessionCommand::ToggleChart => &mut self.toggle_chart,
+            SessionCommand::ToggleKeyboard => &mut self.toggle_keyboard,
+        };
+        *slot = keys;
+    }
+
+    /// Command bound to a key, if any
+    pub fn command_for(&self, chord: KeyChord) -> Option<SessionCommand> {
+        SessionCommand::ALL
+            .into_iter()
+            .find(|&command| self.keys(command).contains(&chord))
+    }
+
+    /// Keys of a command for on-screen hints, e.g. `ctrl+c/esc`
+    pub fn label(&self, command: SessionCommand) -> String {
+        let keys = self.keys(command);
+        if keys.is_empty() {
+            return "unbound".to_string();
+        }
+        keys.iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+
+    /// Check that quit has a key, that no key serves two commands, and that
+    /// no bound key is one `is_typeable` says the player has to type
+    pub fn validate(&self, is_typeable: impl Fn(char) -> bool) -> Result<()> {
+        let mut conflicts = Vec::new();
+
+        if self.quit.is_empty() {
+            conflicts.push("quit needs at least one key".to_string());
+        }
+
+        for (i, &command) in SessionCommand::ALL.iter().enumerate() {
+            for &chord in self.keys(command) {
+                let clash = SessionCommand::ALL[i + 1..]
+                    .iter()
+                    .find(|&&other| self.keys(other).contains(&chord));
+                if let Some(other) = clash {
+                    conflicts.push(format!(
+                        "'{}' is bound to both {} and {}",
+                        chord,
+                        command.config_key(),
+                        other.config_key()
+                    ));
+                }
+                if chord.typed_char().is_some_and(&is_typeable) {
+                    conflicts.push(format!(
+                        "'{}' for {} is a key typed in this mode",
+                        chord,
+                        command.config_key()
+                    ));
+                }
+            }
+        }
+
+        if conflicts.is_empty() {
+            Ok(())
+        } else {
+            Err(CentotypeError::Config(format!(
+                "Key binding conflicts: {}",
+                conflicts.join("; ")
+            )))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chord_parsing() {
+        let ctrl_p: KeyChord = "Ctrl+P".parse().unwrap();
+        assert_eq!(ctrl_p, KeyChord::new(KeyCode::Char('p'), KeyModifiers::CONTROL));
+        assert_eq!(ctrl_p.to_string(), "ctrl+p");
+
+        assert_eq!("esc".parse::<KeyChord>().unwrap().code, KeyCode::Esc);
+        assert_eq!("F1".parse::<KeyChord>().unwrap().code, KeyCode::F(1));
+        assert_eq!("shift+q".parse::<KeyChord>().unwrap(), "Q".parse().unwrap());
+        assert_eq!("alt++".parse::<KeyChord>().unwrap().to_string(), "alt++");
+        assert_eq!("space".parse::<KeyChord>().unwrap().typed_char(), Some(' '));
+
+        assert!("hyper+x".parse::<KeyChord>().is_err());
+        assert!("ctrl+".parse::<KeyChord>().is_err());
+        assert!("f13".parse::<KeyChord>().is_err());
+    }
+
+    #[test]
+    fn test_bindings_from_config() {
+        // Listed commands replace their defaults, the rest keep them
+        let bindings: KeyBindings = toml::from_str(
+            r#"
+            quit = ["ctrl+c"]
+            skip_word = ["alt+n", "f2"]
+            "#,
+        )
+        .unwrap();
+
+        let esc = KeyChord::new(KeyCode::Esc, KeyModifiers::NONE);
+        assert_eq!(bindings.command_for(esc), None);
+        assert_eq!(bindings.label(SessionCommand::SkipWord), "alt+n/f2");
+        assert_eq!(bindings.pause, KeyBindings::default().pause);
+        assert_eq!(
+            bindings.command_for(KeyChord::new(KeyCode::Char('C'), KeyModifiers::CONTROL)),
+            Some(SessionCommand::Quit)
+        );
+    }
+
+    #[test]
+    fn test_binding_conflicts() {
+        assert!(KeyBindings::default().validate(|_| true).is_ok());
+
+        // A letter is free in a numbers drill but not where letters are typed
+        let bindings = KeyBindings {
+            help: vec!["h".parse().unwrap()],
+            ..KeyBindings::default()
+        };
+        assert!(bindings.validate(|ch| ch.is_ascii_digit()).is_ok());
+        assert!(bindings.validate(char::is_alphabetic).is_err());
+
+        let bindings = KeyBindings {
+            restart: vec!["ctrl+p".parse().unwrap()],
+            ..KeyBindings::default()
+        };
+        let error = bindings.validate(|_| false).unwrap_err().to_string();
+        assert!(error.contains("'ctrl+p' is bound to both restart and pause"));
+
+        let bindings = KeyBindings {
+            quit: Vec::new(),
+            ..KeyBindings::default()
+        };
+        assert!(bindings.validate(|_| false).is_err());
+    }
+}
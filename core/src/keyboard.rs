@@ -0,0 +1,642 @@
+//! Physical keyboard geometry for layout-aware error analysis
+//!
+//! Geometry is built from a `LayoutDefinition`: four rows of keys with one
+//! character per shift level, placed on a standard row-staggered ANSI or ISO
+//! board. Keys carry their row, horizontal position and the touch-typing
+//! finger, which is enough to tell adjacent-key slips from same-finger
+//! confusions or wrong-hand mirror errors.
+use crate::layout::{FormFactor, LayoutDefinition, EMPTY_SLOT, MAX_SHIFT_LEVELS};
+use crate::types::*;
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Row index of the space bar
+const SPACE_ROW: u8 = 4;
+
+use Finger::*;
+
+/// Finger per key slot for each row, left to right
+const NUMBER_ROW_FINGERS: [Finger; 13] = [
+    LeftPinky, LeftPinky, LeftRing, LeftMiddle, LeftIndex, LeftIndex, RightIndex, RightIndex,
+    RightMiddle, RightRing, RightPinky, RightPinky, RightPinky,
+];
+const TOP_ROW_FINGERS: [Finger; 13] = [
+    LeftPinky, LeftRing, LeftMiddle, LeftIndex, LeftIndex, RightIndex, RightIndex, RightMiddle,
+    RightRing, RightPinky, RightPinky, RightPinky, RightPinky,
+];
+const HOME_ROW_FINGERS: [Finger; 12] = [
+    LeftPinky, LeftRing, LeftMiddle, LeftIndex, LeftIndex, RightIndex, RightIndex, RightMiddle,
+    RightRing, RightPinky, RightPinky, RightPinky,
+];
+/// ISO bottom row, including the extra key left of the first letter
+const ISO_BOTTOM_ROW_FINGERS: [Finger; 11] = [
+    LeftPinky, LeftPinky, LeftRing, LeftMiddle, LeftIndex, LeftIndex, RightIndex, RightIndex,
+    RightMiddle, RightRing, RightPinky,
+];
+
+/// Horizontal offset of the first key in each row, in key widths
+const NUMBER_ROW_OFFSET: f32 = 0.0;
+const TOP_ROW_OFFSET: f32 = 1.5;
+const HOME_ROW_OFFSET: f32 = 1.75;
+const ANSI_BOTTOM_ROW_OFFSET: f32 = 2.25;
+const ISO_BOTTOM_ROW_OFFSET: f32 = 1.25;
+
+/// Fingers resting on the home-row anchors, left to right
+const ANCHOR_FINGERS: [Finger; 8] = [
+    LeftPinky, LeftRing, LeftMiddle, LeftIndex, RightIndex, RightMiddle, RightRing, RightPinky,
+];
+
+/// Row index of the home row
+const HOME_ROW: u8 = 2;
+
+/// Order in which fingers take on reach keys, strongest first
+fn finger_strength(finger: Finger) -> u8 {
+    match finger {
+        LeftIndex | RightIndex => 0,
+        LeftMiddle | RightMiddle => 1,
+        LeftRing | RightRing => 2,
+        LeftPinky | RightPinky => 3,
+        Thumb => 4,
+    }
+}
+
+/// Reach rows in teaching order: top, bottom, then number row
+fn reach_rank(row: u8) -> u8 {
+    match row {
+        HOME_ROW => 0,
+        1 => 1,
+        3 => 2,
+        _ => 3,
+    }
+}
+
+/// Location of a physical key
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct KeyPosition {
+    /// 0 = number row, 1 = top row, 2 = home row, 3 = bottom row, 4 = space
+    pub row: u8,
+    /// Horizontal centre in key widths from the left edge of the number row
+    pub x: f32,
+    pub finger: Finger,
+}
+
+impl KeyPosition {
+    /// Whether both positions are the same key of the board, whichever
+    /// layouts they were looked up in
+    pub fn same_key(&self, other: &KeyPosition) -> bool {
+        self.row == other.row && (self.x - other.x).abs() < 0.25
+    }
+}
+
+/// A character's key and the shift level it sits on
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct KeyLookup {
+    pub position: KeyPosition,
+    /// 0 = base, 1 = Shift, 2 = AltGr, 3 = Shift+AltGr
+    pub level: u8,
+    key_index: usize,
+}
+
+impl KeyLookup {
+    /// Whether Shift must be held to type the character
+    pub fn shifted(&self) -> bool {
+        self.level % 2 == 1
+    }
+
+    pub fn needs_altgr(&self) -> bool {
+        self.level >= 2
+    }
+}
+
+/// Character-to-key map for one keyboard layout
+#[derive(Debug, Clone)]
+pub struct KeyboardGeometry {
+    id: String,
+    name: String,
+    keys: HashMap<char, KeyLookup>,
+    anchors: Vec<char>,
+}
+
+static BUILTIN_GEOMETRY: Lazy<HashMap<KeyboardLayout, Arc<KeyboardGeometry>>> = Lazy::new(|| {
+    KeyboardLayout::ALL
+        .iter()
+        .map(|&layout| {
+            let geometry = KeyboardGeometry::from_definition(LayoutDefinition::builtin(layout))
+                .unwrap_or_else(|e| panic!("built-in layout {:?} is invalid: {}", layout, e));
+            (layout, Arc::new(geometry))
+        })
+        .collect()
+});
+
+impl KeyboardGeometry {
+    /// Geometry for a built-in layout as shipped. User files can replace a
+    /// built-in id, so at run time take the geometry from the loaded
+    /// `LayoutRegistry` instead.
+    pub fn for_layout(layout: KeyboardLayout) -> &'static KeyboardGeometry {
+        &BUILTIN_GEOMETRY[&layout]
+    }
+
+    /// Shared handle to the geometry of a built-in layout as shipped
+    pub fn shared(layout: KeyboardLayout) -> Arc<KeyboardGeometry> {
+        Arc::clone(&BUILTIN_GEOMETRY[&layout])
+    }
+
+    /// Id of the layout definition the geometry was built from
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Display name of the layout, e.g. "Colemak-DH"
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Map every character of this layout to the character the same physical
+    /// key and shift level produce on `target`. Keys are matched by row and
+    /// horizontal position, so ANSI and ISO boards line up where they overlap.
+    pub fn remap_to(&self, target: &KeyboardGeometry) -> HashMap<char, char> {
+        let slot = |lookup: &KeyLookup| {
+            (lookup.position.row, (lookup.position.x * 4.0).round() as i32, lookup.level)
+        };
+        let target_slots: HashMap<_, char> = target
+            .keys
+            .iter()
+            .map(|(&ch, lookup)| (slot(lookup), ch))
+            .collect();
+
+        self.keys
+            .iter()
+            .filter_map(|(&ch, lookup)| target_slots.get(&slot(lookup)).map(|&to| (ch, to)))
+            .collect()
+    }
+
+    /// Home-row anchor keys, left pinky to right pinky
+    pub fn home_row_anchors(&self) -> &[char] {
+        &self.anchors
+    }
+
+    /// Base-level letters a-z in the order a touch-typing course introduces
+    /// them: the home-row anchors, the rest of the home row, then the reach
+    /// keys by finger strength, top row before bottom row before number row.
+    pub fn letter_order(&self) -> Vec<char> {
+        let mut letters: Vec<(char, &KeyLookup)> = self
+            .keys
+            .iter()
+            .filter(|(ch, key)| key.level == 0 && ch.is_ascii_lowercase())
+            .map(|(&ch, key)| (ch, key))
+            .collect();
+
+        letters.sort_by_key(|&(ch, key)| {
+            let group = if self.anchors.contains(&ch) {
+                0
+            } else if key.position.row == HOME_ROW {
+                1
+            } else {
+                2
+            };
+            (
+                group,
+                finger_strength(key.position.finger),
+                reach_rank(key.position.row),
+                key.key_index,
+            )
+        });
+
+        letters.into_iter().map(|(ch, _)| ch).collect()
+    }
+
+    /// Every key with its base-level character, row by row from the number
+    /// row, left to right, ending with the space bar
+    pub fn base_keys(&self) -> Vec<(char, KeyPosition)> {
+        let mut keys: Vec<(char, &KeyLookup)> = self
+            .keys
+            .iter()
+            .filter(|(_, key)| key.level == 0)
+            .map(|(&ch, key)| (ch, key))
+            .collect();
+        keys.sort_by_key(|&(_, key)| key.key_index);
+        keys.into_iter().map(|(ch, key)| (ch, key.position)).collect()
+    }
+
+    /// Base-level character of the key that types `ch`, e.g. `a` for `A`
+    pub fn base_char(&self, ch: char) -> Option<char> {
+        let key_index = self.key(ch)?.key_index;
+        self.keys
+            .iter()
+            .find(|(_, key)| key.level == 0 && key.key_index == key_index)
+            .map(|(&base, _)| base)
+    }
+
+    /// Key used to type `ch`; base-level placements win over shifted ones
+    pub fn key(&self, ch: char) -> Option<&KeyLookup> {
+        self.keys.get(&ch)
+    }
+
+    /// Touch-typing finger assigned to the key that types `ch`
+    pub fn finger(&self, ch: char) -> Option<Finger> {
+        self.key(ch).map(|lookup| lookup.position.finger)
+    }
+
+    /// Hand that types `ch`; `None` for the space bar and unknown characters
+    pub fn hand(&self, ch: char) -> Option<Hand> {
+        self.finger(ch).and_then(|finger| finger.hand())
+    }
+
+    /// Keyboard row of `ch`, see [`KeyPosition::row`]
+    pub fn row(&self, ch: char) -> Option<u8> {
+        self.key(ch).map(|lookup| lookup.position.row)
+    }
+
+    /// Classify typing `actual` where `expected` was wanted. Adjacent-key
+    /// slips also report which finger drifted and in which direction.
+    pub fn classify_substitution(
+        &self,
+        expected: char,
+        actual: char,
+    ) -> (SubstitutionKind, Option<(Finger, DriftDirection)>) {
+        self.classify_struck_key(expected, actual, self.key(actual))
+    }
+
+    /// Like `classify_substitution`, with the key that typed `actual` given
+    /// directly; it may belong to another layout on the same board, such as
+    /// the physical layout while this one is emulated
+    pub fn classify_struck_key(
+        &self,
+        expected: char,
+        actual: char,
+        struck: Option<&KeyLookup>,
+    ) -> (SubstitutionKind, Option<(Finger, DriftDirection)>) {
+        if expected != actual
+            && expected.is_alphabetic()
+            && actual.is_alphabetic()
+            && expected.to_lowercase().eq(actual.to_lowercase())
+        {
+            return (SubstitutionKind::Case, None);
+        }
+
+        let (Some(wanted), Some(struck)) = (self.key(expected), struck) else {
+            return (SubstitutionKind::Unclassified, None);
+        };
+
+        if wanted.position.same_key(&struck.position) {
+            return (SubstitutionKind::Shift, None);
+        }
+
+        let (from, to) = (wanted.position, struck.position);
+
+        if Self::is_adjacent(&from, &to) {
+            let direction = if to.row < from.row {
+                DriftDirection::Up
+            } else if to.row > from.row {
+                DriftDirection::Down
+            } else if to.x < from.x {
+                DriftDirection::Left
+            } else {
+                DriftDirection::Right
+            };
+            return (SubstitutionKind::AdjacentKey, Some((from.finger, direction)));
+        }
+
+        if from.finger == to.finger {
+            return (SubstitutionKind::SameFinger, None);
+        }
+
+        if from.row == to.row && from.finger.hand().is_some() && to.finger == from.finger.mirror()
+        {
+            return (SubstitutionKind::WrongHand, None);
+        }
+
+        (SubstitutionKind::Unclassified, None)
+    }
+
+    fn is_adjacent(a: &KeyPosition, b: &KeyPosition) -> bool {
+        if a.row == SPACE_ROW || b.row == SPACE_ROW {
+            return false;
+        }
+
+        let dx = (a.x - b.x).abs();
+        match a.row.abs_diff(b.row) {
+            0 => dx > 0.5 && dx < 1.5,
+            1 => dx < 1.0,
+            _ => false,
+        }
+    }
+
+    /// Place every key of `definition` on its board and check that the
+    /// rows fit and the home-row anchors sit under the right fingers
+    pub fn from_definition(definition: &LayoutDefinition) -> Result<Self> {
+        let (bottom_offset, bottom_fingers): (f32, &[Finger]) = match definition.form_factor {
+            FormFactor::Ansi => (ANSI_BOTTOM_ROW_OFFSET, &ISO_BOTTOM_ROW_FINGERS[1..]),
+            FormFactor::Iso => (ISO_BOTTOM_ROW_OFFSET, &ISO_BOTTOM_ROW_FINGERS),
+        };
+        let row_specs: [(f32, &[Finger]); 4] = [
+            (NUMBER_ROW_OFFSET, &NUMBER_ROW_FINGERS),
+            (TOP_ROW_OFFSET, &TOP_ROW_FINGERS),
+            (HOME_ROW_OFFSET, &HOME_ROW_FINGERS),
+            (bottom_offset, bottom_fingers),
+        ];
+
+        // Per row: key positions, then each shift level's characters
+        let mut rows = Vec::with_capacity(row_specs.len());
+        for (row, (levels, (offset, fingers))) in
+            definition.rows.all().into_iter().zip(row_specs).enumerate()
+        {
+            let levels: Vec<Vec<char>> = levels.iter().map(|l| l.chars().collect()).collect();
+            let key_count = levels.first().map_or(0, Vec::len);
+            if key_count > fingers.len() {
+                return Err(CentotypeError::Config(format!(
+                    "Layout '{}' row {} has {} keys, the {:?} board fits {}",
+                    definition.id,
+                    row,
+                    key_count,
+                    definition.form_factor,
+                    fingers.len()
+                )));
+            }
+
+            let positions: Vec<KeyPosition> = (0..key_count)
+                .map(|column| KeyPosition {
+                    row: row as u8,
+                    x: offset + column as f32 + 0.5,
+                    finger: fingers[column],
+                })
+                .collect();
+            rows.push((positions, levels));
+        }
+
+        let mut keys = HashMap::new();
+        for level in 0..MAX_SHIFT_LEVELS {
+            let mut key_index = 0;
+            for (positions, levels) in &rows {
+                for (column, position) in positions.iter().enumerate() {
+                    let ch = levels.get(level).and_then(|chars| chars.get(column));
+                    if let Some(&ch) = ch.filter(|&&ch| ch != EMPTY_SLOT) {
+                        keys.entry(ch).or_insert(KeyLookup {
+                            position: *position,
+                            level: level as u8,
+                            key_index,
+                        });
+                    }
+                    key_index += 1;
+                }
+            }
+        }
+
+        let space_index = rows.iter().map(|(positions, _)| positions.len()).sum();
+        keys.insert(
+            ' ',
+            KeyLookup {
+                position: KeyPosition {
+                    row: SPACE_ROW,
+                    x: 7.5,
+                    finger: Thumb,
+                },
+                level: 0,
+                key_index: space_index,
+            },
+        );
+
+        let geometry = Self {
+            id: definition.id.clone(),
+            name: definition.name.clone(),
+            keys,
+            anchors: definition.home_row.chars().collect(),
+        };
+        geometry.check_home_row(definition)?;
+        Ok(geometry)
+    }
+
+    fn check_home_row(&self, definition: &LayoutDefinition) -> Result<()> {
+        let anchors: Vec<char> = definition.home_row.chars().collect();
+        if anchors.len() != ANCHOR_FINGERS.len() {
+            return Err(CentotypeError::Config(format!(
+                "Layout '{}' home_row must list {} keys, found {}",
+                definition.id,
+                ANCHOR_FINGERS.len(),
+                anchors.len()
+            )));
+        }
+
+        for (anchor, finger) in anchors.into_iter().zip(ANCHOR_FINGERS) {
+            let placed = self.key(anchor).filter(|key| {
+                key.level == 0 && key.position.row == HOME_ROW && key.position.finger == finger
+            });
+            if placed.is_none() {
+                return Err(CentotypeError::Config(format!(
+                    "Layout '{}' home row key '{}' is not on the home row under the {}",
+                    definition.id,
+                    anchor,
+                    finger.name()
+                )));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn classify(layout: KeyboardLayout, expected: char, actual: char) -> SubstitutionKind {
+        KeyboardGeometry::for_layout(layout)
+            .classify_substitution(expected, actual)
+            .0
+    }
+
+    #[test]
+    fn test_qwerty_taxonomy() {
+        use SubstitutionKind::*;
+        let layout = KeyboardLayout::Qwerty;
+
+        assert_eq!(classify(layout, 'a', 'A'), Case);
+        assert_eq!(classify(layout, '1', '!'), Shift);
+        assert_eq!(classify(layout, ';', ':'), Shift);
+        assert_eq!(classify(layout, 'f', 'g'), AdjacentKey);
+        assert_eq!(classify(layout, 'f', 'r'), AdjacentKey);
+        assert_eq!(classify(layout, 'f', 'v'), AdjacentKey);
+        assert_eq!(classify(layout, 'e', 'c'), SameFinger);
+        assert_eq!(classify(layout, 'f', 'j'), WrongHand);
+        assert_eq!(classify(layout, 'd', 'k'), WrongHand);
+        assert_eq!(classify(layout, 'a', 'p'), Unclassified);
+        assert_eq!(classify(layout, 'a', '€'), Unclassified);
+    }
+
+    #[test]
+    fn test_drift_direction() {
+        let geometry = KeyboardGeometry::for_layout(KeyboardLayout::Qwerty);
+
+        // Right ring finger reaching up from `l` to `o`
+        let (_, drift) = geometry.classify_substitution('l', 'o');
+        assert_eq!(drift, Some((RightRing, DriftDirection::Up)));
+
+        let (_, drift) = geometry.classify_substitution('s', 'x');
+        assert_eq!(drift, Some((LeftRing, DriftDirection::Down)));
+
+        let (_, drift) = geometry.classify_substitution('j', 'h');
+        assert_eq!(drift, Some((RightIndex, DriftDirection::Left)));
+    }
+
+    #[test]
+    fn test_iso_layouts() {
+        use SubstitutionKind::*;
+
+        // QWERTZ swaps y and z; z sits above h on the top row
+        assert_eq!(classify(KeyboardLayout::Qwertz, 'z', 'h'), AdjacentKey);
+        assert_eq!(classify(KeyboardLayout::Qwertz, 'y', 'x'), AdjacentKey);
+        assert_eq!(classify(KeyboardLayout::Qwertz, '7', '/'), Shift);
+
+        // AZERTY digits are shifted symbols
+        assert_eq!(classify(KeyboardLayout::Azerty, '1', '&'), Shift);
+        assert_eq!(classify(KeyboardLayout::Azerty, 'a', 'z'), AdjacentKey);
+        assert_eq!(classify(KeyboardLayout::Azerty, 'q', 'm'), WrongHand);
+
+        let azerty = KeyboardGeometry::for_layout(KeyboardLayout::Azerty);
+        assert_eq!(azerty.key('m').unwrap().position.finger, RightPinky);
+        assert!(azerty.key('1').unwrap().shifted());
+    }
+
+    fn assert_finger_map(layout: KeyboardLayout, rows: [(&str, &str); 3]) {
+        let geometry = KeyboardGeometry::for_layout(layout);
+        let home_row_fingers = [
+            LeftPinky, LeftRing, LeftMiddle, LeftIndex, LeftIndex, RightIndex, RightIndex,
+            RightMiddle, RightRing, RightPinky,
+        ];
+
+        for (row_index, (left, right)) in (1u8..).zip(rows) {
+            let letters: Vec<char> = left.chars().chain(right.chars()).collect();
+            assert_eq!(letters.len(), 10, "{:?} row {}", layout, row_index);
+
+            for (ch, finger) in letters.into_iter().zip(home_row_fingers) {
+                assert_eq!(geometry.finger(ch), Some(finger), "{:?} '{}'", layout, ch);
+                assert_eq!(geometry.row(ch), Some(row_index), "{:?} '{}'", layout, ch);
+                assert_eq!(geometry.finger(ch.to_ascii_uppercase()), Some(finger));
+            }
+            for ch in left.chars() {
+                assert_eq!(geometry.hand(ch), Some(Hand::Left), "{:?} '{}'", layout, ch);
+            }
+            for ch in right.chars() {
+                assert_eq!(geometry.hand(ch), Some(Hand::Right), "{:?} '{}'", layout, ch);
+            }
+        }
+
+        for ch in 'a'..='z' {
+            assert!(geometry.key(ch).is_some(), "{:?} missing '{}'", layout, ch);
+        }
+        assert_eq!(geometry.finger(' '), Some(Thumb));
+        assert_eq!(geometry.hand(' '), None);
+    }
+
+    #[test]
+    fn test_qwerty_finger_map() {
+        assert_finger_map(
+            KeyboardLayout::Qwerty,
+            [("qwert", "yuiop"), ("asdfg", "hjkl;"), ("zxcvb", "nm,./")],
+        );
+
+        let geometry = KeyboardGeometry::for_layout(KeyboardLayout::Qwerty);
+        assert_eq!(geometry.finger('1'), Some(LeftPinky));
+        assert_eq!(geometry.finger('6'), Some(RightIndex));
+        assert_eq!(geometry.finger('\''), Some(RightPinky));
+        assert_eq!(geometry.row('5'), Some(0));
+    }
+
+    #[test]
+    fn test_qwertz_finger_map() {
+        assert_finger_map(
+            KeyboardLayout::Qwertz,
+            [("qwert", "zuiop"), ("asdfg", "hjklö"), ("yxcvb", "nm,.-")],
+        );
+
+        let geometry = KeyboardGeometry::for_layout(KeyboardLayout::Qwertz);
+        assert_eq!(geometry.finger('ü'), Some(RightPinky));
+        assert_eq!(geometry.finger('ä'), Some(RightPinky));
+        assert_eq!(geometry.finger('<'), Some(LeftPinky));
+        assert_eq!(geometry.finger('ß'), Some(RightPinky));
+    }
+
+    #[test]
+    fn test_azerty_finger_map() {
+        assert_finger_map(
+            KeyboardLayout::Azerty,
+            [("azert", "yuiop"), ("qsdfg", "hjklm"), ("wxcvb", "n,;:!")],
+        );
+
+        let geometry = KeyboardGeometry::for_layout(KeyboardLayout::Azerty);
+        assert_eq!(geometry.finger('ù'), Some(RightPinky));
+        assert_eq!(geometry.finger('é'), Some(LeftRing));
+        assert_eq!(geometry.finger('2'), Some(LeftRing));
+    }
+
+    #[test]
+    fn test_remap_between_layouts() {
+        let qwerty = KeyboardGeometry::for_layout(KeyboardLayout::Qwerty);
+
+        let colemak = qwerty.remap_to(KeyboardGeometry::for_layout(KeyboardLayout::Colemak));
+        assert_eq!(colemak[&'e'], 'f');
+        assert_eq!(colemak[&'k'], 'e');
+        assert_eq!(colemak[&'E'], 'F');
+        assert_eq!(colemak[&';'], 'o');
+        assert_eq!(colemak[&' '], ' ');
+        assert_eq!(colemak[&'1'], '1');
+
+        let dvorak = qwerty.remap_to(KeyboardGeometry::for_layout(KeyboardLayout::Dvorak));
+        assert_eq!(dvorak[&'q'], '\'');
+        assert_eq!(dvorak[&'z'], ';');
+        assert_eq!(dvorak[&'-'], '[');
+
+        // ANSI bottom-row letters line up with ISO ones past the extra key
+        let qwertz = qwerty.remap_to(KeyboardGeometry::for_layout(KeyboardLayout::Qwertz));
+        assert_eq!(qwertz[&'z'], 'y');
+        assert_eq!(qwertz[&'y'], 'z');
+    }
+
+    #[test]
+    fn test_letter_order() {
+        let order = |layout| -> String {
+            KeyboardGeometry::for_layout(layout)
+                .letter_order()
+                .into_iter()
+                .collect()
+        };
+
+        let qwerty = order(KeyboardLayout::Qwerty);
+        assert_eq!(qwerty.len(), 26);
+        assert!(qwerty.starts_with("fjdksla"));
+        assert!(qwerty[7..].starts_with("gh"));
+        assert!(qwerty.ends_with("qpz"));
+
+        // Every layout starts on its own home row
+        assert!(order(KeyboardLayout::Azerty).starts_with("fjdkslqm"));
+        assert!(order(KeyboardLayout::Dvorak).starts_with("uhetonas"));
+        assert!(order(KeyboardLayout::Colemak).starts_with("tnseriaodh"));
+        for layout in KeyboardLayout::ALL {
+            let mut letters: Vec<char> = order(layout).chars().collect();
+            letters.sort_unstable();
+            assert_eq!(letters, ('a'..='z').collect::<Vec<_>>(), "{:?}", layout);
+        }
+    }
+
+    #[test]
+    fn test_base_keys() {
+        let qwerty = KeyboardGeometry::for_layout(KeyboardLayout::Qwerty);
+        let keys = qwerty.base_keys();
+        let row = |row: u8| -> String {
+            keys.iter()
+                .filter(|(_, position)| position.row == row)
+                .map(|&(ch, _)| ch)
+                .collect()
+        };
+        assert_eq!(row(0), "`1234567890-=");
+        assert_eq!(row(2), "asdfghjkl;'");
+        assert_eq!(row(4), " ");
+        assert!(keys.windows(2).all(|pair| pair[0].1.row <= pair[1].1.row));
+
+        assert_eq!(qwerty.base_char('A'), Some('a'));
+        assert_eq!(qwerty.base_char('?'), Some('/'));
+        assert_eq!(qwerty.base_char('q'), Some('q'));
+        assert_eq!(qwerty.base_char('é'), None);
+    }
+}
@@ -0,0 +1,427 @@
+//! Data-driven keyboard layout definitions
+//!
+//! Built-in layouts ship as TOML data files in `core/layouts/`. Users can add
+//! their own layouts, or replace a built-in one by reusing its `id`, by placing
+//! files in the same format in the `layouts/` folder of the config directory.
+use crate::keyboard::KeyboardGeometry;
+use crate::types::*;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::Path;
+use std::sync::Arc;
+use tracing::{debug, warn};
+
+/// Marks a key with no character on a shift level
+pub const EMPTY_SLOT: char = ' ';
+
+/// Shift levels a row may define: base, Shift, AltGr and Shift+AltGr
+pub const MAX_SHIFT_LEVELS: usize = 4;
+
+/// Letter keys per row, from the left pinky column to the right pinky column
+const LETTER_BLOCK_WIDTH: usize = 10;
+
+const BUILTIN_LAYOUT_FILES: [(KeyboardLayout, &str); 7] = [
+    (KeyboardLayout::Qwerty, include_str!("../layouts/qwerty.toml")),
+    (KeyboardLayout::Qwertz, include_str!("../layouts/qwertz.toml")),
+    (KeyboardLayout::Azerty, include_str!("../layouts/azerty.toml")),
+    (KeyboardLayout::Dvorak, include_str!("../layouts/dvorak.toml")),
+    (KeyboardLayout::Colemak, include_str!("../layouts/colemak.toml")),
+    (KeyboardLayout::ColemakDh, include_str!("../layouts/colemak-dh.toml")),
+    (KeyboardLayout::Workman, include_str!("../layouts/workman.toml")),
+];
+
+static BUILTIN_LAYOUTS: Lazy<HashMap<KeyboardLayout, LayoutDefinition>> = Lazy::new(|| {
+    BUILTIN_LAYOUT_FILES
+        .iter()
+        .map(|(layout, source)| {
+            let definition = LayoutDefinition::from_toml(source)
+                .unwrap_or_else(|e| panic!("built-in layout {:?} is invalid: {}", layout, e));
+            (*layout, definition)
+        })
+        .collect()
+});
+
+/// Physical board the layout is drawn on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FormFactor {
+    /// US-style board with a wide left Shift
+    #[default]
+    Ansi,
+    /// European board with an extra key left of the bottom-row letters
+    Iso,
+}
+
+/// Key rows from the number row down, one string per shift level
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LayoutRows {
+    pub number: Vec<String>,
+    pub top: Vec<String>,
+    pub home: Vec<String>,
+    pub bottom: Vec<String>,
+}
+
+impl LayoutRows {
+    /// Rows in keyboard order, number row first
+    pub fn all(&self) -> [&[String]; 4] {
+        [&self.number, &self.top, &self.home, &self.bottom]
+    }
+}
+
+/// Key positions, shift levels and home row of one keyboard layout
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LayoutDefinition {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub form_factor: FormFactor,
+    /// Resting keys from the left pinky to the right pinky
+    pub home_row: String,
+    pub rows: LayoutRows,
+}
+
+/// Group of keys introduced together in a home-row-first curriculum
+#[derive(Debug, Clone, PartialEq)]
+pub struct CurriculumStage {
+    pub name: &'static str,
+    /// Keys new to this stage, in keyboard order
+    pub keys: Vec<char>,
+}
+
+impl LayoutDefinition {
+    /// Definition of a built-in layout as shipped, ignoring user files; the
+    /// rest of the application looks layouts up in a `LayoutRegistry`
+    pub(crate) fn builtin(layout: KeyboardLayout) -> &'static LayoutDefinition {
+        &BUILTIN_LAYOUTS[&layout]
+    }
+
+    /// Parse and validate a layout data file
+    pub fn from_toml(source: &str) -> Result<Self> {
+        let definition: LayoutDefinition = toml::from_str(source)
+            .map_err(|e| CentotypeError::Config(format!("Failed to parse layout: {}", e)))?;
+        definition.validate()?;
+        Ok(definition)
+    }
+
+    /// Check the parts of the definition that do not need key geometry;
+    /// row lengths and home-row fingers are checked when the geometry is built
+    pub fn validate(&self) -> Result<()> {
+        if self.id.trim().is_empty() {
+            return Err(CentotypeError::Config("Layout id must not be empty".to_string()));
+        }
+
+        for (row_name, levels) in ["number", "top", "home", "bottom"]
+            .iter()
+            .zip(self.rows.all())
+        {
+            if levels.is_empty() || levels.len() > MAX_SHIFT_LEVELS {
+                return Err(CentotypeError::Config(format!(
+                    "Layout '{}' {} row must have 1 to {} shift levels, found {}",
+                    self.id,
+                    row_name,
+                    MAX_SHIFT_LEVELS,
+                    levels.len()
+                )));
+            }
+
+            let base_len = levels[0].chars().count();
+            if let Some(level) = levels.iter().position(|l| l.chars().count() > base_len) {
+                return Err(CentotypeError::Config(format!(
+                    "Layout '{}' {} row has more keys on shift level {} than on the base level",
+                    self.id, row_name, level
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Base-level characters of a row, `None` for empty slots
+    fn base_keys(levels: &[String]) -> Vec<Option<char>> {
+        levels
+            .first()
+            .map(|base| {
+                base.chars()
+                    .map(|ch| (ch != EMPTY_SLOT).then_some(ch))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Split the layout into stages that start on the home row and move
+    /// outwards, ending with the shifted characters. Every key appears in
+    /// exactly one stage; AltGr levels are left out.
+    pub fn curriculum_stages(&self) -> Vec<CurriculumStage> {
+        let [number, top, home, bottom] = self.rows.all().map(Self::base_keys);
+        let letter_block = |keys: &[Option<char>], skip: usize| -> Vec<char> {
+            keys.iter()
+                .skip(skip)
+                .take(LETTER_BLOCK_WIDTH)
+                .flatten()
+                .copied()
+                .collect()
+        };
+        let bottom_skip = match self.form_factor {
+            FormFactor::Ansi => 0,
+            FormFactor::Iso => 1,
+        };
+
+        let remaining: Vec<char> = [&number, &top, &home, &bottom]
+            .into_iter()
+            .flat_map(|keys| keys.iter().flatten().copied())
+            .collect();
+        let shifted: Vec<char> = self
+            .rows
+            .all()
+            .iter()
+            .filter_map(|levels| levels.get(1))
+            .flat_map(|level| level.chars())
+            .filter(|&ch| ch != EMPTY_SLOT)
+            .collect();
+
+        let candidates = [
+            ("Home row anchors", self.home_row.chars().collect()),
+            ("Home row", letter_block(&home, 0)),
+            ("Top row", letter_block(&top, 0)),
+            ("Bottom row", letter_block(&bottom, bottom_skip)),
+            ("Number row", letter_block(&number, 1)),
+            ("Remaining keys", remaining),
+            ("Shifted keys", shifted),
+        ];
+
+        let mut seen = HashSet::new();
+        candidates
+            .into_iter()
+            .map(|(name, keys): (&'static str, Vec<char>)| CurriculumStage {
+                name,
+                keys: keys.into_iter().filter(|&ch| seen.insert(ch)).collect(),
+            })
+            .filter(|stage| !stage.keys.is_empty())
+            .collect()
+    }
+}
+
+/// A validated layout together with its key geometry
+#[derive(Debug, Clone)]
+struct RegisteredLayout {
+    definition: Arc<LayoutDefinition>,
+    geometry: Arc<KeyboardGeometry>,
+}
+
+/// Every layout available to the application, keyed by id
+#[derive(Debug, Clone)]
+pub struct LayoutRegistry {
+    layouts: BTreeMap<String, RegisteredLayout>,
+}
+
+impl LayoutRegistry {
+    /// Registry holding the built-in layouts
+    pub fn new() -> Self {
+        let layouts = KeyboardLayout::ALL
+            .iter()
+            .map(|&layout| {
+                let registered = RegisteredLayout {
+                    definition: Arc::new(LayoutDefinition::builtin(layout).clone()),
+                    geometry: KeyboardGeometry::shared(layout),
+                };
+                (layout.id().to_string(), registered)
+            })
+            .collect();
+
+        Self { layouts }
+    }
+
+    /// Add a layout, replacing any layout with the same id
+    pub fn register(&mut self, definition: LayoutDefinition) -> Result<()> {
+        definition.validate()?;
+        let geometry = KeyboardGeometry::from_definition(&definition)?;
+
+        self.layouts.insert(
+            definition.id.clone(),
+            RegisteredLayout {
+                definition: Arc::new(definition),
+                geometry: Arc::new(geometry),
+            },
+        );
+        Ok(())
+    }
+
+    /// Load every `*.toml` file in `dir`. Invalid files are skipped with a
+    /// warning so one broken layout does not hide the others. Returns the ids
+    /// that were loaded; a missing directory loads nothing.
+    pub fn load_dir(&mut self, dir: &Path) -> Result<Vec<String>> {
+        if !dir.is_dir() {
+            return Ok(Vec::new());
+        }
+
+        let mut paths: Vec<_> = std::fs::read_dir(dir)?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == "toml"))
+            .collect();
+        paths.sort();
+
+        let mut loaded = Vec::new();
+        for path in paths {
+            let result = std::fs::read_to_string(&path)
+                .map_err(CentotypeError::from)
+                .and_then(|source| LayoutDefinition::from_toml(&source))
+                .and_then(|definition| {
+                    let id = definition.id.clone();
+                    self.register(definition).map(|_| id)
+                });
+
+            match result {
+                Ok(id) => {
+                    debug!(id = %id, path = %path.display(), "Loaded user layout");
+                    loaded.push(id);
+                }
+                Err(e) => warn!(path = %path.display(), "Skipping invalid layout: {}", e),
+            }
+        }
+
+        Ok(loaded)
+    }
+
+    pub fn get(&self, id: &str) -> Option<&LayoutDefinition> {
+        self.layouts.get(id).map(|layout| layout.definition.as_ref())
+    }
+
+    pub fn geometry(&self, id: &str) -> Option<Arc<KeyboardGeometry>> {
+        self.layouts.get(id).map(|layout| Arc::clone(&layout.geometry))
+    }
+
+    /// Registered layout ids in alphabetical order
+    pub fn ids(&self) -> impl Iterator<Item = &str> {
+        self.layouts.keys().map(String::as_str)
+    }
+}
+
+impl Default for LayoutRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CUSTOM_LAYOUT: &str = r#"
+id = "qwerty-swapped"
+name = "QWERTY with swapped semicolon"
+home_row = "asdfjkl:"
+
+[rows]
+number = ["`1234567890-=", "~!@#$%^&*()_+"]
+top = ["qwertyuiop[]\\", "QWERTYUIOP{}|"]
+home = ["asdfghjkl:'", "ASDFGHJKL;\""]
+bottom = ["zxcvbnm,./", "ZXCVBNM<>?"]
+"#;
+
+    #[test]
+    fn test_builtin_layouts_load() {
+        let registry = LayoutRegistry::new();
+
+        for layout in KeyboardLayout::ALL {
+            let definition = registry.get(layout.id()).unwrap();
+            assert_eq!(definition.id, layout.id());
+            assert_eq!(definition.home_row.chars().count(), 8);
+
+            let geometry = registry.geometry(layout.id()).unwrap();
+            for ch in 'a'..='z' {
+                assert!(geometry.key(ch).is_some(), "{} missing '{}'", layout.id(), ch);
+            }
+        }
+    }
+
+    #[test]
+    fn test_curriculum_starts_on_home_row() {
+        let colemak = LayoutDefinition::builtin(KeyboardLayout::Colemak);
+        let stages = colemak.curriculum_stages();
+
+        assert_eq!(stages[0].name, "Home row anchors");
+        assert_eq!(stages[0].keys, "arstneio".chars().collect::<Vec<_>>());
+        assert_eq!(stages[1].keys, vec!['d', 'h']);
+        assert_eq!(stages[2].keys, "qwfpgjluy;".chars().collect::<Vec<_>>());
+        assert_eq!(stages[3].keys, "zxcvbkm,./".chars().collect::<Vec<_>>());
+
+        // Every key is introduced exactly once
+        let all: Vec<char> = stages.iter().flat_map(|s| s.keys.iter().copied()).collect();
+        let unique: HashSet<char> = all.iter().copied().collect();
+        assert_eq!(all.len(), unique.len());
+        assert!(unique.contains(&'Q') && unique.contains(&'7'));
+
+        // ISO boards skip the extra key left of the bottom-row letters
+        let qwertz = LayoutDefinition::builtin(KeyboardLayout::Qwertz);
+        let bottom = &qwertz.curriculum_stages()[3];
+        assert_eq!(bottom.keys, "yxcvbnm,.-".chars().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_user_layout_registration() {
+        let mut registry = LayoutRegistry::new();
+        let definition = LayoutDefinition::from_toml(CUSTOM_LAYOUT).unwrap();
+        assert_eq!(definition.form_factor, FormFactor::Ansi);
+
+        registry.register(definition).unwrap();
+        let geometry = registry.geometry("qwerty-swapped").unwrap();
+        assert!(!geometry.key(':').unwrap().shifted());
+        assert!(geometry.key(';').unwrap().shifted());
+        assert!(registry.ids().any(|id| id == "qwerty-swapped"));
+    }
+
+    #[test]
+    fn test_user_file_replaces_builtin_layout() {
+        let dir = std::env::temp_dir().join(format!("centotype-layouts-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let replacement = CUSTOM_LAYOUT.replace("id = \"qwerty-swapped\"", "id = \"qwerty\"");
+        std::fs::write(dir.join("qwerty.toml"), replacement).unwrap();
+
+        let mut registry = LayoutRegistry::new();
+        registry.load_dir(&dir).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(registry.get("qwerty").unwrap().name, "QWERTY with swapped semicolon");
+        let geometry = registry.geometry("qwerty").unwrap();
+        assert!(!geometry.key(':').unwrap().shifted());
+        assert!(KeyboardGeometry::for_layout(KeyboardLayout::Qwerty).key(':').unwrap().shifted());
+    }
+
+    #[test]
+    fn test_invalid_layouts_are_rejected() {
+        // Home row anchors must sit under the matching fingers
+        let wrong_anchor = CUSTOM_LAYOUT.replace("home_row = \"asdfjkl:\"", "home_row = \"qsdfjkl:\"");
+        let definition = LayoutDefinition::from_toml(&wrong_anchor).unwrap();
+        assert!(LayoutRegistry::new().register(definition).is_err());
+
+        let too_many_levels = CUSTOM_LAYOUT.replace(
+            "bottom = [\"zxcvbnm,./\", \"ZXCVBNM<>?\"]",
+            "bottom = [\"zxcvbnm,./\", \"a\", \"b\", \"c\", \"d\"]",
+        );
+        assert!(LayoutDefinition::from_toml(&too_many_levels).is_err());
+
+        let too_long = CUSTOM_LAYOUT.replace("zxcvbnm,./", "zxcvbnm,./§");
+        let definition = LayoutDefinition::from_toml(&too_long).unwrap();
+        assert!(KeyboardGeometry::from_definition(&definition).is_err());
+
+        assert!(LayoutDefinition::from_toml("id = \"broken\"").is_err());
+    }
+
+    #[test]
+    fn test_load_dir_skips_invalid_files() {
+        let dir = std::env::temp_dir().join(format!("centotype-layouts-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("custom.toml"), CUSTOM_LAYOUT).unwrap();
+        std::fs::write(dir.join("broken.toml"), "id = \"broken\"").unwrap();
+        std::fs::write(dir.join("notes.txt"), "not a layout").unwrap();
+
+        let mut registry = LayoutRegistry::new();
+        let loaded = registry.load_dir(&dir).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(loaded, vec!["qwerty-swapped".to_string()]);
+        assert!(registry.get("broken").is_none());
+        assert!(registry.load_dir(&dir).unwrap().is_empty());
+    }
+}
@@ -71,6 +71,9 @@ impl Level {
 
     /// Check if Level 100 mastery criteria is met
     pub fn is_level_100_mastered(&self, result: &SessionResult) -> bool {
+        if result.unverified {
+            return false;
+        }
         if let TrainingMode::Arcade { level } = result.mode {
             if level.0 == 100 {
                 return self.meets_mastery_criteria(&result.metrics);
@@ -178,7 +181,7 @@ impl Level {
                 let suggestion = PracticeSuggestion {
                     level: *level_id,
                     reason: SuggestionReason::LowGrade(result.grade),
-                    target_metrics: self.calculate_target_metrics(*level_id),
+                    target_metrics: self.target_metrics(*level_id),
                 };
                 suggestions.push(suggestion);
             } else if result.metrics.accuracy < 95.0 {
@@ -186,7 +189,7 @@ impl Level {
                 let suggestion = PracticeSuggestion {
                     level: *level_id,
                     reason: SuggestionReason::LowAccuracy(result.metrics.accuracy),
-                    target_metrics: self.calculate_target_metrics(*level_id),
+                    target_metrics: self.target_metrics(*level_id),
                 };
                 suggestions.push(suggestion);
             } else if result.metrics.effective_wpm < self.get_tier_target_wpm(level_id.tier()) {
@@ -194,7 +197,7 @@ impl Level {
                 let suggestion = PracticeSuggestion {
                     level: *level_id,
                     reason: SuggestionReason::LowSpeed(result.metrics.effective_wpm),
-                    target_metrics: self.calculate_target_metrics(*level_id),
+                    target_metrics: self.target_metrics(*level_id),
                 };
                 suggestions.push(suggestion);
             }
@@ -247,7 +250,8 @@ impl Level {
         }
     }
 
-    fn calculate_target_metrics(&self, level_id: LevelId) -> TargetMetrics {
+    /// Speed, accuracy and consistency a level asks for
+    pub fn target_metrics(&self, level_id: LevelId) -> TargetMetrics {
         let tier = level_id.tier();
         let base_wpm = self.get_tier_target_wpm(tier);
 
@@ -357,6 +361,20 @@ pub struct TargetMetrics {
     pub target_consistency: f64,
 }
 
+impl TargetMetrics {
+    /// One star for each of speed, accuracy and consistency on target
+    pub fn stars(&self, metrics: &FinalMetrics) -> u8 {
+        [
+            metrics.effective_wpm >= self.target_wpm,
+            metrics.accuracy >= self.target_accuracy,
+            metrics.consistency >= self.target_consistency,
+        ]
+        .into_iter()
+        .filter(|&reached| reached)
+        .count() as u8
+    }
+}
+
 /// Static level definitions - loaded once
 static LEVEL_DEFINITIONS: Lazy<LevelDefinitions> = Lazy::new(|| {
     let mut levels = IndexMap::new();
@@ -717,6 +735,12 @@ mod tests {
                 idle_events: 0,
             },
             latency_p99: Duration::from_millis(15),
+            keystroke_accuracy: 99.0,
+            corrections: CorrectionStats::default(),
+            slowest_words: Vec::new(),
+            layout_errors: LayoutErrorStats::default(),
+            finger_usage: FingerUsageStats::default(),
+            key_timing: KeyTimingStats::default(),
         };
 
         assert!(level_manager.meets_mastery_criteria(&mastery_metrics));
@@ -729,6 +753,12 @@ mod tests {
             longest_streak: 200,
             errors: ErrorStats::default(),
             latency_p99: Duration::from_millis(30),
+            keystroke_accuracy: 95.0,
+            corrections: CorrectionStats::default(),
+            slowest_words: Vec::new(),
+            layout_errors: LayoutErrorStats::default(),
+            finger_usage: FingerUsageStats::default(),
+            key_timing: KeyTimingStats::default(),
         };
 
         assert!(!level_manager.meets_mastery_criteria(&insufficient_metrics));
@@ -24,18 +24,27 @@
 
 pub mod error;
 pub mod events;
+pub mod keyboard;
+pub mod keybindings;
+pub mod layout;
 pub mod level;
 pub mod scoring;
 pub mod session;
+pub mod settings;
 pub mod traits;
 pub mod types;
 
 // Re-export main types for convenience
 pub use error::Error as ErrorClassifier;
+pub use error::IncrementalClassifier;
 pub use events::*;
+pub use keyboard::KeyboardGeometry;
+pub use keybindings::{KeyBindings, KeyChord, SessionCommand};
+pub use layout::{LayoutDefinition, LayoutRegistry};
 pub use level::Level as LevelManager;
 pub use scoring::Scoring as ScoringEngine;
 pub use session::SessionManager;
+pub use settings::Setting;
 pub use traits::*;
 pub use types::*;
 
@@ -45,26 +54,43 @@ use std::sync::Arc;
 /// Core engine that coordinates all components
 pub struct CentotypeCore {
     session_manager: Arc<RwLock<SessionManager>>,
-    scoring_engine: Arc<ScoringEngine>,
+    scoring_engine: Arc<RwLock<ScoringEngine>>,
     level_manager: Arc<LevelManager>,
     error_classifier: Arc<ErrorClassifier>,
+    live_classifier: Arc<RwLock<Option<IncrementalClassifier>>>,
 }
 
 impl CentotypeCore {
     pub fn new() -> Self {
+        Self::with_layout(KeyboardLayout::Qwerty)
+    }
+
+    /// Create a core whose scoring classifies errors against `layout`
+    pub fn with_layout(layout: KeyboardLayout) -> Self {
+        Self::with_geometry(KeyboardGeometry::shared(layout))
+    }
+
+    /// Create a core for any registered layout, including user layouts
+    pub fn with_geometry(geometry: Arc<KeyboardGeometry>) -> Self {
         Self {
             session_manager: Arc::new(RwLock::new(SessionManager::new())),
-            scoring_engine: Arc::new(ScoringEngine::new()),
+            scoring_engine: Arc::new(RwLock::new(ScoringEngine::with_geometry(geometry))),
             level_manager: Arc::new(LevelManager::new()),
             error_classifier: Arc::new(ErrorClassifier::new()),
+            live_classifier: Arc::new(RwLock::new(None)),
         }
     }
 
+    /// Layout the user practises
+    pub fn geometry(&self) -> Arc<KeyboardGeometry> {
+        self.scoring_engine.read().geometry()
+    }
+
     pub fn session_manager(&self) -> Arc<RwLock<SessionManager>> {
         Arc::clone(&self.session_manager)
     }
 
-    pub fn scoring_engine(&self) -> Arc<ScoringEngine> {
+    pub fn scoring_engine(&self) -> Arc<RwLock<ScoringEngine>> {
         Arc::clone(&self.scoring_engine)
     }
 
@@ -79,6 +105,7 @@ impl CentotypeCore {
     /// Start a new typing session - stub implementation
     pub fn start_session(&self, mode: TrainingMode, target_text: String) -> Result<uuid::Uuid> {
         let session_id = uuid::Uuid::new_v4();
+        *self.live_classifier.write() = Some(IncrementalClassifier::new(&target_text));
         let session_state = SessionState {
             session_id,
             mode,
@@ -96,45 +123,122 @@ impl CentotypeCore {
         Ok(session_id)
     }
 
-    /// Add a keystroke to an active session
+    /// Add a keystroke to the session with the given id, which must be the
+    /// current one
     pub fn add_keystroke(&self, session_id: uuid::Uuid, keystroke: Keystroke) -> Result<()> {
-        // TODO: Implement proper keystroke handling
-        // For now, just validate that the session exists
-        let _ = session_id; // Prevent unused warning
-        let _ = keystroke; // Prevent unused warning
-        Ok(())
+        let mut session_manager = self.session_manager.write();
+        if session_manager.current_state()?.session_id != session_id {
+            return Err(CentotypeError::State(format!(
+                "Session {} is not the current session",
+                session_id
+            )));
+        }
+
+        self.process_keystroke(keystroke.char_typed, keystroke.is_correction)?;
+        session_manager.update_state(StateUpdate::AddKeystroke(keystroke))
     }
 
-    /// Process a keystroke - stub implementation
+    /// Process a keystroke, updating live error classification incrementally
     pub fn process_keystroke(
         &self,
-        _char_typed: Option<char>,
-        _is_correction: bool,
+        char_typed: Option<char>,
+        is_correction: bool,
     ) -> Result<LiveMetrics> {
-        Ok(LiveMetrics::default())
+        let mut live_classifier = self.live_classifier.write();
+        let classifier = live_classifier
+            .as_mut()
+            .ok_or_else(|| CentotypeError::State("No active session".to_string()))?;
+
+        match char_typed {
+            Some(ch) if !is_correction => classifier.push_char(ch),
+            _ => {
+                classifier.backspace();
+            }
+        }
+
+        let analysis = classifier.analysis();
+        Ok(LiveMetrics {
+            accuracy: analysis.accuracy,
+            errors: analysis.stats,
+            ..LiveMetrics::default()
+        })
     }
 
-    /// Complete the current session - stub implementation
-    pub fn complete_session(&self) -> Result<SessionResult> {
-        let session_id = uuid::Uuid::new_v4();
-        let level = LevelId::new(1)?;
+    /// Live metrics of the current session: speed and streaks from the
+    /// session state, accuracy and errors from the live classification
+    pub fn live_metrics(&self) -> Result<LiveMetrics> {
+        let session = self.session_manager.read().current_state()?;
+        let mut metrics = self.scoring_engine.write().calculate_live_metrics(&session)?;
+
+        if let Some(classifier) = self.live_classifier.read().as_ref() {
+            let analysis = classifier.analysis();
+            metrics.accuracy = analysis.accuracy;
+            metrics.effective_wpm = metrics.raw_wpm * analysis.accuracy / 100.0;
+            metrics.errors = ErrorStats {
+                backspace_count: metrics.errors.backspace_count,
+                ..analysis.stats
+            };
+        }
+        Ok(metrics)
+    }
+
+    /// Skip the rest of the current word, typing the whitespace that ends
+    /// it; the skipped characters score as missed. Returns the text to
+    /// record as typed, or `None` within the last word.
+    pub fn skip_word(&self) -> Result<Option<String>> {
+        let mut live_classifier = self.live_classifier.write();
+        let classifier = live_classifier
+            .as_mut()
+            .ok_or_else(|| CentotypeError::State("No active session".to_string()))?;
+        Ok(classifier.skip_word())
+    }
+
+    /// Errors in the text typed so far, positioned against the target
+    pub fn error_analysis(&self) -> Result<error::ErrorAnalysis> {
+        self.live_classifier
+            .read()
+            .as_ref()
+            .map(IncrementalClassifier::analysis)
+            .ok_or_else(|| CentotypeError::State("No active session".to_string()))
+    }
+
+    /// Complete the current session and score it against the tier of the
+    /// level it was played at. `unverified` keeps the result off the records,
+    /// for input the caller found to be pasted or injected.
+    pub fn complete_session(&self, unverified: bool) -> Result<SessionResult> {
+        let session = self.session_manager.write().complete_current_session()?;
+        self.score_session(&session, unverified)
+    }
+
+    /// Score a session a termination signal cut short, saved to be recorded
+    /// on the next start. The input checks of the run it came from are gone,
+    /// so it is unverified.
+    pub fn score_interrupted_session(&self, session: &SessionState) -> Result<SessionResult> {
+        let session = SessionState {
+            is_completed: true,
+            ..session.clone()
+        };
+        self.score_session(&session, true)
+    }
+
+    fn score_session(&self, session: &SessionState, unverified: bool) -> Result<SessionResult> {
+        let metrics = self.scoring_engine.write().calculate_final_metrics(session)?;
+
+        let level = session.mode.difficulty_level();
+        let tier = level.tier();
+        let skill_index = self.scoring_engine.read().calculate_skill_index(&metrics, tier);
+        let stars = self.level_manager.target_metrics(level).stars(&metrics);
+
         Ok(SessionResult {
-            session_id,
-            mode: TrainingMode::Arcade { level },
+            session_id: session.session_id,
+            mode: session.mode,
             completed_at: chrono::Utc::now(),
-            duration_seconds: 60.0,
-            metrics: FinalMetrics {
-                raw_wpm: 50.0,
-                effective_wpm: 45.0,
-                accuracy: 90.0,
-                consistency: 85.0,
-                longest_streak: 100,
-                errors: ErrorStats::default(),
-                latency_p99: std::time::Duration::from_millis(20),
-            },
-            skill_index: 700.0,
-            grade: Grade::B,
-            stars: 2,
+            duration_seconds: ScoringEngine::typing_duration(session).as_secs_f64(),
+            metrics,
+            skill_index,
+            grade: Grade::from_skill_index(skill_index, tier),
+            stars,
+            unverified,
         })
     }
 }
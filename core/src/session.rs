@@ -1,6 +1,6 @@
 //! Session management with thread-safe state tracking and performance monitoring
 use crate::types::*;
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use parking_lot::{Mutex, RwLock};
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -12,6 +12,8 @@ pub struct SessionManager {
     current_session: Arc<RwLock<Option<SessionState>>>,
     sessions: Arc<Mutex<HashMap<uuid::Uuid, SessionState>>>,
     performance_tracker: Arc<Mutex<SessionPerformanceTracker>>,
+    /// When the current session was paused, to add to its paused duration
+    paused_since: Arc<Mutex<Option<Instant>>>,
 }
 
 impl SessionManager {
@@ -20,6 +22,7 @@ impl SessionManager {
             current_session: Arc::new(RwLock::new(None)),
             sessions: Arc::new(Mutex::new(HashMap::new())),
             performance_tracker: Arc::new(Mutex::new(SessionPerformanceTracker::new())),
+            paused_since: Arc::new(Mutex::new(None)),
         }
     }
 
@@ -42,6 +45,7 @@ impl SessionManager {
         session_state.is_completed = false;
         session_state.started_at = Utc::now();
         session_state.paused_duration = Duration::default();
+        *self.paused_since.lock() = None;
 
         let session_id = session_state.session_id;
 
@@ -103,6 +107,9 @@ impl SessionManager {
             StateUpdate::AddKeystroke(keystroke) => {
                 self.apply_keystroke(session, keystroke)?;
             }
+            StateUpdate::KeyReleased { key, released_at } => {
+                Self::apply_key_release(session, key, released_at);
+            }
             StateUpdate::SetPaused(paused) => {
                 self.set_paused_state(session, paused)?;
             }
@@ -191,11 +198,42 @@ impl SessionManager {
             }
         }
 
+        // A released previous key now has its flight time
+        if let Some(previous) = session.keystrokes.last_mut() {
+            if let (Some(dwell), None) = (previous.dwell, previous.flight) {
+                let gap = (keystroke.timestamp - previous.timestamp)
+                    .to_std()
+                    .unwrap_or_default();
+                previous.flight = Some(gap.saturating_sub(dwell));
+            }
+        }
+
         // Record keystroke
         session.keystrokes.push(keystroke);
         Ok(())
     }
 
+    /// Fill in the dwell time of the latest press of `key`. When the next key
+    /// went down before this release, the flight time is zero.
+    fn apply_key_release(session: &mut SessionState, key: char, released_at: DateTime<Utc>) {
+        // Shift may be let go first, so the release can differ in case
+        let same_key = |ch: char| ch.to_lowercase().eq(key.to_lowercase());
+        let Some(index) = session.keystrokes.iter().rposition(|k| {
+            k.dwell.is_none() && !k.is_correction && k.char_typed.is_some_and(same_key)
+        }) else {
+            debug!("Release of '{}' without a matching press", key);
+            return;
+        };
+
+        let pressed_at = session.keystrokes[index].timestamp;
+        let next_press = session.keystrokes.get(index + 1).map(|k| k.timestamp);
+        let keystroke = &mut session.keystrokes[index];
+        keystroke.dwell = Some((released_at - pressed_at).to_std().unwrap_or_default());
+        if let Some(next_press) = next_press {
+            keystroke.flight = Some((next_press - released_at).to_std().unwrap_or_default());
+        }
+    }
+
     fn handle_regular_input(&self, session: &mut SessionState, ch: char) -> Result<()> {
         // Validate cursor position
         if session.cursor_position > session.target_text.len() {
@@ -257,11 +295,16 @@ impl SessionManager {
             return Ok(()); // No change needed
         }
 
+        // The clock stops while paused
         if paused {
             session.is_paused = true;
+            *self.paused_since.lock() = Some(Instant::now());
             debug!("Session paused");
         } else {
             session.is_paused = false;
+            if let Some(since) = self.paused_since.lock().take() {
+                session.paused_duration += since.elapsed();
+            }
             debug!("Session resumed");
         }
 
@@ -478,6 +521,9 @@ mod tests {
             char_typed: Some('t'),
             is_correction: false,
             cursor_pos: 0,
+            physical_char: None,
+            dwell: None,
+            flight: None,
         };
 
         assert!(manager
@@ -488,4 +534,84 @@ mod tests {
         assert_eq!(current.typed_text, "t");
         assert_eq!(current.cursor_position, 1);
     }
+
+    #[test]
+    fn test_key_release_timing() {
+        let mut manager = SessionManager::new();
+        let session_state = SessionState {
+            session_id: uuid::Uuid::new_v4(),
+            mode: TrainingMode::Arcade {
+                level: LevelId::new(1).unwrap(),
+            },
+            target_text: "te".to_string(),
+            typed_text: String::new(),
+            cursor_position: 0,
+            started_at: Utc::now(),
+            paused_duration: Duration::default(),
+            is_paused: false,
+            is_completed: false,
+            keystrokes: Vec::new(),
+        };
+        manager.start_session(session_state).unwrap();
+
+        let start = Utc::now();
+        let at = |ms| start + chrono::Duration::milliseconds(ms);
+        let keystroke = |ch, ms, pos| Keystroke {
+            timestamp: at(ms),
+            char_typed: Some(ch),
+            is_correction: false,
+            cursor_pos: pos,
+            physical_char: None,
+            dwell: None,
+            flight: None,
+        };
+
+        // Shift let go first makes the second release come back in upper case
+        let updates = [
+            StateUpdate::AddKeystroke(keystroke('t', 0, 0)),
+            StateUpdate::KeyReleased { key: 't', released_at: at(80) },
+            StateUpdate::AddKeystroke(keystroke('e', 200, 1)),
+            StateUpdate::KeyReleased { key: 'E', released_at: at(290) },
+        ];
+        for update in updates {
+            manager.update_state(update).unwrap();
+        }
+
+        let keystrokes = manager.current_state().unwrap().keystrokes;
+        assert_eq!(keystrokes[0].dwell, Some(Duration::from_millis(80)));
+        assert_eq!(keystrokes[0].flight, Some(Duration::from_millis(120)));
+        assert_eq!(keystrokes[1].dwell, Some(Duration::from_millis(90)));
+        // Nothing followed the last key yet
+        assert_eq!(keystrokes[1].flight, None);
+    }
+
+    #[test]
+    fn test_pause_stops_clock() {
+        let mut manager = SessionManager::new();
+        let session_state = SessionState {
+            session_id: uuid::Uuid::new_v4(),
+            mode: TrainingMode::Arcade {
+                level: LevelId::new(1).unwrap(),
+            },
+            target_text: "hello world".to_string(),
+            typed_text: String::new(),
+            cursor_position: 0,
+            started_at: Utc::now(),
+            paused_duration: Duration::default(),
+            is_paused: false,
+            is_completed: false,
+            keystrokes: Vec::new(),
+        };
+        manager.start_session(session_state).unwrap();
+
+        manager.update_state(StateUpdate::SetPaused(true)).unwrap();
+        std::thread::sleep(Duration::from_millis(20));
+        // Pausing twice doesn't restart the pause
+        manager.update_state(StateUpdate::SetPaused(true)).unwrap();
+        manager.update_state(StateUpdate::SetPaused(false)).unwrap();
+
+        let state = manager.current_state().unwrap();
+        assert!(!state.is_paused);
+        assert!(state.paused_duration >= Duration::from_millis(20));
+    }
 }
@@ -0,0 +1,352 @@
+//! Named access to every config setting
+//!
+//! Settings are addressed by their name in the config file, `layout` or
+//! `keybindings.quit`, and read and written as strings so the settings form
+//! and `centotype config get|set|reset` share one set of parsing and
+//! validation rules.
+use crate::keybindings::{KeyBindings, KeyChord, SessionCommand};
+use crate::types::*;
+use std::fmt;
+use std::str::FromStr;
+
+/// Log levels accepted by `log_level`, quietest first
+pub const LOG_LEVELS: [&str; 5] = ["error", "warn", "info", "debug", "trace"];
+
+/// Value of an optional setting that is not set
+pub const UNSET: &str = "none";
+
+/// A setting in the config file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Setting {
+    Layout,
+    CustomLayout,
+    PhysicalLayout,
+    KeyTiming,
+    Language,
+    Theme,
+    CustomTheme,
+    TextDisplay,
+    SoundEnabled,
+    TelemetryEnabled,
+    LogLevel,
+    KeyBinding(SessionCommand),
+}
+
+/// Values a setting accepts
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SettingKind {
+    /// One of a fixed list
+    Choice(Vec<&'static str>),
+    /// `true` or `false`
+    Toggle,
+    /// Free text checked when set
+    Text,
+}
+
+impl Setting {
+    /// Every setting, in config file order
+    pub fn all() -> Vec<Setting> {
+        let mut settings = vec![
+            Setting::Layout,
+            Setting::CustomLayout,
+            Setting::PhysicalLayout,
+            Setting::KeyTiming,
+            Setting::Language,
+            Setting::Theme,
+            Setting::CustomTheme,
+            Setting::TextDisplay,
+            Setting::SoundEnabled,
+            Setting::TelemetryEnabled,
+            Setting::LogLevel,
+        ];
+        settings.extend(SessionCommand::ALL.map(Setting::KeyBinding));
+        settings
+    }
+
+    /// Name of the setting in the config file
+    pub fn name(&self) -> String {
+        match self {
+            Setting::Layout => "layout".to_string(),
+            Setting::CustomLayout => "custom_layout".to_string(),
+            Setting::PhysicalLayout => "physical_layout".to_string(),
+            Setting::KeyTiming => "key_timing".to_string(),
+            Setting::Language => "language".to_string(),
+            Setting::Theme => "theme".to_string(),
+            Setting::CustomTheme => "custom_theme".to_string(),
+            Setting::TextDisplay => "text_display".to_string(),
+            Setting::SoundEnabled => "sound_enabled".to_string(),
+            Setting::TelemetryEnabled => "telemetry_enabled".to_string(),
+            Setting::LogLevel => "log_level".to_string(),
+            Setting::KeyBinding(command) => format!("keybindings.{}", command.config_key()),
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Setting::Layout => "Keyboard layout to practise",
+            Setting::CustomLayout => "User layout from the layouts folder, used instead of layout",
+            Setting::PhysicalLayout => "Layout the operating system is set to, when it differs",
+            Setting::KeyTiming => "Measure dwell and flight times where the terminal allows",
+            Setting::Language => "Language of the practice text",
+            Setting::Theme => "Colour theme",
+            Setting::CustomTheme => "User theme from the themes folder, used instead of theme",
+            Setting::TextDisplay => "How the text to type is laid out",
+            Setting::SoundEnabled => "Sound alerts",
+            Setting::TelemetryEnabled => "Share anonymous usage statistics",
+            Setting::LogLevel => "Detail of the log output",
+            Setting::KeyBinding(command) => match command {
+                SessionCommand::Restart => "Keys that restart the session",
+                SessionCommand::PauseResume => "Keys that pause and resume",
+                SessionCommand::Quit => "Keys that end the session",
+                SessionCommand::Help => "Keys that show the help overlay",
+                SessionCommand::SkipWord => "Keys that skip the rest of the word",
+                SessionCommand::ToggleMetrics => "Keys that show or hide live metrics",
+                SessionCommand::ToggleChart => "Keys that show or hide the live chart",
+                SessionCommand::ToggleKeyboard => "Keys that show or hide the keyboard heatmap",
+            },
+        }
+    }
+
+    pub fn kind(&self) -> SettingKind {
+        match self {
+            Setting::Layout => SettingKind::Choice(KeyboardLayout::ALL.map(|l| l.id()).to_vec()),
+            Setting::PhysicalLayout => {
+                let mut choices = vec![UNSET];
+                choices.extend(KeyboardLayout::ALL.map(|l| l.id()));
+                SettingKind::Choice(choices)
+            }
+            Setting::Language => SettingKind::Choice(Language::ALL.map(|l| l.id()).to_vec()),
+            Setting::Theme => SettingKind::Choice(Theme::ALL.map(|t| t.id()).to_vec()),
+            Setting::TextDisplay => SettingKind::Choice(TextDisplay::ALL.map(|d| d.id()).to_vec()),
+            Setting::LogLevel => SettingKind::Choice(LOG_LEVELS.to_vec()),
+            Setting::KeyTiming | Setting::SoundEnabled | Setting::TelemetryEnabled => {
+                SettingKind::Toggle
+            }
+            Setting::CustomLayout | Setting::CustomTheme | Setting::KeyBinding(_) => {
+                SettingKind::Text
+            }
+        }
+    }
+}
+
+impl fmt::Display for Setting {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for Setting {
+    type Err = CentotypeError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let name = s.trim().to_ascii_lowercase();
+        Setting::all()
+            .into_iter()
+            .find(|setting| setting.name() == name)
+            .ok_or_else(|| {
+                let names: Vec<String> = Setting::all().iter().map(Setting::name).collect();
+                CentotypeError::Config(format!(
+                    "Unknown setting '{}'; expected one of: {}",
+                    s.trim(),
+                    names.join(", ")
+                ))
+            })
+    }
+}
+
+/// Pick the option whose id is `value`, or explain which ids are allowed
+fn parse_choice<T: Copy>(
+    setting: Setting,
+    value: &str,
+    options: &[T],
+    id: impl Fn(&T) -> &'static str,
+) -> Result<T> {
+    let value = value.trim().to_ascii_lowercase();
+    options
+        .iter()
+        .find(|option| id(option) == value)
+        .copied()
+        .ok_or_else(|| {
+            let ids: Vec<&str> = options.iter().map(&id).collect();
+            CentotypeError::Config(format!(
+                "Invalid value '{}' for {}; expected one of: {}",
+                value,
+                setting,
+                ids.join(", ")
+            ))
+        })
+}
+
+fn parse_toggle(setting: Setting, value: &str) -> Result<bool> {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "true" | "on" | "yes" | "1" => Ok(true),
+        "false" | "off" | "no" | "0" => Ok(false),
+        other => Err(CentotypeError::Config(format!(
+            "Invalid value '{}' for {}; expected true or false",
+            other, setting
+        ))),
+    }
+}
+
+/// An id, or `None` for an empty value or `none`
+fn parse_optional_id(value: &str) -> Option<String> {
+    let value = value.trim();
+    (!value.is_empty() && !value.eq_ignore_ascii_case(UNSET)).then(|| value.to_string())
+}
+
+impl Config {
+    /// Current value of a setting as it is written to `set`. Optional
+    /// settings that are not set read as `none`; a command without keys
+    /// reads as an empty string.
+    pub fn get(&self, setting: Setting) -> String {
+        match setting {
+            Setting::Layout => self.layout.id().to_string(),
+            Setting::CustomLayout => self.custom_layout.clone().unwrap_or(UNSET.to_string()),
+            Setting::PhysicalLayout => self.physical_layout.map_or(UNSET, |l| l.id()).to_string(),
+            Setting::KeyTiming => self.key_timing.to_string(),
+            Setting::Language => self.language.id().to_string(),
+            Setting::Theme => self.theme.id().to_string(),
+            Setting::CustomTheme => self.custom_theme.clone().unwrap_or(UNSET.to_string()),
+            Setting::TextDisplay => self.text_display.id().to_string(),
+            Setting::SoundEnabled => self.sound_enabled.to_string(),
+            Setting::TelemetryEnabled => self.telemetry_enabled.to_string(),
+            Setting::LogLevel => self.log_level.clone(),
+            Setting::KeyBinding(command) => self
+                .keybindings
+                .keys(command)
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(" "),
+        }
+    }
+
+    /// Parse and apply a value. Nothing changes when the value is invalid.
+    /// Key bindings are a space-separated list of keys such as
+    /// `ctrl+c esc`, and must leave quit bound and no key on two commands.
+    pub fn set(&mut self, setting: Setting, value: &str) -> Result<()> {
+        match setting {
+            Setting::Layout => {
+                self.layout = parse_choice(setting, value, &KeyboardLayout::ALL, |l| l.id())?
+            }
+            Setting::CustomLayout => self.custom_layout = parse_optional_id(value),
+            Setting::PhysicalLayout => {
+                self.physical_layout = match parse_optional_id(value) {
+                    Some(id) => Some(parse_choice(setting, &id, &KeyboardLayout::ALL, |l| {
+                        l.id()
+                    })?),
+                    None => None,
+                }
+            }
+            Setting::KeyTiming => self.key_timing = parse_toggle(setting, value)?,
+            Setting::Language => {
+                self.language = parse_choice(setting, value, &Language::ALL, |l| l.id())?
+            }
+            Setting::Theme => self.theme = parse_choice(setting, value, &Theme::ALL, |t| t.id())?,
+            Setting::CustomTheme => self.custom_theme = parse_optional_id(value),
+            Setting::TextDisplay => {
+                self.text_display = parse_choice(setting, value, &TextDisplay::ALL, |d| d.id())?
+            }
+            Setting::SoundEnabled => self.sound_enabled = parse_toggle(setting, value)?,
+            Setting::TelemetryEnabled => self.telemetry_enabled = parse_toggle(setting, value)?,
+            Setting::LogLevel => {
+                self.log_level = parse_choice(setting, value, &LOG_LEVELS, |l| l)?.to_string()
+            }
+            Setting::KeyBinding(command) => {
+                let keys = value
+                    .split_whitespace()
+                    .map(KeyChord::from_str)
+                    .collect::<Result<Vec<_>>>()?;
+                let mut bindings = self.keybindings.clone();
+                bindings.set_keys(command, keys);
+                // Whether a key is typed depends on the mode, checked when a
+                // session starts
+                bindings.validate(|_| false)?;
+                self.keybindings = bindings;
+            }
+        }
+        Ok(())
+    }
+
+    /// Put a setting back to its default
+    pub fn reset(&mut self, setting: Setting) {
+        let defaults = Config::default();
+        match setting {
+            Setting::KeyBinding(command) => {
+                let keys = KeyBindings::default().keys(command).to_vec();
+                self.keybindings.set_keys(command, keys);
+            }
+            _ => self
+                .set(setting, &defaults.get(setting))
+                .expect("default settings are valid"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_names_round_trip() {
+        let config = Config::default();
+        for setting in Setting::all() {
+            assert_eq!(setting.name().parse::<Setting>().unwrap(), setting);
+
+            // Every default reads back as a value `set` accepts
+            let mut copy = config.clone();
+            copy.set(setting, &config.get(setting)).unwrap();
+            assert_eq!(copy.get(setting), config.get(setting));
+        }
+
+        let err = "colour".parse::<Setting>().unwrap_err().to_string();
+        assert!(err.contains("Unknown setting 'colour'"));
+        assert!(err.contains("keybindings.quit"));
+    }
+
+    #[test]
+    fn test_set_validates() {
+        let mut config = Config::default();
+
+        config.set(Setting::Layout, "Dvorak").unwrap();
+        assert_eq!(config.layout, KeyboardLayout::Dvorak);
+        let err = config.set(Setting::Layout, "bepo").unwrap_err().to_string();
+        assert!(err.contains("expected one of: qwerty, qwertz"));
+        assert_eq!(config.layout, KeyboardLayout::Dvorak);
+
+        config.set(Setting::PhysicalLayout, "colemak-dh").unwrap();
+        assert_eq!(config.physical_layout, Some(KeyboardLayout::ColemakDh));
+        config.set(Setting::PhysicalLayout, "none").unwrap();
+        assert_eq!(config.physical_layout, None);
+
+        config.set(Setting::SoundEnabled, "on").unwrap();
+        assert!(config.sound_enabled);
+        assert!(config.set(Setting::SoundEnabled, "loud").is_err());
+        assert!(config.set(Setting::LogLevel, "verbose").is_err());
+
+        config.set(Setting::CustomTheme, " solarized ").unwrap();
+        assert_eq!(config.get(Setting::CustomTheme), "solarized");
+        config.reset(Setting::CustomTheme);
+        assert_eq!(config.custom_theme, None);
+    }
+
+    #[test]
+    fn test_keybinding_settings() {
+        let mut config = Config::default();
+        let quit = Setting::KeyBinding(SessionCommand::Quit);
+        assert_eq!(config.get(quit), "ctrl+c esc");
+
+        config.set(quit, "ctrl+q").unwrap();
+        assert_eq!(config.get(quit), "ctrl+q");
+
+        // Quit must keep a key, and keys cannot serve two commands
+        assert!(config.set(quit, "").is_err());
+        let err = config.set(quit, "ctrl+p").unwrap_err().to_string();
+        assert!(err.contains("'ctrl+p' is bound to both pause and quit"));
+        assert!(config.set(quit, "ctrl+").is_err());
+        assert_eq!(config.get(quit), "ctrl+q");
+
+        config.reset(quit);
+        assert_eq!(config.keybindings, KeyBindings::default());
+    }
+}
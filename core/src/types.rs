@@ -6,6 +6,7 @@
 //! - Serializable for persistence
 //! - Thread-safe where needed for concurrent operations
 
+use crate::keybindings::KeyBindings;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::time::Duration;
@@ -68,15 +69,49 @@ pub enum TrainingMode {
     },
 }
 
+impl TrainingMode {
+    /// Level whose content and targets the mode is played at: the level
+    /// itself in arcade mode, mid-tier for drills and high for endurance
+    pub fn difficulty_level(&self) -> LevelId {
+        match self {
+            TrainingMode::Arcade { level } => *level,
+            TrainingMode::Drill { .. } => LevelId(50),
+            TrainingMode::Endurance { .. } => LevelId(75),
+        }
+    }
+}
+
 /// Drill practice categories
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum DrillCategory {
+    /// The configured layout's keys from the home row outwards, up to the
+    /// stage the arcade progress has reached
+    LayoutKeys,
     Numbers,
     Punctuation,
     Symbols,
     CamelCase,
     SnakeCase,
     Operators,
+    /// The keys a session went wrong on most; the text comes from the
+    /// results, so the category is not offered on its own
+    WeakKeys,
+    /// Words that were among the slowest in several sessions; the text
+    /// comes from the profile, so the category is not offered on its own
+    SlowWords,
+}
+
+impl DrillCategory {
+    /// Categories offered in the drill menu
+    pub const ALL: [DrillCategory; 7] = [
+        DrillCategory::LayoutKeys,
+        DrillCategory::Numbers,
+        DrillCategory::Punctuation,
+        DrillCategory::Symbols,
+        DrillCategory::CamelCase,
+        DrillCategory::SnakeCase,
+        DrillCategory::Operators,
+    ];
 }
 
 impl std::str::FromStr for DrillCategory {
@@ -84,6 +119,7 @@ impl std::str::FromStr for DrillCategory {
 
     fn from_str(s: &str) -> Result<Self> {
         match s.to_lowercase().as_str() {
+            "layout" => Ok(DrillCategory::LayoutKeys),
             "numbers" => Ok(DrillCategory::Numbers),
             "punctuation" => Ok(DrillCategory::Punctuation),
             "symbols" => Ok(DrillCategory::Symbols),
@@ -118,12 +154,116 @@ pub enum Language {
     Indonesian,
 }
 
+impl Language {
+    pub const ALL: [Language; 2] = [Language::English, Language::Indonesian];
+
+    /// Identifier of the language in settings
+    pub fn id(&self) -> &'static str {
+        match self {
+            Language::English => "english",
+            Language::Indonesian => "indonesian",
+        }
+    }
+}
+
 /// Keyboard layout
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum KeyboardLayout {
     Qwerty,
     Qwertz,
     Azerty,
+    Dvorak,
+    Colemak,
+    ColemakDh,
+    Workman,
+}
+
+impl KeyboardLayout {
+    pub const ALL: [KeyboardLayout; 7] = [
+        KeyboardLayout::Qwerty,
+        KeyboardLayout::Qwertz,
+        KeyboardLayout::Azerty,
+        KeyboardLayout::Dvorak,
+        KeyboardLayout::Colemak,
+        KeyboardLayout::ColemakDh,
+        KeyboardLayout::Workman,
+    ];
+
+    /// Identifier of the layout's data file, also used by user overrides
+    pub fn id(&self) -> &'static str {
+        match self {
+            KeyboardLayout::Qwerty => "qwerty",
+            KeyboardLayout::Qwertz => "qwertz",
+            KeyboardLayout::Azerty => "azerty",
+            KeyboardLayout::Dvorak => "dvorak",
+            KeyboardLayout::Colemak => "colemak",
+            KeyboardLayout::ColemakDh => "colemak-dh",
+            KeyboardLayout::Workman => "workman",
+        }
+    }
+}
+
+/// Hand used to press a key
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Hand {
+    Left,
+    Right,
+}
+
+/// Finger assigned to a key in standard touch typing (thumbs share the space bar)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Finger {
+    LeftPinky,
+    LeftRing,
+    LeftMiddle,
+    LeftIndex,
+    Thumb,
+    RightIndex,
+    RightMiddle,
+    RightRing,
+    RightPinky,
+}
+
+impl Finger {
+    pub fn hand(&self) -> Option<Hand> {
+        match self {
+            Finger::LeftPinky | Finger::LeftRing | Finger::LeftMiddle | Finger::LeftIndex => {
+                Some(Hand::Left)
+            }
+            Finger::Thumb => None,
+            _ => Some(Hand::Right),
+        }
+    }
+
+    /// Same finger on the opposite hand
+    pub fn mirror(&self) -> Finger {
+        match self {
+            Finger::LeftPinky => Finger::RightPinky,
+            Finger::LeftRing => Finger::RightRing,
+            Finger::LeftMiddle => Finger::RightMiddle,
+            Finger::LeftIndex => Finger::RightIndex,
+            Finger::Thumb => Finger::Thumb,
+            Finger::RightIndex => Finger::LeftIndex,
+            Finger::RightMiddle => Finger::LeftMiddle,
+            Finger::RightRing => Finger::LeftRing,
+            Finger::RightPinky => Finger::LeftPinky,
+        }
+    }
+
+    /// Human-readable name, e.g. "right ring finger"
+    pub fn name(&self) -> &'static str {
+        match self {
+            Finger::LeftPinky => "left pinky",
+            Finger::LeftRing => "left ring finger",
+            Finger::LeftMiddle => "left middle finger",
+            Finger::LeftIndex => "left index finger",
+            Finger::Thumb => "thumb",
+            Finger::RightIndex => "right index finger",
+            Finger::RightMiddle => "right middle finger",
+            Finger::RightRing => "right ring finger",
+            Finger::RightPinky => "right pinky",
+        }
+    }
 }
 
 // ============================================================================
@@ -152,6 +292,18 @@ pub struct Keystroke {
     pub char_typed: Option<char>,
     pub is_correction: bool,
     pub cursor_pos: usize,
+    /// Character of the key actually pressed when another layout is being
+    /// emulated; `char_typed` then holds the emulated layout's character
+    #[serde(default)]
+    pub physical_char: Option<char>,
+    /// Time the key was held down; only measured on terminals that report
+    /// key releases
+    #[serde(default)]
+    pub dwell: Option<Duration>,
+    /// Time from releasing this key to pressing the next one, zero when the
+    /// next key went down first
+    #[serde(default)]
+    pub flight: Option<Duration>,
 }
 
 /// Real-time performance metrics during a session
@@ -187,6 +339,214 @@ impl ErrorStats {
     }
 }
 
+/// Keystroke-level correction accounting, replayed from the keystroke stream
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CorrectionStats {
+    /// Every recorded keystroke, including backspaces
+    pub total_keystrokes: u32,
+    /// Character keystrokes that did not match the target when pressed
+    pub wrong_keystrokes: u32,
+    /// Wrong keystrokes that were later removed with backspace
+    pub corrected_errors: u32,
+    /// Wrong keystrokes still present in the final text
+    pub uncorrected_errors: u32,
+    /// Keystrokes that did not end up as correct characters in the final text
+    pub wasted_keystrokes: u32,
+    /// Mean time between a wrong keystroke and the backspace that removed it
+    pub avg_correction_latency: Duration,
+}
+
+impl CorrectionStats {
+    /// Share of all keystrokes that were wasted (0.0-1.0)
+    pub fn wasted_keystroke_ratio(&self) -> f64 {
+        if self.total_keystrokes == 0 {
+            0.0
+        } else {
+            self.wasted_keystrokes as f64 / self.total_keystrokes as f64
+        }
+    }
+}
+
+/// Layout-aware classification of a substituted character
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum SubstitutionKind {
+    /// Neighbouring key on the same or an adjacent row
+    AdjacentKey,
+    /// Non-adjacent key struck by the same finger
+    SameFinger,
+    /// Mirrored key pressed by the opposite hand (e.g. `f` for `j`)
+    WrongHand,
+    /// Right key, wrong shift state (`1` vs `!`)
+    Shift,
+    /// Right letter, wrong case
+    Case,
+    /// No layout relationship, or a character outside the layout
+    Unclassified,
+}
+
+/// Direction a finger drifted from the intended key
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum DriftDirection {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+/// Adjacent-key slips of one finger in one direction
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FingerDrift {
+    pub finger: Finger,
+    pub direction: DriftDirection,
+    pub count: u32,
+}
+
+/// Substitution errors broken down by keyboard layout relationship
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LayoutErrorStats {
+    pub adjacent_key: u32,
+    pub same_finger: u32,
+    pub wrong_hand: u32,
+    pub shift: u32,
+    pub case: u32,
+    pub unclassified: u32,
+    pub finger_drift: Vec<FingerDrift>,
+}
+
+impl LayoutErrorStats {
+    pub fn record(&mut self, kind: SubstitutionKind, drift: Option<(Finger, DriftDirection)>) {
+        match kind {
+            SubstitutionKind::AdjacentKey => self.adjacent_key += 1,
+            SubstitutionKind::SameFinger => self.same_finger += 1,
+            SubstitutionKind::WrongHand => self.wrong_hand += 1,
+            SubstitutionKind::Shift => self.shift += 1,
+            SubstitutionKind::Case => self.case += 1,
+            SubstitutionKind::Unclassified => self.unclassified += 1,
+        }
+
+        if let Some((finger, direction)) = drift {
+            match self
+                .finger_drift
+                .iter_mut()
+                .find(|d| d.finger == finger && d.direction == direction)
+            {
+                Some(existing) => existing.count += 1,
+                None => self.finger_drift.push(FingerDrift {
+                    finger,
+                    direction,
+                    count: 1,
+                }),
+            }
+        }
+    }
+
+    pub fn total(&self) -> u32 {
+        self.adjacent_key + self.same_finger + self.wrong_hand + self.shift + self.case
+            + self.unclassified
+    }
+
+    /// Most frequent finger drift, if any
+    pub fn dominant_drift(&self) -> Option<&FingerDrift> {
+        self.finger_drift
+            .iter()
+            .max_by(|a, b| a.count.cmp(&b.count).then(b.finger.cmp(&a.finger)))
+    }
+}
+
+/// Speed and accuracy of the keys assigned to one finger
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FingerStats {
+    pub finger: Finger,
+    /// Character keystrokes whose target key belongs to this finger
+    pub keystrokes: u32,
+    /// Of those, keystrokes that did not match the target
+    pub errors: u32,
+    /// Mean time from the previous keystroke
+    pub average_interval: Duration,
+}
+
+impl FingerStats {
+    /// Share of this finger's keystrokes that were wrong (0.0-1.0)
+    pub fn error_rate(&self) -> f64 {
+        if self.keystrokes == 0 {
+            0.0
+        } else {
+            self.errors as f64 / self.keystrokes as f64
+        }
+    }
+}
+
+/// Finger and hand usage replayed from the keystroke stream
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FingerUsageStats {
+    /// One entry per finger that typed at least one key, in finger order
+    pub fingers: Vec<FingerStats>,
+    /// Consecutive pairs of keystrokes that both landed on known keys
+    pub bigrams: u32,
+    /// Of those, pairs typed on two different keys with the same finger
+    pub same_finger_bigrams: u32,
+    pub left_hand_keystrokes: u32,
+    pub right_hand_keystrokes: u32,
+}
+
+impl FingerUsageStats {
+    pub fn finger(&self, finger: Finger) -> Option<&FingerStats> {
+        self.fingers.iter().find(|stats| stats.finger == finger)
+    }
+
+    /// Share of bigrams typed with one finger on two keys (0.0-1.0)
+    pub fn same_finger_bigram_rate(&self) -> f64 {
+        if self.bigrams == 0 {
+            0.0
+        } else {
+            self.same_finger_bigrams as f64 / self.bigrams as f64
+        }
+    }
+
+    /// Share of hand keystrokes typed by the left hand (0.0-1.0, 0.5 when
+    /// nothing was typed)
+    pub fn left_hand_share(&self) -> f64 {
+        let total = self.left_hand_keystrokes + self.right_hand_keystrokes;
+        if total == 0 {
+            0.5
+        } else {
+            self.left_hand_keystrokes as f64 / total as f64
+        }
+    }
+
+    /// Finger with the highest error rate among those with `min_keystrokes`
+    pub fn least_accurate_finger(&self, min_keystrokes: u32) -> Option<&FingerStats> {
+        self.fingers
+            .iter()
+            .filter(|stats| stats.keystrokes >= min_keystrokes && stats.errors > 0)
+            .max_by(|a, b| a.error_rate().total_cmp(&b.error_rate()))
+    }
+
+    /// Finger with the longest average interval among those with
+    /// `min_keystrokes`
+    pub fn slowest_finger(&self, min_keystrokes: u32) -> Option<&FingerStats> {
+        self.fingers
+            .iter()
+            .filter(|stats| stats.keystrokes >= min_keystrokes)
+            .max_by_key(|stats| stats.average_interval)
+    }
+}
+
+/// Timing breakdown for a single whitespace-delimited word of the target text
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WordTiming {
+    pub word: String,
+    /// Grapheme offset of the word within the target text
+    pub grapheme_start: usize,
+    /// Time from the preceding keystroke to the last keystroke on the word
+    pub duration: Duration,
+    pub wpm: f64,
+    /// Wrong keystrokes made inside the word, corrected or not
+    pub errors: u32,
+    /// Pause between the preceding keystroke and the word's first key
+    pub hesitation: Duration,
+}
+
 // ============================================================================
 // Scoring Types
 // ============================================================================
@@ -202,6 +562,39 @@ pub struct SessionResult {
     pub skill_index: f64,
     pub grade: Grade,
     pub stars: u8,
+    /// Pasted or injected input was seen; the result cannot set a personal
+    /// best or unlock levels
+    #[serde(default)]
+    pub unverified: bool,
+}
+
+/// Key dwell and flight times of a session; empty when the terminal does
+/// not report key releases
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct KeyTimingStats {
+    /// Keystrokes with a measured dwell time
+    pub timed_keystrokes: u32,
+    pub average_dwell: Duration,
+    pub average_flight: Duration,
+    /// Coefficient of variation of flight times; lower is a steadier rhythm
+    pub flight_variation: f64,
+    /// Keystrokes whose next key went down before they were released
+    pub rollovers: u32,
+}
+
+impl KeyTimingStats {
+    pub fn is_measured(&self) -> bool {
+        self.timed_keystrokes > 0
+    }
+
+    /// Share of timed keystrokes overlapping the next one (0.0-1.0)
+    pub fn rollover_rate(&self) -> f64 {
+        if self.timed_keystrokes == 0 {
+            0.0
+        } else {
+            self.rollovers as f64 / self.timed_keystrokes as f64
+        }
+    }
 }
 
 /// Final calculated metrics for a completed session
@@ -214,6 +607,23 @@ pub struct FinalMetrics {
     pub longest_streak: u32,
     pub errors: ErrorStats,
     pub latency_p99: Duration,
+    /// Accuracy over every character keystroke, counting fixed mistakes
+    #[serde(default)]
+    pub keystroke_accuracy: f64,
+    #[serde(default)]
+    pub corrections: CorrectionStats,
+    /// Slowest words of the session, slowest first
+    #[serde(default)]
+    pub slowest_words: Vec<WordTiming>,
+    /// Wrong keystrokes classified against the configured keyboard layout
+    #[serde(default)]
+    pub layout_errors: LayoutErrorStats,
+    /// Per-finger speed and accuracy, same-finger bigrams and hand balance
+    #[serde(default)]
+    pub finger_usage: FingerUsageStats,
+    /// Dwell and flight times, when the terminal reports key releases
+    #[serde(default)]
+    pub key_timing: KeyTimingStats,
 }
 
 /// Performance grade (S/A/B/C/D)
@@ -339,8 +749,31 @@ impl PerformanceCheck {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
     pub layout: KeyboardLayout,
+    /// Id of a user layout from the config dir's `layouts/` folder; takes
+    /// precedence over `layout` when it names a registered layout
+    #[serde(default)]
+    pub custom_layout: Option<String>,
+    /// Layout the operating system is set to. When it differs from the
+    /// practised layout, key presses are translated so a new layout can be
+    /// learned without switching the OS layout.
+    #[serde(default)]
+    pub physical_layout: Option<KeyboardLayout>,
+    /// Ask terminals that support the kitty keyboard protocol to report key
+    /// releases, so dwell and flight times can be measured
+    #[serde(default)]
+    pub key_timing: bool,
+    /// Keys for in-session commands such as quit, pause and help
+    #[serde(default)]
+    pub keybindings: KeyBindings,
     pub language: Language,
     pub theme: Theme,
+    /// Id of a user theme from the config dir's `themes/` folder; takes
+    /// precedence over `theme` when it names a loaded theme
+    #[serde(default)]
+    pub custom_theme: Option<String>,
+    /// How the text to type is laid out in the typing pane
+    #[serde(default)]
+    pub text_display: TextDisplay,
     pub sound_enabled: bool,
     pub telemetry_enabled: bool,
     pub log_level: String,
@@ -350,8 +783,14 @@ impl Default for Config {
     fn default() -> Self {
         Self {
             layout: KeyboardLayout::Qwerty,
+            custom_layout: None,
+            physical_layout: None,
+            key_timing: false,
+            keybindings: KeyBindings::default(),
             language: Language::English,
             theme: Theme::Default,
+            custom_theme: None,
+            text_display: TextDisplay::default(),
             sound_enabled: false,
             telemetry_enabled: false,
             log_level: "info".to_string(),
@@ -365,6 +804,64 @@ pub enum Theme {
     Default,
     HighContrast,
     Mono,
+    /// Colour-blind safe presets that avoid the confusable hues of each
+    /// kind of colour vision deficiency
+    Deuteranopia,
+    Protanopia,
+    Tritanopia,
+}
+
+impl Theme {
+    pub const ALL: [Theme; 6] = [
+        Theme::Default,
+        Theme::HighContrast,
+        Theme::Mono,
+        Theme::Deuteranopia,
+        Theme::Protanopia,
+        Theme::Tritanopia,
+    ];
+
+    /// Identifier of the theme, also used by user themes that replace it
+    pub fn id(&self) -> &'static str {
+        match self {
+            Theme::Default => "default",
+            Theme::HighContrast => "high-contrast",
+            Theme::Mono => "mono",
+            Theme::Deuteranopia => "deuteranopia",
+            Theme::Protanopia => "protanopia",
+            Theme::Tritanopia => "tritanopia",
+        }
+    }
+}
+
+/// Layout of the text in the typing pane
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TextDisplay {
+    /// Three wrapped lines that scroll up as lines are finished
+    #[default]
+    ThreeLine,
+    /// One line scrolling sideways under a caret fixed in the centre
+    Ticker,
+    /// The whole text with its line breaks, indentation and line numbers,
+    /// for code
+    Paragraph,
+}
+
+impl TextDisplay {
+    pub const ALL: [TextDisplay; 3] = [
+        TextDisplay::ThreeLine,
+        TextDisplay::Ticker,
+        TextDisplay::Paragraph,
+    ];
+
+    /// Identifier of the display mode in settings
+    pub fn id(&self) -> &'static str {
+        match self {
+            TextDisplay::ThreeLine => "three-line",
+            TextDisplay::Ticker => "ticker",
+            TextDisplay::Paragraph => "paragraph",
+        }
+    }
 }
 
 // ============================================================================
@@ -388,6 +885,11 @@ pub trait StateManager {
 #[derive(Debug, Clone)]
 pub enum StateUpdate {
     AddKeystroke(Keystroke),
+    /// A key went up; fills in the dwell time of its last press
+    KeyReleased {
+        key: char,
+        released_at: DateTime<Utc>,
+    },
     SetPaused(bool),
     MoveCursor(usize),
     Complete,
@@ -430,12 +932,31 @@ pub struct UserProgress {
     pub total_sessions: u32,
     pub total_time_seconds: f64,
     pub overall_skill_index: f64,
+    /// Words that showed up in per-session slowest-word lists
+    #[serde(default)]
+    pub slow_words: std::collections::HashMap<String, SlowWordStats>,
+}
+
+/// Cross-session record of a word that keeps appearing among the slowest
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SlowWordStats {
+    pub occurrences: u32,
+    pub average_wpm: f64,
+    pub total_errors: u32,
+    pub last_seen: DateTime<Utc>,
 }
 
 impl UserProgress {
+    /// Upper bound on tracked slow words; the least recently seen are dropped
+    pub const MAX_TRACKED_SLOW_WORDS: usize = 500;
+
     pub fn update_with_result(&mut self, result: SessionResult) {
         self.total_sessions += 1;
         self.total_time_seconds += result.duration_seconds;
+        if result.unverified {
+            return;
+        }
+        self.record_slow_words(&result);
 
         // Update best result for the level if this is better
         if let TrainingMode::Arcade { level } = result.mode {
@@ -454,6 +975,51 @@ impl UserProgress {
         self.recalculate_overall_skill_index();
     }
 
+    /// Words that were among the slowest in at least `min_occurrences`
+    /// sessions, most frequent first, then slowest first
+    pub fn repeatedly_slow_words(&self, min_occurrences: u32, limit: usize) -> Vec<&str> {
+        let mut words: Vec<(&String, &SlowWordStats)> = self
+            .slow_words
+            .iter()
+            .filter(|(_, stats)| stats.occurrences >= min_occurrences)
+            .collect();
+
+        words.sort_by(|a, b| {
+            b.1.occurrences
+                .cmp(&a.1.occurrences)
+                .then(a.1.average_wpm.total_cmp(&b.1.average_wpm))
+                .then(a.0.cmp(b.0))
+        });
+
+        words
+            .into_iter()
+            .take(limit)
+            .map(|(word, _)| word.as_str())
+            .collect()
+    }
+
+    fn record_slow_words(&mut self, result: &SessionResult) {
+        for timing in &result.metrics.slowest_words {
+            let stats = self.slow_words.entry(timing.word.clone()).or_default();
+            stats.occurrences += 1;
+            stats.average_wpm += (timing.wpm - stats.average_wpm) / stats.occurrences as f64;
+            stats.total_errors += timing.errors;
+            stats.last_seen = result.completed_at;
+        }
+
+        while self.slow_words.len() > Self::MAX_TRACKED_SLOW_WORDS {
+            let oldest = self
+                .slow_words
+                .iter()
+                .min_by_key(|(_, stats)| stats.last_seen)
+                .map(|(word, _)| word.clone());
+            match oldest {
+                Some(word) => self.slow_words.remove(&word),
+                None => break,
+            };
+        }
+    }
+
     fn recalculate_overall_skill_index(&mut self) {
         if self.best_results.is_empty() {
             self.overall_skill_index = 0.0;
@@ -519,6 +1085,9 @@ pub enum CentotypeError {
     #[error("State error: {0}")]
     State(String),
 
+    #[error("Interrupted by signal {0}")]
+    Interrupted(i32),
+
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
 
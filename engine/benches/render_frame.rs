@@ -0,0 +1,139 @@
+//! Frame render benchmarks for the typing screen
+//!
+//! Each frame updates the render state with one more keystroke and draws it
+//! through the cell-diffing renderer, as the typing loop does. Showing the
+//! live chart and keyboard heatmap may add at most 2ms to a frame, checked
+//! at P95 against the plain screen at the same size.
+
+use centotype_core::types::*;
+use centotype_engine::Renderer;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use ratatui::layout::Rect;
+use std::time::{Duration, Instant};
+
+/// Level 100 content length in characters
+const LEVEL_100_LENGTH: usize = 3000;
+/// Time the side panel may add to a frame
+const PANEL_FRAME_BUDGET: Duration = Duration::from_millis(2);
+/// Keystrokes typed per measured session
+const FRAMES: usize = 500;
+
+fn level_100_text() -> String {
+    "async fn process<T: Send + 'static>() -> Result<Vec<T>, Box<dyn Error>> { 0x1F3F } "
+        .chars()
+        .cycle()
+        .take(LEVEL_100_LENGTH)
+        .collect()
+}
+
+fn session(target: &str) -> SessionState {
+    SessionState {
+        session_id: uuid::Uuid::new_v4(),
+        mode: TrainingMode::Arcade {
+            level: LevelId::new(100).expect("level 100 exists"),
+        },
+        target_text: target.to_string(),
+        typed_text: String::new(),
+        cursor_position: 0,
+        started_at: chrono::Utc::now(),
+        paused_duration: Duration::ZERO,
+        is_paused: false,
+        is_completed: false,
+        keystrokes: Vec::with_capacity(FRAMES),
+    }
+}
+
+/// Type `FRAMES` characters, every 20th wrong, rendering after each one.
+/// Returns the time of every frame.
+fn type_session(renderer: &mut Renderer, target: &str, area: Rect) -> Vec<Duration> {
+    let mut state = session(target);
+    let mut frame_times = Vec::with_capacity(FRAMES);
+
+    for (i, expected) in target.chars().take(FRAMES).enumerate() {
+        let ch = if i % 20 == 19 { '#' } else { expected };
+        // 100ms apart, so the chart takes a new sample every 10 frames
+        let elapsed = Duration::from_millis(i as u64 * 100);
+        state.keystrokes.push(Keystroke {
+            timestamp: state.started_at + chrono::Duration::from_std(elapsed).expect("elapsed fits a chrono duration"),
+            char_typed: Some(ch),
+            is_correction: false,
+            cursor_pos: i,
+            physical_char: None,
+            dwell: None,
+            flight: None,
+        });
+        state.typed_text.push(ch);
+        state.cursor_position += 1;
+        let metrics = LiveMetrics {
+            effective_wpm: 60.0,
+            accuracy: 95.0,
+            elapsed_seconds: elapsed.as_secs_f64(),
+            ..LiveMetrics::default()
+        };
+
+        let start = Instant::now();
+        renderer.update_state(&state, &metrics);
+        criterion::black_box(renderer.render_diff(area));
+        frame_times.push(start.elapsed());
+    }
+
+    frame_times
+}
+
+fn p95(mut frame_times: Vec<Duration>) -> Duration {
+    frame_times.sort();
+    frame_times[(frame_times.len() - 1) * 95 / 100]
+}
+
+fn renderer(panels: bool) -> Renderer {
+    let mut renderer = Renderer::new().expect("renderer");
+    if panels {
+        renderer.toggle_chart();
+        renderer.toggle_keyboard();
+    }
+    renderer
+}
+
+fn frame_render(c: &mut Criterion) {
+    let target = level_100_text();
+    let mut group = c.benchmark_group("typing_frame");
+    group.sample_size(10);
+
+    for (width, height) in [(80, 24), (120, 40)] {
+        let area = Rect::new(0, 0, width, height);
+        let size = format!("{}x{}", width, height);
+        let plain_p95 = (0..3)
+            .map(|_| p95(type_session(&mut renderer(false), &target, area)))
+            .min()
+            .expect("three runs");
+
+        for panels in [false, true] {
+            let name = if panels { "with_panels" } else { "plain" };
+            group.bench_with_input(BenchmarkId::new(name, &size), &area, |b, &area| {
+                b.iter_custom(|iterations| {
+                    let mut total = Duration::ZERO;
+                    for _ in 0..iterations {
+                        let frame_times = type_session(&mut renderer(panels), &target, area);
+                        total += frame_times.iter().sum::<Duration>();
+
+                        let p95 = p95(frame_times);
+                        assert!(
+                            !panels || p95.saturating_sub(plain_p95) < PANEL_FRAME_BUDGET,
+                            "Side panel adds {:?} to the P95 frame at {}, over the {:?} budget",
+                            p95.saturating_sub(plain_p95),
+                            size,
+                            PANEL_FRAME_BUDGET
+                        );
+                    }
+                    // Criterion reports time per frame
+                    total / FRAMES as u32
+                })
+            });
+        }
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, frame_render);
+criterion_main!(benches);
@@ -293,6 +293,9 @@ impl Event {
                     char_typed: Some(c),
                     is_correction: false,
                     cursor_pos: 0, // This would be updated from session state
+                    physical_char: None,
+                    dwell: None,
+                    flight: None,
                 };
 
                 self.core.process_keystroke(Some(c), false)?;
@@ -309,6 +312,9 @@ impl Event {
                     char_typed: None,
                     is_correction: true,
                     cursor_pos: 0,
+                    physical_char: None,
+                    dwell: None,
+                    flight: None,
                 };
 
                 self.core.process_keystroke(None, true)?;
@@ -1,10 +1,14 @@
 //! High-performance input handling with security validation and event batching
 use centotype_core::types::*;
-use crossterm::event::{Event as CrosstermEvent, KeyCode, KeyEvent, KeyModifiers};
+use centotype_core::{KeyBindings, KeyChord, KeyboardGeometry, SessionCommand};
+use crossterm::event::{Event as CrosstermEvent, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
 use regex::Regex;
-use std::collections::VecDeque;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tracing::{debug, warn};
+use unicode_normalization::char::is_combining_mark;
+use unicode_normalization::UnicodeNormalization;
 use unicode_segmentation::UnicodeSegmentation;
 
 /// Enhanced input statistics with performance metrics
@@ -47,6 +51,63 @@ pub struct Input {
     event_batcher: EventBatcher,
     /// Performance monitoring
     performance_monitor: InputPerformanceMonitor,
+    /// Translation from the physical layout to a practised layout
+    layout_emulation: Option<LayoutEmulation>,
+    /// Grapheme cluster being assembled from typed characters
+    composer: GraphemeAssembler,
+    /// Detector for key presses arriving faster than anyone types
+    burst_detector: BurstDetector,
+    /// Whether pasted or injected input was seen since the last clear
+    synthetic_input: bool,
+    /// Keys claimed by session commands instead of being typed
+    keybindings: KeyBindings,
+}
+
+/// Translates key presses on the physical layout into the layout being
+/// practised, so a new layout can be learned without switching the OS layout
+#[derive(Debug, Clone)]
+pub struct LayoutEmulation {
+    physical: Arc<KeyboardGeometry>,
+    physical_name: String,
+    logical_name: String,
+    to_logical: HashMap<char, char>,
+    to_physical: HashMap<char, char>,
+}
+
+impl LayoutEmulation {
+    pub fn new(physical: &KeyboardGeometry, logical: &KeyboardGeometry) -> Self {
+        let to_logical = physical.remap_to(logical);
+        let to_physical = logical.remap_to(physical);
+
+        Self {
+            physical: Arc::new(physical.clone()),
+            physical_name: physical.name().to_string(),
+            logical_name: logical.name().to_string(),
+            to_logical,
+            to_physical,
+        }
+    }
+
+    /// Character the practised layout produces for a physical key press;
+    /// keys missing from either layout pass through unchanged
+    pub fn to_logical(&self, physical: char) -> char {
+        self.to_logical.get(&physical).copied().unwrap_or(physical)
+    }
+
+    /// Physical key to press for a character of the practised layout
+    pub fn physical_key_for(&self, logical: char) -> Option<char> {
+        self.to_physical.get(&logical).copied()
+    }
+
+    /// Layout of the keyboard the keys are pressed on
+    pub fn physical_geometry(&self) -> Arc<KeyboardGeometry> {
+        Arc::clone(&self.physical)
+    }
+
+    /// Short description such as "Colemak on QWERTY"
+    pub fn label(&self) -> String {
+        format!("{} on {}", self.logical_name, self.physical_name)
+    }
 }
 
 /// Event batching system for reducing system call overhead
@@ -269,21 +330,112 @@ impl Input {
             security_policy: SecurityPolicy::default(),
             event_batcher: EventBatcher::new(),
             performance_monitor: InputPerformanceMonitor::new(),
+            layout_emulation: None,
+            composer: GraphemeAssembler::default(),
+            burst_detector: BurstDetector::new(),
+            synthetic_input: false,
+            keybindings: KeyBindings::default(),
         }
     }
 
     /// Process key event with enhanced performance monitoring
     pub fn process_key_event(&mut self, key_event: KeyEvent) -> Result<ProcessedInput> {
+        // A single key press completes at most one character; a dead key
+        // completes none until the next press
+        let mut processed = self.process_key_events(&[key_event])?;
+        Ok(processed.pop().unwrap_or_else(ProcessedInput::composing))
+    }
+
+    /// Process key events that have just arrived together. Typed characters
+    /// are assembled into grapheme clusters first, so a letter followed by
+    /// combining marks, or a dead key followed by a letter, becomes one NFC
+    /// character.
+    pub fn process_key_events(&mut self, key_events: &[KeyEvent]) -> Result<Vec<ProcessedInput>> {
+        self.process_key_batch(key_events, Instant::now())
+    }
+
+    /// Process key events read together that arrived some time after
+    /// `arrived_after`, when the input was last read to the end. Keys that
+    /// queued up while the loop was busy are not mistaken for a burst.
+    pub fn process_key_batch(
+        &mut self,
+        key_events: &[KeyEvent],
+        arrived_after: Instant,
+    ) -> Result<Vec<ProcessedInput>> {
         let processing_start = Instant::now();
+        let mut processed = Vec::with_capacity(key_events.len());
+
+        for &key_event in key_events {
+            // Rate limiting check
+            if !self.rate_limiter.allow_input() {
+                warn!("Input rate limit exceeded");
+                return Err(CentotypeError::Input("Rate limit exceeded".to_string()));
+            }
+
+            // Translate to the practised layout before validation and scoring
+            let (key_event, physical_char) = self.emulate_layout(key_event);
+
+            // Releases, reported only with keyboard enhancement, time the key
+            // and never type anything
+            if key_event.kind == KeyEventKind::Release {
+                if let KeyCode::Char(key) = key_event.code {
+                    if let Some((grapheme, physical)) = self.composer.finish() {
+                        processed.push(self.validate_grapheme(&grapheme, physical));
+                    }
+                    processed.push(ProcessedInput::release(key, physical_char));
+                }
+                continue;
+            }
+
+            // Bound keys run their command and are never typed
+            if let Some(command) = self.keybindings.command_for(KeyChord::from_event(&key_event)) {
+                if let Some((grapheme, physical)) = self.composer.finish() {
+                    processed.push(self.validate_grapheme(&grapheme, physical));
+                }
+                self.composer.cancel();
+                processed.push(ProcessedInput::command(command));
+                continue;
+            }
 
-        // Rate limiting check
-        if !self.rate_limiter.allow_input() {
-            warn!("Input rate limit exceeded");
-            return Err(CentotypeError::Input("Rate limit exceeded".to_string()));
+            match key_event.code {
+                KeyCode::Char(ch) if !key_event.modifiers.contains(KeyModifiers::CONTROL) => {
+                    if let Some((grapheme, physical)) = self.composer.push(ch, physical_char) {
+                        processed.push(self.validate_grapheme(&grapheme, physical));
+                    }
+                }
+                _ => {
+                    // Any other key ends the grapheme and cancels a dead key
+                    if let Some((grapheme, physical)) = self.composer.finish() {
+                        processed.push(self.validate_grapheme(&grapheme, physical));
+                    }
+                    self.composer.cancel();
+                    processed.push(self.sanitize_and_validate(key_event)?);
+                }
+            }
         }
 
-        // Process the key event
-        let processed = self.sanitize_and_validate(key_event)?;
+        if let Some((grapheme, physical)) = self.composer.finish() {
+            processed.push(self.validate_grapheme(&grapheme, physical));
+        }
+
+        // When each key came is unknown, only that it was after the input
+        // was last drained; spread them evenly over that time. A long run
+        // arriving together is injected.
+        let typed: Vec<&mut ProcessedInput> = processed
+            .iter_mut()
+            .filter(|input| input.is_valid && matches!(input.input_type, InputType::Character(_)))
+            .collect();
+        let count = typed.len() as u32;
+        let window = processing_start.saturating_duration_since(arrived_after);
+        for (i, input) in (1..).zip(typed) {
+            if self.burst_detector.record(arrived_after + window * i / count) {
+                input.security_flags.synthetic_input = true;
+                if !self.synthetic_input {
+                    warn!("Implausibly fast burst of key presses");
+                }
+                self.synthetic_input = true;
+            }
+        }
 
         // Record processing time for monitoring
         let processing_time = processing_start.elapsed();
@@ -291,7 +443,8 @@ impl Input {
 
         debug!(
             duration_ms = %processing_time.as_millis(),
-            input_type = ?processed.input_type,
+            events = key_events.len(),
+            inputs = processed.len(),
             "Processed input"
         );
 
@@ -313,6 +466,9 @@ impl Input {
                     let processed = self.process_key_event(key_event)?;
                     processed_events.push(processed);
                 }
+                CrosstermEvent::Paste(text) => {
+                    processed_events.push(self.reject_paste(&text));
+                }
                 _ => {
                     // Handle other event types if needed
                     debug!("Ignoring non-key event: {:?}", event);
@@ -325,6 +481,35 @@ impl Input {
         Ok(processed_events)
     }
 
+    /// Reject pasted text: it never counts as typing, and the session is
+    /// flagged as unverified
+    pub fn reject_paste(&mut self, text: &str) -> ProcessedInput {
+        warn!("Rejected paste of {} characters", text.chars().count());
+        self.synthetic_input = true;
+        ProcessedInput {
+            input_type: InputType::Filtered,
+            sanitized_char: None,
+            is_valid: false,
+            security_flags: SecurityFlags {
+                synthetic_input: true,
+                ..Default::default()
+            },
+            physical_char: None,
+        }
+    }
+
+    /// Whether a paste or an implausibly fast burst was seen since the last
+    /// `clear_synthetic_input`
+    pub fn synthetic_input_detected(&self) -> bool {
+        self.synthetic_input
+    }
+
+    /// Forget earlier synthetic input, at the start of a session
+    pub fn clear_synthetic_input(&mut self) {
+        self.synthetic_input = false;
+        self.burst_detector = BurstDetector::new();
+    }
+
     /// Get comprehensive input statistics including performance metrics
     pub fn get_statistics(&self) -> EnhancedInputStatistics {
         let performance_stats = self.performance_monitor.get_performance_stats();
@@ -425,11 +610,35 @@ impl Input {
         Ok(())
     }
 
+    /// Replace the keys bound to session commands
+    pub fn set_keybindings(&mut self, keybindings: KeyBindings) {
+        self.keybindings = keybindings;
+    }
+
+    /// Check the key bindings against the characters the current training
+    /// mode needs typed
+    pub fn check_keybindings(&self) -> Result<()> {
+        self.keybindings
+            .validate(|ch| self.allowed_characters.is_allowed(ch.encode_utf8(&mut [0; 4])))
+    }
+
     /// Update allowed character set based on training mode
     pub fn set_training_mode(&mut self, mode: TrainingMode) {
         self.allowed_characters.set_mode(mode);
         debug!("Updated allowed characters for mode: {:?}", mode);
     }
+
+    /// Enable or disable layout emulation
+    pub fn set_layout_emulation(&mut self, emulation: Option<LayoutEmulation>) {
+        if let Some(emulation) = &emulation {
+            debug!("Emulating layout: {}", emulation.label());
+        }
+        self.layout_emulation = emulation;
+    }
+
+    pub fn layout_emulation(&self) -> Option<&LayoutEmulation> {
+        self.layout_emulation.as_ref()
+    }
 }
 
 impl Default for EventBatcher {
@@ -447,6 +656,88 @@ impl Default for InputPerformanceMonitor {
 impl Input {
     // Private methods
 
+    /// Remap a printable key press through the active layout emulation.
+    /// Shortcuts with Ctrl or Alt keep their physical key.
+    fn emulate_layout(&self, key_event: KeyEvent) -> (KeyEvent, Option<char>) {
+        let Some(emulation) = &self.layout_emulation else {
+            return (key_event, None);
+        };
+
+        match key_event.code {
+            KeyCode::Char(physical)
+                if !key_event
+                    .modifiers
+                    .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) =>
+            {
+                let translated = KeyEvent {
+                    code: KeyCode::Char(emulation.to_logical(physical)),
+                    ..key_event
+                };
+                (translated, Some(physical))
+            }
+            _ => (key_event, None),
+        }
+    }
+
+    /// Check an assembled grapheme against the allowlist. Scoring works per
+    /// character, so a cluster without a precomposed form is filtered.
+    fn validate_grapheme(&self, grapheme: &str, physical_char: Option<char>) -> ProcessedInput {
+        let mut chars = grapheme.chars();
+        let c = match (chars.next(), chars.next()) {
+            (Some(c), None) => c,
+            _ => {
+                debug!("No single character for grapheme {:?}", grapheme);
+                return ProcessedInput {
+                    input_type: InputType::Filtered,
+                    sanitized_char: None,
+                    is_valid: false,
+                    security_flags: SecurityFlags {
+                        disallowed_character: true,
+                        ..Default::default()
+                    },
+                    physical_char,
+                };
+            }
+        };
+
+        // Check character allowlist
+        if !self.allowed_characters.is_allowed(grapheme) {
+            return ProcessedInput {
+                input_type: InputType::Filtered,
+                sanitized_char: None,
+                is_valid: false,
+                security_flags: SecurityFlags {
+                    disallowed_character: true,
+                    ..Default::default()
+                },
+                physical_char,
+            };
+        }
+
+        // Check for control characters in disguise
+        if c.is_control() && c != '\t' && c != '\n' && c != '\r' {
+            warn!("Control character filtered: {:?}", c);
+            return ProcessedInput {
+                input_type: InputType::Filtered,
+                sanitized_char: None,
+                is_valid: false,
+                security_flags: SecurityFlags {
+                    control_character: true,
+                    ..Default::default()
+                },
+                physical_char,
+            };
+        }
+
+        ProcessedInput {
+            input_type: InputType::Character(c),
+            sanitized_char: Some(c),
+            is_valid: true,
+            security_flags: SecurityFlags::default(),
+            physical_char,
+        }
+    }
+
     fn sanitize_and_validate(&mut self, key_event: KeyEvent) -> Result<ProcessedInput> {
         // Handle special key combinations first
         if key_event.modifiers.contains(KeyModifiers::CONTROL) {
@@ -455,59 +746,26 @@ impl Input {
                 sanitized_char: None,
                 is_valid: true,
                 security_flags: SecurityFlags::default(),
+                physical_char: None,
             });
         }
 
         // Handle special keys
         match key_event.code {
-            KeyCode::Char(c) => {
-                let char_str = c.to_string();
-
-                // Check character allowlist
-                if !self.allowed_characters.is_allowed(&char_str) {
-                    return Ok(ProcessedInput {
-                        input_type: InputType::Filtered,
-                        sanitized_char: None,
-                        is_valid: false,
-                        security_flags: SecurityFlags {
-                            disallowed_character: true,
-                            ..Default::default()
-                        },
-                    });
-                }
-
-                // Check for control characters in disguise
-                if c.is_control() && c != '\t' && c != '\n' && c != '\r' {
-                    warn!("Control character filtered: {:?}", c);
-                    return Ok(ProcessedInput {
-                        input_type: InputType::Filtered,
-                        sanitized_char: None,
-                        is_valid: false,
-                        security_flags: SecurityFlags {
-                            control_character: true,
-                            ..Default::default()
-                        },
-                    });
-                }
-
-                Ok(ProcessedInput {
-                    input_type: InputType::Character(c),
-                    sanitized_char: Some(c),
-                    is_valid: true,
-                    security_flags: SecurityFlags::default(),
-                })
-            }
+            KeyCode::Char(c) => Ok(self.validate_grapheme(&c.to_string(), None)),
             KeyCode::Backspace => Ok(ProcessedInput {
                 input_type: InputType::Backspace,
                 sanitized_char: None,
                 is_valid: true,
                 security_flags: SecurityFlags::default(),
+                physical_char: None,
             }),
             KeyCode::Enter => Ok(ProcessedInput {
                 input_type: InputType::Enter,
                 sanitized_char: None,
                 is_valid: true,
                 security_flags: SecurityFlags::default(),
+                physical_char: None,
             }),
             KeyCode::Tab => {
                 if self.allowed_characters.allows_tab() {
@@ -516,6 +774,7 @@ impl Input {
                         sanitized_char: Some('\t'),
                         is_valid: true,
                         security_flags: SecurityFlags::default(),
+                        physical_char: None,
                     })
                 } else {
                     Ok(ProcessedInput {
@@ -523,6 +782,7 @@ impl Input {
                         sanitized_char: None,
                         is_valid: false,
                         security_flags: SecurityFlags::default(),
+                        physical_char: None,
                     })
                 }
             }
@@ -531,12 +791,14 @@ impl Input {
                 sanitized_char: None,
                 is_valid: true,
                 security_flags: SecurityFlags::default(),
+                physical_char: None,
             }),
             _ => Ok(ProcessedInput {
                 input_type: InputType::Other(key_event),
                 sanitized_char: None,
                 is_valid: false,
                 security_flags: SecurityFlags::default(),
+                physical_char: None,
             }),
         }
     }
@@ -619,13 +881,15 @@ impl AllowedCharacters {
 
     fn set_mode(&mut self, mode: TrainingMode) {
         self.mode = Some(mode);
+        self.custom_allowed.clear();
 
         match mode {
             TrainingMode::Arcade { level } => {
                 let tier = level.tier();
                 match tier.0 {
                     1..=2 => {
-                        // Letters only for basic tiers
+                        // Letters only; the content introduces them in the
+                        // layout's order, a stray key counts as an error
                         self.allow_letters = true;
                         self.allow_numbers = false;
                         self.allow_punctuation = false;
@@ -686,6 +950,16 @@ impl AllowedCharacters {
                         self.allow_symbols = true;
                         self.allow_punctuation = true;
                     }
+                    // Layout keys, weak keys and slow words can be any
+                    // printable character
+                    DrillCategory::LayoutKeys
+                    | DrillCategory::WeakKeys
+                    | DrillCategory::SlowWords => {
+                        self.allow_letters = true;
+                        self.allow_numbers = true;
+                        self.allow_punctuation = true;
+                        self.allow_symbols = true;
+                    }
                 }
             }
             TrainingMode::Endurance { .. } => {
@@ -701,12 +975,12 @@ impl AllowedCharacters {
     }
 
     fn is_allowed(&self, grapheme: &str) -> bool {
-        if grapheme.len() != 1 {
-            // Multi-byte graphemes need special handling
-            return self.is_complex_grapheme_allowed(grapheme);
-        }
-
-        let ch = grapheme.chars().next().unwrap();
+        let mut chars = grapheme.chars();
+        let ch = match (chars.next(), chars.next()) {
+            (Some(ch), None) => ch,
+            // Multi-character graphemes need special handling
+            _ => return self.is_complex_grapheme_allowed(grapheme),
+        };
 
         // Check custom allowed characters first
         if self.custom_allowed.contains(&ch) {
@@ -787,10 +1061,20 @@ impl AllowedCharacters {
         )
     }
 
-    fn is_complex_grapheme_allowed(&self, _grapheme: &str) -> bool {
-        // For now, reject complex graphemes for security
-        // In a full implementation, you might have a whitelist of allowed Unicode ranges
-        false
+    /// A cluster is allowed when it composes to one allowed character, or is
+    /// an allowed letter carrying marks that have no precomposed form
+    fn is_complex_grapheme_allowed(&self, grapheme: &str) -> bool {
+        let composed: String = grapheme.nfc().collect();
+        let mut chars = composed.chars();
+        match (chars.next(), chars.next()) {
+            (Some(_), None) => self.is_allowed(&composed),
+            (Some(base), Some(_)) => {
+                base.is_alphabetic()
+                    && self.is_allowed(base.encode_utf8(&mut [0; 4]))
+                    && composed.chars().skip(1).all(is_combining_mark)
+            }
+            _ => false,
+        }
     }
 }
 
@@ -927,6 +1211,38 @@ impl RateLimiter {
     }
 }
 
+/// Flags runs of key presses arriving faster than fingers can type, as when
+/// text is injected without bracketed paste
+#[derive(Debug)]
+struct BurstDetector {
+    last_key_at: Option<Instant>,
+    /// Consecutive intervals shorter than `min_interval`
+    run: u32,
+    min_interval: Duration,
+    max_run: u32,
+}
+
+impl BurstDetector {
+    fn new() -> Self {
+        Self {
+            last_key_at: None,
+            run: 0,
+            min_interval: Duration::from_millis(5), // Well below any human inter-key time
+            max_run: 7,                             // Eight keys in a row
+        }
+    }
+
+    /// Record a typed key; true while the keys form an implausible burst
+    fn record(&mut self, at: Instant) -> bool {
+        match self.last_key_at {
+            Some(last) if at.saturating_duration_since(last) < self.min_interval => self.run += 1,
+            _ => self.run = 0,
+        }
+        self.last_key_at = Some(at);
+        self.run >= self.max_run
+    }
+}
+
 /// Security policy configuration
 #[derive(Debug)]
 struct SecurityPolicy {
@@ -967,12 +1283,143 @@ pub struct ProcessedInput {
     pub sanitized_char: Option<char>,
     pub is_valid: bool,
     pub security_flags: SecurityFlags,
+    /// Key pressed on the physical layout while emulating another layout
+    pub physical_char: Option<char>,
+}
+
+impl ProcessedInput {
+    fn release(key: char, physical_char: Option<char>) -> Self {
+        Self {
+            input_type: InputType::Release(key),
+            sanitized_char: None,
+            is_valid: true,
+            security_flags: SecurityFlags::default(),
+            physical_char,
+        }
+    }
+
+    fn command(command: SessionCommand) -> Self {
+        Self {
+            input_type: InputType::Command(command),
+            sanitized_char: None,
+            is_valid: true,
+            security_flags: SecurityFlags::default(),
+            physical_char: None,
+        }
+    }
+
+    /// A key press that started a character without completing it
+    fn composing() -> Self {
+        Self {
+            input_type: InputType::Composing,
+            sanitized_char: None,
+            is_valid: true,
+            security_flags: SecurityFlags::default(),
+            physical_char: None,
+        }
+    }
+}
+
+/// Spacing form of a dead key, typed by following the dead key with space
+fn spacing_accent(mark: char) -> Option<char> {
+    match mark {
+        '\u{300}' => Some('`'),
+        '\u{301}' => Some('´'),
+        '\u{302}' => Some('^'),
+        '\u{303}' => Some('~'),
+        '\u{308}' => Some('¨'),
+        '\u{30A}' => Some('°'),
+        '\u{327}' => Some('¸'),
+        _ => None,
+    }
+}
+
+/// Groups typed characters into grapheme clusters. Characters that extend
+/// the pending cluster, such as combining marks, join it; a mark with nothing
+/// to join acts as a dead key and waits for the next character, even across
+/// batches. Completed clusters are returned in NFC.
+#[derive(Debug, Default)]
+struct GraphemeAssembler {
+    pending: String,
+    physical: Option<char>,
+}
+
+impl GraphemeAssembler {
+    /// Add a typed character; returns the previous cluster once `ch` starts
+    /// a new one
+    fn push(&mut self, ch: char, physical: Option<char>) -> Option<(String, Option<char>)> {
+        if self.is_dead_key() {
+            if !is_combining_mark(ch) {
+                let marks = std::mem::take(&mut self.pending);
+                if ch == ' ' {
+                    let accent = marks.chars().next().and_then(spacing_accent);
+                    self.pending.push(accent.unwrap_or(ch));
+                } else {
+                    self.pending.push(ch);
+                    self.pending.push_str(&marks);
+                }
+                self.physical = physical;
+            } else {
+                self.pending.push(ch);
+            }
+            return None;
+        }
+
+        if !self.pending.is_empty() {
+            let mut joined = self.pending.clone();
+            joined.push(ch);
+            if joined.graphemes(true).count() == 1 {
+                self.pending = joined;
+                return None;
+            }
+        }
+
+        let completed = self.take();
+        self.pending.push(ch);
+        self.physical = physical;
+        completed
+    }
+
+    /// Complete the pending cluster at the end of a batch; a dead key keeps
+    /// waiting for its letter
+    fn finish(&mut self) -> Option<(String, Option<char>)> {
+        if self.is_dead_key() {
+            None
+        } else {
+            self.take()
+        }
+    }
+
+    fn cancel(&mut self) {
+        self.pending.clear();
+        self.physical = None;
+    }
+
+    fn is_dead_key(&self) -> bool {
+        !self.pending.is_empty() && self.pending.chars().all(is_combining_mark)
+    }
+
+    fn take(&mut self) -> Option<(String, Option<char>)> {
+        if self.pending.is_empty() {
+            return None;
+        }
+
+        let grapheme = self.pending.nfc().collect();
+        self.pending.clear();
+        Some((grapheme, self.physical.take()))
+    }
 }
 
 /// Type of processed input
 #[derive(Debug, Clone)]
 pub enum InputType {
     Character(char),
+    /// A dead key or other partial character awaiting the next key press
+    Composing,
+    /// A character key went up
+    Release(char),
+    /// A key bound to a session command
+    Command(SessionCommand),
     Backspace,
     Enter,
     Escape,
@@ -989,6 +1436,8 @@ pub struct SecurityFlags {
     pub escape_sequence: bool,
     pub rate_limited: bool,
     pub pattern_match: bool,
+    /// Pasted, or part of a burst too fast to be typed
+    pub synthetic_input: bool,
 }
 
 /// Input processing statistics
@@ -1062,6 +1511,11 @@ mod tests {
         assert!(!allowed.is_allowed("1"));
         assert!(!allowed.is_allowed("@"));
 
+        // Letters the level hasn't introduced yet can still be typed, so a
+        // wrong key is scored as an error rather than dropped
+        assert!(allowed.is_allowed("q"));
+        assert!(allowed.is_allowed("z"));
+
         // Test advanced tier (everything)
         allowed.set_mode(TrainingMode::Arcade {
             level: LevelId::new(91).unwrap(),
@@ -1069,6 +1523,225 @@ mod tests {
         assert!(allowed.is_allowed("a"));
         assert!(allowed.is_allowed("1"));
         assert!(allowed.is_allowed("@"));
+
+        // Accented letters count as letters in either normalization form
+        assert!(allowed.is_allowed("é"));
+        assert!(allowed.is_allowed("e\u{301}"));
+        assert!(!allowed.is_allowed("\u{301}"));
+    }
+
+    #[test]
+    fn test_layout_emulation() {
+        let mut input_handler = Input::new();
+        input_handler.set_layout_emulation(Some(LayoutEmulation::new(
+            KeyboardGeometry::for_layout(KeyboardLayout::Qwerty),
+            KeyboardGeometry::for_layout(KeyboardLayout::Colemak),
+        )));
+        assert_eq!(
+            input_handler.layout_emulation().unwrap().label(),
+            "Colemak on QWERTY"
+        );
+
+        let press = |code, modifiers| KeyEvent {
+            code,
+            modifiers,
+            kind: crossterm::event::KeyEventKind::Press,
+            state: crossterm::event::KeyEventState::NONE,
+        };
+
+        // The physical 'k' key types Colemak's 'e'
+        let result = input_handler
+            .process_key_event(press(KeyCode::Char('k'), KeyModifiers::NONE))
+            .unwrap();
+        assert_eq!(result.sanitized_char, Some('e'));
+        assert_eq!(result.physical_char, Some('k'));
+        assert_eq!(
+            input_handler.layout_emulation().unwrap().physical_key_for('e'),
+            Some('k')
+        );
+
+        // Shortcuts keep their physical keys
+        let result = input_handler
+            .process_key_event(press(KeyCode::Char('k'), KeyModifiers::CONTROL))
+            .unwrap();
+        assert!(matches!(
+            result.input_type,
+            InputType::Control(KeyEvent {
+                code: KeyCode::Char('k'),
+                ..
+            })
+        ));
+        assert_eq!(result.physical_char, None);
+    }
+
+    #[test]
+    fn test_grapheme_assembly() {
+        let mut input_handler = Input::new();
+        let key = |ch| KeyEvent {
+            code: KeyCode::Char(ch),
+            modifiers: KeyModifiers::NONE,
+            kind: crossterm::event::KeyEventKind::Press,
+            state: crossterm::event::KeyEventState::NONE,
+        };
+        let chars = |processed: Vec<ProcessedInput>| -> Vec<Option<char>> {
+            processed.into_iter().map(|p| p.sanitized_char).collect()
+        };
+
+        // A decomposed sequence read together is one precomposed character
+        let processed = input_handler
+            .process_key_events(&[key('c'), key('a'), key('f'), key('e'), key('\u{301}')])
+            .unwrap();
+        assert_eq!(chars(processed), [Some('c'), Some('a'), Some('f'), Some('é')]);
+
+        // Precomposed characters pass through unchanged
+        let processed = input_handler.process_key_event(key('ü')).unwrap();
+        assert_eq!(processed.sanitized_char, Some('ü'));
+
+        // A dead key waits for its letter, even across reads
+        let processed = input_handler.process_key_event(key('\u{327}')).unwrap();
+        assert!(matches!(processed.input_type, InputType::Composing));
+        let processed = input_handler.process_key_event(key('c')).unwrap();
+        assert_eq!(processed.sanitized_char, Some('ç'));
+
+        // Dead key then space types the accent itself
+        let processed = input_handler
+            .process_key_events(&[key('\u{302}'), key(' ')])
+            .unwrap();
+        assert_eq!(chars(processed), [Some('^')]);
+
+        // Backspace cancels a pending dead key
+        input_handler.process_key_event(key('\u{308}')).unwrap();
+        let backspace = KeyEvent {
+            code: KeyCode::Backspace,
+            ..key('x')
+        };
+        let processed = input_handler.process_key_events(&[backspace, key('u')]).unwrap();
+        assert!(matches!(processed[0].input_type, InputType::Backspace));
+        assert_eq!(processed[1].sanitized_char, Some('u'));
+    }
+
+    #[test]
+    fn test_key_release_events() {
+        let mut input_handler = Input::new();
+        let event = |ch, kind| KeyEvent {
+            code: KeyCode::Char(ch),
+            modifiers: KeyModifiers::NONE,
+            kind,
+            state: crossterm::event::KeyEventState::NONE,
+        };
+
+        let processed = input_handler
+            .process_key_events(&[
+                event('a', KeyEventKind::Press),
+                event('s', KeyEventKind::Press),
+                event('a', KeyEventKind::Release),
+                event('s', KeyEventKind::Release),
+            ])
+            .unwrap();
+
+        // Releases come after their presses and don't type characters
+        assert_eq!(processed.len(), 4);
+        assert_eq!(processed[0].sanitized_char, Some('a'));
+        assert_eq!(processed[1].sanitized_char, Some('s'));
+        assert!(matches!(processed[2].input_type, InputType::Release('a')));
+        assert!(matches!(processed[3].input_type, InputType::Release('s')));
+        assert!(processed[2].sanitized_char.is_none());
+    }
+
+    #[test]
+    fn test_keybindings() {
+        let mut input_handler = Input::new();
+        let press = |code, modifiers| KeyEvent::new(code, modifiers);
+
+        let result = input_handler
+            .process_key_event(press(KeyCode::Char('p'), KeyModifiers::CONTROL))
+            .unwrap();
+        assert!(matches!(
+            result.input_type,
+            InputType::Command(SessionCommand::PauseResume)
+        ));
+
+        // With Esc unbound it no longer quits
+        input_handler.set_keybindings(KeyBindings {
+            quit: vec!["ctrl+c".parse().unwrap()],
+            ..KeyBindings::default()
+        });
+        let result = input_handler
+            .process_key_event(press(KeyCode::Esc, KeyModifiers::NONE))
+            .unwrap();
+        assert!(matches!(result.input_type, InputType::Escape));
+
+        // A letter binding is fine in a numbers drill, not in a letters level
+        input_handler.set_keybindings(KeyBindings {
+            skip_word: vec!["n".parse().unwrap()],
+            ..KeyBindings::default()
+        });
+        input_handler.set_training_mode(TrainingMode::Drill {
+            category: DrillCategory::Numbers,
+            duration_secs: 60,
+        });
+        assert!(input_handler.check_keybindings().is_ok());
+        input_handler.set_training_mode(TrainingMode::Arcade {
+            level: LevelId::new(30).unwrap(),
+        });
+        assert!(input_handler.check_keybindings().is_err());
+
+        // The bound letter is not typed
+        let result = input_handler
+            .process_key_event(press(KeyCode::Char('n'), KeyModifiers::NONE))
+            .unwrap();
+        assert!(matches!(result.input_type, InputType::Command(SessionCommand::SkipWord)));
+    }
+
+    #[test]
+    fn test_synthetic_input_detection() {
+        let mut input_handler = Input::new();
+        let press = |ch| KeyEvent::new(KeyCode::Char(ch), KeyModifiers::NONE);
+
+        // A few keys read together is ordinary rollover
+        input_handler
+            .process_key_events(&[press('a'), press('s'), press('d')])
+            .unwrap();
+        assert!(!input_handler.synthetic_input_detected());
+
+        // A whole word at once is not typed by hand, but still goes through
+        let burst: Vec<KeyEvent> = "sentence".chars().map(press).collect();
+        let processed = input_handler.process_key_events(&burst).unwrap();
+        assert!(processed.iter().all(|input| input.is_valid));
+        assert!(processed.last().unwrap().security_flags.synthetic_input);
+        assert!(input_handler.synthetic_input_detected());
+
+        input_handler.clear_synthetic_input();
+        assert!(!input_handler.synthetic_input_detected());
+
+        // The same keys queued up while the loop was busy for a second
+        let busy_since = Instant::now() - Duration::from_secs(1);
+        let processed = input_handler.process_key_batch(&burst, busy_since).unwrap();
+        assert!(processed.iter().all(|input| !input.security_flags.synthetic_input));
+        assert!(!input_handler.synthetic_input_detected());
+
+        let pasted = input_handler.reject_paste("the target text");
+        assert!(!pasted.is_valid);
+        assert!(pasted.sanitized_char.is_none());
+        assert!(input_handler.synthetic_input_detected());
+    }
+
+    #[test]
+    fn test_burst_detector() {
+        let mut detector = BurstDetector::new();
+        let start = Instant::now();
+
+        // Steady 200 WPM typing is a key every 60ms
+        for i in 0..20 {
+            assert!(!detector.record(start + Duration::from_millis(60 * i)));
+        }
+
+        let burst_start = start + Duration::from_secs(2);
+        let flagged: Vec<bool> = (0..8)
+            .map(|i| detector.record(burst_start + Duration::from_millis(i)))
+            .collect();
+        assert_eq!(flagged.iter().filter(|&&f| f).count(), 1);
+        assert!(flagged[7]);
     }
 
     #[test]
@@ -0,0 +1,222 @@
+//! Screen layout selection and text reflow for the typing interface
+//!
+//! The layout follows the terminal size: the full layout from 80x24 up, a
+//! compact one that folds the metrics into the header below that, and a
+//! pause screen when not even the compact layout fits. Target text is wrapped
+//! on word boundaries and scrolled so the caret line keeps its place on
+//! screen when the terminal is resized.
+use std::ops::Range;
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+/// Smallest size for the full layout
+pub const FULL_WIDTH: u16 = 80;
+pub const FULL_HEIGHT: u16 = 24;
+/// Smallest size the compact layout can draw a session in
+pub const MIN_WIDTH: u16 = 30;
+pub const MIN_HEIGHT: u16 = 8;
+
+/// Lines of already typed text kept above the caret line
+const CARET_CONTEXT_LINES: usize = 1;
+
+/// Which layout fits the terminal
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LayoutMode {
+    /// Header, bordered panes, status, progress and help bars
+    Full,
+    /// Metrics in the header, no help bar, panes without side borders
+    Compact,
+    /// Nothing fits; the session is paused until the terminal grows
+    TooSmall,
+}
+
+impl LayoutMode {
+    pub fn for_size(width: u16, height: u16) -> Self {
+        if width >= FULL_WIDTH && height >= FULL_HEIGHT {
+            LayoutMode::Full
+        } else if width >= MIN_WIDTH && height >= MIN_HEIGHT {
+            LayoutMode::Compact
+        } else {
+            LayoutMode::TooSmall
+        }
+    }
+
+    pub fn fits(self) -> bool {
+        self != LayoutMode::TooSmall
+    }
+}
+
+/// Columns a tab takes up
+pub const TAB_WIDTH: usize = 4;
+
+/// Columns a grapheme takes up. Line breaks take one, for the mark shown
+/// where Enter is expected.
+pub fn grapheme_width(grapheme: &str) -> usize {
+    match grapheme {
+        "\t" => TAB_WIDTH,
+        "\n" | "\r\n" => 1,
+        _ => grapheme.width(),
+    }
+}
+
+pub fn is_line_break(grapheme: &str) -> bool {
+    matches!(grapheme, "\n" | "\r\n")
+}
+
+fn is_space(grapheme: &str) -> bool {
+    grapheme.chars().all(char::is_whitespace)
+}
+
+/// Break `text` into lines of at most `width` columns, as ranges of char
+/// indices. Lines end after the spaces that follow a word or at a line
+/// break, so every character belongs to exactly one line and indentation
+/// starts its line; graphemes are never split, words wider than a line are.
+pub fn wrap_words(text: &str, width: usize) -> Vec<Range<usize>> {
+    let width = width.max(1);
+    let graphemes: Vec<&str> = text.graphemes(true).collect();
+    let mut char_starts = Vec::with_capacity(graphemes.len() + 1);
+    let mut chars = 0;
+    for grapheme in &graphemes {
+        char_starts.push(chars);
+        chars += grapheme.chars().count();
+    }
+    char_starts.push(chars);
+
+    let mut lines = Vec::new();
+    let mut start = 0;
+    let mut columns = 0;
+    let mut index = 0;
+    while index < graphemes.len() {
+        // A word and the spaces after it are placed together
+        let word_end = graphemes[index..]
+            .iter()
+            .position(|grapheme| is_space(grapheme))
+            .map_or(graphemes.len(), |offset| index + offset);
+        let word_columns: usize = graphemes[index..word_end]
+            .iter()
+            .map(|grapheme| grapheme_width(grapheme))
+            .sum();
+
+        if columns > 0 && columns + word_columns > width {
+            lines.push(char_starts[start]..char_starts[index]);
+            start = index;
+            columns = 0;
+        }
+
+        for grapheme in &graphemes[index..word_end] {
+            // Only a word longer than a whole line gets here overflowing
+            let grapheme_columns = grapheme_width(grapheme);
+            if columns > 0 && columns + grapheme_columns > width {
+                lines.push(char_starts[start]..char_starts[index]);
+                start = index;
+                columns = 0;
+            }
+            columns += grapheme_columns;
+            index += 1;
+        }
+
+        // Trailing spaces stay on the line even past the edge, like a
+        // word processor, so the next word starts the following line
+        while index < graphemes.len() && is_space(graphemes[index]) {
+            columns += grapheme_width(graphemes[index]);
+            index += 1;
+            if is_line_break(graphemes[index - 1]) {
+                lines.push(char_starts[start]..char_starts[index]);
+                start = index;
+                columns = 0;
+                break;
+            }
+        }
+    }
+    if start < graphemes.len() || lines.is_empty() {
+        lines.push(char_starts[start]..chars);
+    }
+    lines
+}
+
+/// The line holding char index `position`; past the end is the last line
+pub fn line_of(lines: &[Range<usize>], position: usize) -> usize {
+    lines
+        .iter()
+        .position(|line| position < line.end)
+        .unwrap_or(lines.len().saturating_sub(1))
+}
+
+/// First line to show in a pane of `rows` lines. The caret line sits just
+/// below the lines of context, whatever the width, unless that would leave
+/// the end of the text short of the bottom of the pane.
+pub fn scroll_offset(caret_line: usize, total_lines: usize, rows: usize) -> usize {
+    let context = CARET_CONTEXT_LINES.min(rows.saturating_sub(1));
+    let anchored = caret_line.saturating_sub(context);
+    anchored.min(total_lines.saturating_sub(rows))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wrapped(text: &str, width: usize) -> Vec<String> {
+        let chars: Vec<char> = text.chars().collect();
+        wrap_words(text, width)
+            .into_iter()
+            .map(|line| chars[line].iter().collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_wrap_words() {
+        let text = "the quick brown fox jumps";
+        assert_eq!(wrapped(text, 10), ["the quick ", "brown fox ", "jumps"]);
+        assert_eq!(wrapped(text, 12), ["the quick ", "brown fox ", "jumps"]);
+        assert_eq!(wrapped(text, 80), [text]);
+
+        // Overlong words are split, wide characters take two columns
+        assert_eq!(wrapped("a abcdefgh b", 4), ["a ", "abcd", "efgh ", "b"]);
+        assert_eq!(wrapped("日本語 x", 4), ["日本", "語 x"]);
+        // A base letter and its combining mark stay together
+        assert_eq!(wrapped("cafe\u{301}s", 4), ["cafe\u{301}", "s"]);
+
+        // Line breaks end a line and indentation starts the next
+        assert_eq!(
+            wrapped("fn main() {\n    go();\n}", 40),
+            ["fn main() {\n", "    go();\n", "}"]
+        );
+
+        // Every character is on exactly one line
+        let lines = wrap_words(text, 7);
+        assert_eq!(lines.first().unwrap().start, 0);
+        assert_eq!(lines.last().unwrap().end, text.len());
+        assert!(lines.windows(2).all(|pair| pair[0].end == pair[1].start));
+        assert_eq!(wrap_words("", 10).len(), 1);
+    }
+
+    #[test]
+    fn test_caret_line_stays_put() {
+        let text = "the quick brown fox jumps over the lazy dog ".repeat(6);
+        let caret = 150;
+        let rows = 4;
+
+        // Whatever the width, the caret line is drawn on the same row
+        for width in [30, 45, 60] {
+            let lines = wrap_words(&text, width);
+            let caret_line = line_of(&lines, caret);
+            let offset = scroll_offset(caret_line, lines.len(), rows);
+            assert_eq!(caret_line - offset, 1, "width {}", width);
+        }
+
+        // Near the start and end the pane stays filled
+        assert_eq!(scroll_offset(0, 10, 4), 0);
+        assert_eq!(scroll_offset(9, 10, 4), 6);
+        assert_eq!(scroll_offset(5, 10, 1), 5);
+        assert_eq!(line_of(&wrap_words("ab cd", 3), 5), 1);
+    }
+
+    #[test]
+    fn test_layout_mode_for_size() {
+        assert_eq!(LayoutMode::for_size(80, 24), LayoutMode::Full);
+        assert_eq!(LayoutMode::for_size(79, 40), LayoutMode::Compact);
+        assert_eq!(LayoutMode::for_size(120, 12), LayoutMode::Compact);
+        assert_eq!(LayoutMode::for_size(29, 24), LayoutMode::TooSmall);
+        assert!(!LayoutMode::for_size(80, 7).fits());
+    }
+}
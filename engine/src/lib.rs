@@ -6,25 +6,37 @@
 pub mod arena;
 pub mod event;
 pub mod input;
+pub mod layout;
+pub mod palette;
+pub mod panels;
 pub mod performance;
 pub mod render;
+pub mod results;
+pub mod signals;
+pub mod text_view;
+pub mod theme;
 pub mod tty;
 
 // Re-export main types
 pub use arena::{RenderArena, FrameData, ArenaStats};
 pub use event::Event as EngineEvent;
-pub use input::Input as InputProcessor;
+pub use input::{Input as InputProcessor, LayoutEmulation};
+pub use layout::LayoutMode;
+pub use palette::{ColorResolver, Rgb, RoleModifiers, ThemePalette};
 pub use performance::{Performance as PerformanceMonitor, LatencyProfiler, InputCycleTimer};
 pub use render::Render as Renderer;
+pub use results::{ResultsAction, SessionOutcome, SessionReport};
+pub use theme::ThemeRegistry;
 pub use tty::{Tty as TtyManager, TypingModeGuard, AsyncTypingModeGuard};
 
-use centotype_analytics::AnalyticsEngine;
-use centotype_content::ContentManager;
-use centotype_core::{types::*, CentotypeCore};
+use centotype_analytics::{AnalysisReport, AnalyticsEngine};
+use centotype_content::{ContentConfig, ContentManager, LayoutCurriculum, LAYOUT_DRILL_LENGTH};
+use centotype_core::{types::*, CentotypeCore, KeyBindings, KeyboardGeometry, SessionCommand};
 use centotype_persistence::PersistenceManager;
 use centotype_platform::PlatformManager;
-use crossterm::event::{Event as CrosstermEvent, KeyCode, KeyEvent, KeyModifiers};
+use crossterm::event::{Event as CrosstermEvent, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
 use parking_lot::RwLock;
+use signals::SessionSignal;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tokio::time::timeout;
@@ -50,6 +62,14 @@ pub struct CentotypeEngine {
     performance_monitor: Arc<RwLock<PerformanceMonitor>>,
     /// TUI render system
     renderer: Arc<RwLock<Renderer>>,
+    /// When the terminal input was last read to the end
+    input_drained_at: RwLock<Instant>,
+    /// Words the results screen offers to drill
+    slow_words: RwLock<Vec<String>>,
+    /// Stages of the layout being practised, for layout key drills
+    curriculum: RwLock<Option<LayoutCurriculum>>,
+    /// Furthest arcade level played, which sets the layout drill's stage
+    furthest_level: RwLock<LevelId>,
 }
 
 impl CentotypeEngine {
@@ -62,9 +82,12 @@ impl CentotypeEngine {
 
         // Initialize content management system
         let content_manager = Arc::new(
-            ContentManager::new()
-                .await
-                .map_err(|e| CentotypeError::Content(format!("Content manager init failed: {}", e)))?,
+            ContentManager::with_config(ContentConfig {
+                keyboard: core.geometry(),
+                ..ContentConfig::default()
+            })
+            .await
+            .map_err(|e| CentotypeError::Content(format!("Content manager init failed: {}", e)))?,
         );
 
         // Initialize analytics engine
@@ -89,10 +112,10 @@ impl CentotypeEngine {
         let performance_monitor = Arc::new(RwLock::new(PerformanceMonitor::new()));
 
         // Initialize TUI renderer
-        let renderer = Arc::new(RwLock::new(
-            Renderer::new()
-                .map_err(|e| CentotypeError::Platform(format!("Renderer init failed: {}", e)))?
-        ));
+        let mut renderer = Renderer::new()
+            .map_err(|e| CentotypeError::Platform(format!("Renderer init failed: {}", e)))?;
+        renderer.set_color_support(platform.terminal_capabilities().color_support);
+        let renderer = Arc::new(RwLock::new(renderer));
 
         info!("All subsystems initialized successfully");
 
@@ -106,30 +129,126 @@ impl CentotypeEngine {
             tty_manager,
             performance_monitor,
             renderer,
+            input_drained_at: RwLock::new(Instant::now()),
+            slow_words: RwLock::new(Vec::new()),
+            curriculum: RwLock::new(None),
+            furthest_level: RwLock::new(LevelId(1)),
         })
     }
 
-    /// Start the main typing session - complete implementation
+    /// Translate key presses from the physical layout to a practised one,
+    /// or turn translation off with `None`
+    pub fn set_layout_emulation(&self, emulation: Option<input::LayoutEmulation>) {
+        self.core
+            .scoring_engine()
+            .write()
+            .set_physical_geometry(emulation.as_ref().map(input::LayoutEmulation::physical_geometry));
+        self.renderer.write().set_layout_emulation(emulation.clone());
+        self.input_processor.write().set_layout_emulation(emulation);
+    }
+
+    /// Draw the keyboard heatmap for the practised layout
+    pub fn set_keyboard(&self, geometry: Arc<KeyboardGeometry>) {
+        self.renderer.write().set_keyboard(geometry);
+    }
+
+    /// Draw with the given theme, downsampled to the terminal's colours
+    pub fn set_theme(&self, theme: Theme) {
+        self.set_palette(ThemePalette::for_theme(theme));
+    }
+
+    /// Draw with a user theme, downsampled to the terminal's colours
+    pub fn set_palette(&self, palette: ThemePalette) {
+        self.renderer.write().set_theme(palette);
+    }
+
+    /// Lay the text out as a three-line window, a ticker or a paragraph
+    pub fn set_text_display(&self, display: TextDisplay) {
+        self.renderer.write().set_text_display(display);
+    }
+
+    /// Ask the terminal for key release events so dwell and flight times
+    /// can be measured; terminals without keyboard enhancement ignore this
+    pub fn set_key_timing(&self, enabled: bool) {
+        self.tty_manager.write().set_key_release_reporting(enabled);
+    }
+
+    /// Bind session commands to keys. Bindings that clash with each other are
+    /// rejected here; clashes with typed characters when a session starts.
+    pub fn set_keybindings(&self, keybindings: KeyBindings) -> Result<()> {
+        keybindings.validate(|_| false)?;
+        self.renderer.write().set_keybindings(keybindings.clone());
+        self.input_processor.write().set_keybindings(keybindings);
+        Ok(())
+    }
+
+    /// Words that keep being slow, offered as a drill on the results screen
+    /// when there are any
+    pub fn set_slow_words(&self, words: Vec<String>) {
+        *self.slow_words.write() = words;
+    }
+
+    /// Practise the keys of the layout `curriculum` was built from in
+    /// layout key drills
+    pub fn set_curriculum(&self, curriculum: LayoutCurriculum) {
+        *self.curriculum.write() = Some(curriculum);
+    }
+
+    /// The furthest arcade level played; layout key drills cover the keys
+    /// of the curriculum stage that goes with it
+    pub fn set_furthest_level(&self, level: LevelId) {
+        *self.furthest_level.write() = level;
+    }
+
+    /// Check the key bindings against the characters `mode` needs typed. A
+    /// clash is reported before the session starts, so the player can be
+    /// sent back to change it.
+    pub fn check_keybindings(&self, mode: TrainingMode) -> Result<()> {
+        let mut input = self.input_processor.write();
+        input.set_training_mode(mode);
+        input.check_keybindings()
+    }
+
+    /// Run a typing session on `target_text`, or on the mode's content from
+    /// the content manager when it is empty, and then its results screen.
+    /// Returns the results and what the user picked to do next.
     pub async fn run(
         &mut self,
         mode: TrainingMode,
-        _target_text: String, // Ignored in favor of content from ContentManager
-    ) -> Result<SessionResult> {
+        target_text: String,
+    ) -> Result<SessionOutcome> {
         let session_start = Instant::now();
         info!("Starting typing session with mode: {:?}", mode);
 
-        // 1. Load content from content manager
-        let content = self.load_session_content(&mode).await?;
+        // Bound keys must not be ones the mode needs typed
+        self.check_keybindings(mode)?;
+
+        // 1. Load content from content manager unless the text was given
+        let content = if target_text.is_empty() {
+            self.load_session_content(&mode).await?
+        } else {
+            target_text
+        };
         debug!("Loaded session content ({} chars)", content.len());
 
         // 2. Start session in core with loaded content
         let session_id = self.core.start_session(mode, content.clone())?;
         info!("Started session {}", session_id);
 
-        // 3. Setup terminal for typing mode using async-safe guard
+        // 3. Configure input processor for this training mode
+        {
+            let mut input = self.input_processor.write();
+            input.set_training_mode(mode);
+            input.clear_synthetic_input();
+        }
+
+        // 4. Setup terminal for typing mode using async-safe guard; signals
+        // are routed to the loop first so none leave the terminal raw
+        signals::install_panic_hook();
+        let _signal_guard = signals::SignalGuard::install()?;
         let _typing_guard = AsyncTypingModeGuard::new(self.tty_manager.clone())?;
 
-        // 3a. Initialize renderer for terminal UI
+        // 4a. Initialize renderer for terminal UI
         {
             let mut renderer = self.renderer.write();
             renderer.initialize()?;
@@ -137,27 +256,26 @@ impl CentotypeEngine {
             debug!("TUI renderer initialized");
         }
 
-        // 4. Configure input processor for this training mode
-        {
-            let mut input = self.input_processor.write();
-            input.set_training_mode(mode);
-        }
-
         // 5. Main typing loop (now safe to await without holding locks)
-        let result = self.run_typing_loop(session_id, &content).await?;
+        let (result, analysis) = self.run_typing_loop(mode, session_id, &content).await?;
 
-        // 6. Session completed - cleanup handled by guards
         let total_duration = session_start.elapsed();
         info!(
             "Session {} completed in {:?} with skill index {:.1}",
             session_id, total_duration, result.skill_index
         );
 
-        // 7. Persist session results
+        // 6. Persist session results before waiting on the results screen
         self.persistence.save_session_result(&result)?;
         debug!("Session results persisted");
 
-        Ok(result)
+        // 7. Show the results until the user picks what to do next; cleanup
+        // handled by guards
+        let report = self.session_report(result, analysis)?;
+        let action = self.run_results_screen(&report).await?;
+        info!("Results screen closed with {:?}", action);
+
+        Ok(SessionOutcome { report, action })
     }
 
     /// Emergency shutdown - restore terminal state immediately
@@ -197,10 +315,22 @@ impl CentotypeEngine {
                     .await
                     .map_err(|e| CentotypeError::Content(format!("Failed to load level content: {}", e)))
             }
+            TrainingMode::Drill {
+                category: DrillCategory::LayoutKeys,
+                ..
+            } => {
+                let curriculum = self.curriculum.read();
+                let curriculum = curriculum.as_ref().ok_or_else(|| {
+                    CentotypeError::Content("No layout curriculum to drill".to_string())
+                })?;
+                let level = *self.furthest_level.read();
+                let seed = DrillCategory::LayoutKeys as u64 * 67890 + level.0 as u64;
+                curriculum.generate(curriculum.stage_for_level(level), seed, LAYOUT_DRILL_LENGTH)
+            }
             TrainingMode::Drill { category, .. } => {
                 // For drill mode, generate content based on category
                 // This is a simplified implementation - full version would have drill-specific content
-                let level = LevelId::new(50).unwrap(); // Mid-tier difficulty for drills
+                let level = mode.difficulty_level();
                 let seed = Some((*category as u64) * 67890);
                 self.content_manager
                     .get_level_content(level, seed)
@@ -209,7 +339,7 @@ impl CentotypeEngine {
             }
             TrainingMode::Endurance { .. } => {
                 // For endurance mode, use varied content
-                let level = LevelId::new(75).unwrap(); // Higher difficulty for endurance
+                let level = mode.difficulty_level();
                 self.content_manager
                     .get_level_content(level, None) // Random seed for variety
                     .await
@@ -218,87 +348,132 @@ impl CentotypeEngine {
         }
     }
 
-    async fn run_typing_loop(&self, session_id: uuid::Uuid, target_text: &str) -> Result<SessionResult> {
+    async fn run_typing_loop(
+        &self,
+        mode: TrainingMode,
+        mut session_id: uuid::Uuid,
+        target_text: &str,
+    ) -> Result<(SessionResult, AnalysisReport)> {
         info!("Starting typing loop for session {}", session_id);
+        let mut paused = false;
+        // Some while no layout fits; holds whether that pause is ours to lift
+        let mut too_small = None;
 
         let loop_start = Instant::now();
-        let mut last_analytics_update = Instant::now();
         let mut last_render_update = Instant::now();
-        let analytics_interval = Duration::from_millis(100); // Update analytics every 100ms
         let render_interval = Duration::from_millis(16); // Target 60 FPS (~16ms)
 
-        // Initial render to show the interface
-        self.render_current_state().await?;
+        // Initial render to show the interface, or that it doesn't fit
+        let (width, height) = self.renderer.read().check_terminal_size()?;
+        self.handle_resize(width, height, &mut paused, &mut too_small).await?;
 
-        loop {
+        'typing: loop {
             let iteration_start = Instant::now();
 
+            // Job control and termination requests
+            for signal in signals::take_pending() {
+                match signal {
+                    SessionSignal::Terminate(signal) => {
+                        self.save_interrupted_session();
+                        return Err(CentotypeError::Interrupted(signal));
+                    }
+                    SessionSignal::Suspend => self.suspend(&mut paused).await?,
+                    SessionSignal::Continue => self.reenter_typing_mode().await?,
+                }
+            }
+
             // Poll for input events with low latency timeout
-            match timeout(Duration::from_millis(10), self.poll_input_event()).await {
-                Ok(Ok(Some(event))) => {
+            match timeout(Duration::from_millis(10), self.poll_input_events()).await {
+                Ok(Ok(Some((events, arrived_after)))) => {
                     let process_start = Instant::now();
 
-                    // Process the input event
-                    match self.process_input_event(event).await? {
-                        InputAction::Character(ch) => {
-                            // Record keystroke with analytics
-                            let keystroke = Keystroke {
-                                timestamp: chrono::Utc::now(),
-                                char_typed: Some(ch),
-                                is_correction: false,
-                                cursor_pos: 0, // Updated by core
-                            };
-
-                            // Process through core scoring engine
-                            let _live_metrics = self.core.process_keystroke(Some(ch), false)?;
-
-                            // Update session state
-                            let state_update = StateUpdate::AddKeystroke(keystroke);
-                            self.update_session_state(state_update).await?;
-
-                            // Immediate UI update for responsive feedback
-                            self.render_current_state().await?;
+                    // Process the input events
+                    for action in self.process_input_events(events, arrived_after, paused).await? {
+                        if action.is_blocked(paused, too_small.is_some()) {
+                            continue;
                         }
-                        InputAction::Backspace => {
-                            let keystroke = Keystroke {
-                                timestamp: chrono::Utc::now(),
-                                char_typed: None,
-                                is_correction: true,
-                                cursor_pos: 0,
-                            };
 
-                            let _live_metrics = self.core.process_keystroke(None, true)?;
+                        match action {
+                            InputAction::Character { ch, physical } => {
+                                self.record_keystroke(Some(ch), physical).await?;
 
-                            let state_update = StateUpdate::AddKeystroke(keystroke);
-                            self.update_session_state(state_update).await?;
+                                // Immediate UI update for responsive feedback
+                                self.render_current_state().await?;
+                            }
+                            InputAction::Backspace => {
+                                self.record_keystroke(None, None).await?;
 
-                            // Immediate UI update for backspace feedback
-                            self.render_current_state().await?;
-                        }
-                        InputAction::Quit => {
-                            info!("User quit requested");
-                            break;
-                        }
-                        InputAction::Pause => {
-                            self.handle_pause().await?;
-                            // Update UI to show paused state
-                            self.render_current_state().await?;
-                        }
-                        InputAction::Resume => {
-                            self.handle_resume().await?;
-                            // Update UI to show resumed state
-                            self.render_current_state().await?;
-                        }
-                        InputAction::ToggleHelp => {
-                            // Toggle help overlay
-                            {
-                                let mut renderer = self.renderer.write();
-                                renderer.toggle_help();
+                                // Immediate UI update for backspace feedback
+                                self.render_current_state().await?;
+                            }
+                            InputAction::KeyReleased(key) => {
+                                // Dwell and flight times for rhythm analytics
+                                let state_update = StateUpdate::KeyReleased {
+                                    key,
+                                    released_at: chrono::Utc::now(),
+                                };
+                                self.update_session_state(state_update).await?;
+                            }
+                            InputAction::Quit => {
+                                info!("User quit requested");
+                                break 'typing;
+                            }
+                            InputAction::Suspend => {
+                                self.suspend(&mut paused).await?;
+                            }
+                            InputAction::Pause => {
+                                self.handle_pause().await?;
+                                paused = true;
+                                // Update UI to show paused state
+                                self.render_current_state().await?;
+                            }
+                            InputAction::Resume => {
+                                self.handle_resume().await?;
+                                paused = false;
+                                // Update UI to show resumed state
+                                self.render_current_state().await?;
+                            }
+                            InputAction::Restart => {
+                                // Same text from the start; a fresh attempt
+                                // starts out verified
+                                session_id = self.core.start_session(mode, target_text.to_string())?;
+                                self.input_processor.write().clear_synthetic_input();
+                                paused = false;
+                                info!("Restarted as session {}", session_id);
+                                self.render_current_state().await?;
+                            }
+                            InputAction::SkipWord => {
+                                if self.skip_word().await? {
+                                    self.render_current_state().await?;
+                                }
+                            }
+                            InputAction::ToggleMetrics => {
+                                self.renderer.write().toggle_metrics();
+                                self.render_current_state().await?;
+                            }
+                            InputAction::ToggleChart => {
+                                self.renderer.write().toggle_chart();
+                                self.render_current_state().await?;
+                            }
+                            InputAction::ToggleKeyboard => {
+                                self.renderer.write().toggle_keyboard();
+                                self.render_current_state().await?;
+                            }
+                            InputAction::ToggleHelp => {
+                                // Toggle help overlay
+                                {
+                                    let mut renderer = self.renderer.write();
+                                    renderer.toggle_help();
+                                }
+                                self.render_current_state().await?;
+                            }
+                            InputAction::Resize { width, height } => {
+                                self.handle_resize(width, height, &mut paused, &mut too_small)
+                                    .await?;
+                            }
+                            InputAction::Ignore => {
+                                // Filtered or invalid input - continue loop
                             }
-                            self.render_current_state().await?;
-                        }
-                        InputAction::Ignore => {
-                            // Filtered or invalid input - continue loop
                         }
                     }
 
@@ -309,7 +484,7 @@ impl CentotypeEngine {
                     }
 
                     // Check if session is complete
-                    if self.is_session_complete(target_text).await? {
+                    if self.is_session_complete().await? {
                         info!("Session completed by user input");
                         break;
                     }
@@ -334,14 +509,6 @@ impl CentotypeEngine {
                 last_render_update = Instant::now();
             }
 
-            // Periodic analytics update
-            if last_analytics_update.elapsed() >= analytics_interval {
-                if let Err(e) = self.update_analytics().await {
-                    warn!("Failed to update analytics: {}", e);
-                }
-                last_analytics_update = Instant::now();
-            }
-
             // Maintain loop timing for consistent performance
             let iteration_time = iteration_start.elapsed();
             if iteration_time > Duration::from_millis(15) {
@@ -352,120 +519,363 @@ impl CentotypeEngine {
         let loop_duration = loop_start.elapsed();
         info!("Typing loop completed in {:?}", loop_duration);
 
-        // Complete session and get final results
-        self.core.complete_session()
+        self.finish_session()
     }
 
-    async fn poll_input_event(&self) -> Result<Option<CrosstermEvent>> {
-        // Poll for crossterm events with minimal latency
-        match crossterm::event::poll(Duration::from_millis(1))? {
-            true => {
-                let event = crossterm::event::read()
-                    .map_err(|e| CentotypeError::Input(format!("Failed to read input: {}", e)))?;
-                Ok(Some(event))
-            }
-            false => Ok(None),
+    /// Score and analyse the finished session; pasted or injected input
+    /// keeps the results off the records
+    fn finish_session(&self) -> Result<(SessionResult, AnalysisReport)> {
+        let synthetic = self.input_processor.read().synthetic_input_detected();
+        let result = self.core.complete_session(synthetic)?;
+        if synthetic {
+            warn!(
+                "Session {} marked unverified: synthetic input detected",
+                result.session_id
+            );
         }
+        let analysis = self.analytics.analyze_session(&result);
+        Ok((result, analysis))
+    }
+
+    /// Gather what the results screen shows from the finished session
+    fn session_report(
+        &self,
+        result: SessionResult,
+        analysis: AnalysisReport,
+    ) -> Result<SessionReport> {
+        let session = self.core.session_manager().read().current_state()?;
+        let errors = self.core.error_analysis()?.error_positions;
+
+        let span = session
+            .keystrokes
+            .last()
+            .and_then(|keystroke| (keystroke.timestamp - session.started_at).to_std().ok())
+            .unwrap_or_default();
+        let wpm_timeline = self
+            .core
+            .scoring_engine()
+            .read()
+            .wpm_timeline(&session, results::sample_interval(span));
+
+        let targets = match session.mode {
+            TrainingMode::Arcade { level } => Some(self.core.level_manager().target_metrics(level)),
+            _ => None,
+        };
+
+        Ok(SessionReport {
+            result,
+            targets,
+            target_text: session.target_text,
+            typed_text: session.typed_text,
+            weak_keys: results::weak_keys(&errors),
+            slow_words: self.slow_words.read().clone(),
+            improvement_areas: analysis.improvement_areas,
+            strengths: analysis.strengths,
+            errors,
+            wpm_timeline,
+        })
     }
 
-    async fn process_input_event(&self, event: CrosstermEvent) -> Result<InputAction> {
-        match event {
-            CrosstermEvent::Key(key_event) => {
-                // Process through input security validation
-                let processed = {
-                    let mut input = self.input_processor.write();
-                    input.process_key_event(key_event)?
-                };
+    /// Show the results and wait for one of the actions they offer. Resizes
+    /// redraw and job control works as while typing.
+    async fn run_results_screen(&self, report: &SessionReport) -> Result<ResultsAction> {
+        {
+            let mut renderer = self.renderer.write();
+            renderer.set_help_visible(false);
+            renderer.show_results(report.clone());
+            renderer.render_frame()?;
+        }
 
-                if !processed.is_valid {
-                    debug!("Input filtered by security validation");
-                    return Ok(InputAction::Ignore);
+        loop {
+            for signal in signals::take_pending() {
+                match signal {
+                    SessionSignal::Terminate(signal) => return Err(CentotypeError::Interrupted(signal)),
+                    SessionSignal::Suspend => {
+                        self.tty_manager.write().exit_typing_mode()?;
+                        signals::suspend_process();
+                        self.reset_terminal()?;
+                        self.renderer.write().render_frame()?;
+                    }
+                    SessionSignal::Continue => {
+                        self.reset_terminal()?;
+                        self.renderer.write().render_frame()?;
+                    }
                 }
-
-                // Convert to action based on key type
-                self.convert_key_to_action(key_event, processed).await
             }
-            CrosstermEvent::Resize(_, _) => {
-                // Handle terminal resize - for now, continue
-                debug!("Terminal resize event");
-                Ok(InputAction::Ignore)
+
+            let Some((events, _)) = self.poll_input_events().await? else {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+                continue;
+            };
+            for event in events {
+                match event {
+                    CrosstermEvent::Key(key) if key.kind != KeyEventKind::Release => {
+                        let action = match key.code {
+                            KeyCode::Esc => Some(ResultsAction::Quit),
+                            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                                Some(ResultsAction::Quit)
+                            }
+                            KeyCode::Char(ch) => report.action_for_key(ch),
+                            _ => None,
+                        };
+                        if let Some(action) = action {
+                            return Ok(action);
+                        }
+                    }
+                    CrosstermEvent::Resize(..) => {
+                        self.renderer.write().render_frame()?;
+                    }
+                    _ => {}
+                }
             }
-            _ => Ok(InputAction::Ignore),
         }
     }
 
-    async fn convert_key_to_action(
+    /// Events read together, with the time since which they arrived
+    async fn poll_input_events(&self) -> Result<Option<(Vec<CrosstermEvent>, Instant)>> {
+        let arrived_after = *self.input_drained_at.read();
+        // Poll for crossterm events with minimal latency, then take whatever
+        // arrived with the first one: terminals deliver a character and its
+        // combining marks in a single write
+        let mut events = Vec::new();
+        let mut wait = Duration::from_millis(1);
+        while crossterm::event::poll(wait)? {
+            let event = crossterm::event::read()
+                .map_err(|e| CentotypeError::Input(format!("Failed to read input: {}", e)))?;
+            events.push(event);
+            wait = Duration::ZERO;
+        }
+        *self.input_drained_at.write() = Instant::now();
+
+        Ok((!events.is_empty()).then_some((events, arrived_after)))
+    }
+
+    async fn process_input_events(
         &self,
-        key_event: KeyEvent,
-        _processed: input::ProcessedInput,
-    ) -> Result<InputAction> {
-        // Handle special key combinations
-        if key_event.modifiers.contains(KeyModifiers::CONTROL) {
-            match key_event.code {
-                KeyCode::Char('c') => return Ok(InputAction::Quit),
-                KeyCode::Char('p') => return Ok(InputAction::Pause),
-                _ => {}
+        events: Vec<CrosstermEvent>,
+        arrived_after: Instant,
+        paused: bool,
+    ) -> Result<Vec<InputAction>> {
+        let mut pastes = Vec::new();
+        let mut resize = None;
+        let key_events: Vec<KeyEvent> = events
+            .into_iter()
+            .filter_map(|event| match event {
+                CrosstermEvent::Key(key_event) => Some(key_event),
+                CrosstermEvent::Paste(text) => {
+                    pastes.push(text);
+                    None
+                }
+                CrosstermEvent::Resize(width, height) => {
+                    // Only the final size of a drag matters
+                    resize = Some(InputAction::Resize { width, height });
+                    None
+                }
+                _ => None,
+            })
+            .collect();
+
+        // Process through input security validation; keys read together are
+        // assembled into characters as one batch
+        let processed = {
+            let mut input = self.input_processor.write();
+            for text in &pastes {
+                input.reject_paste(text);
             }
+            input.process_key_batch(&key_events, arrived_after)?
+        };
+
+        Ok(processed
+            .into_iter()
+            .map(|processed| Self::convert_to_action(processed, paused))
+            .chain(resize)
+            .collect())
+    }
+
+    fn convert_to_action(processed: input::ProcessedInput, paused: bool) -> InputAction {
+        if !processed.is_valid {
+            debug!("Input filtered by security validation");
+            return InputAction::Ignore;
         }
 
-        // Handle regular keys
-        match key_event.code {
-            KeyCode::Char(ch) => Ok(InputAction::Character(ch)),
-            KeyCode::Backspace => Ok(InputAction::Backspace),
-            KeyCode::Esc => Ok(InputAction::Quit),
-            KeyCode::F(1) => Ok(InputAction::ToggleHelp),
-            KeyCode::Enter => {
-                // For now, treat enter as session completion check
-                Ok(InputAction::Ignore)
-            }
-            _ => Ok(InputAction::Ignore),
+        match processed.input_type {
+            // Keys bound in the config's `[keybindings]`
+            input::InputType::Command(command) => match command {
+                SessionCommand::Restart => InputAction::Restart,
+                SessionCommand::PauseResume if paused => InputAction::Resume,
+                SessionCommand::PauseResume => InputAction::Pause,
+                SessionCommand::Quit => InputAction::Quit,
+                SessionCommand::Help => InputAction::ToggleHelp,
+                SessionCommand::SkipWord => InputAction::SkipWord,
+                SessionCommand::ToggleMetrics => InputAction::ToggleMetrics,
+                SessionCommand::ToggleChart => InputAction::ToggleChart,
+                SessionCommand::ToggleKeyboard => InputAction::ToggleKeyboard,
+            },
+            // Use the processed character so layout emulation and grapheme
+            // assembly apply
+            input::InputType::Character(ch) => InputAction::Character {
+                ch,
+                physical: processed.physical_char,
+            },
+            input::InputType::Backspace => InputAction::Backspace,
+            // Raw mode turns off the terminal's own Ctrl+Z
+            input::InputType::Control(KeyEvent {
+                code: KeyCode::Char('z'),
+                ..
+            }) => InputAction::Suspend,
+            input::InputType::Release(key) => InputAction::KeyReleased(key),
+            // Unbound keys such as Esc or Ctrl chords do nothing
+            _ => InputAction::Ignore,
         }
     }
 
-    async fn update_session_state(&self, _update: StateUpdate) -> Result<()> {
-        // This would integrate with core session state management
-        // For now, this is a placeholder
-        debug!("Session state update requested");
-        Ok(())
+    async fn update_session_state(&self, update: StateUpdate) -> Result<()> {
+        self.core.session_manager().write().update_state(update)
     }
 
-    async fn update_analytics(&self) -> Result<()> {
-        // Update performance analytics
-        debug!("Analytics update requested");
-        Ok(())
+    /// Record a typed character, or a backspace for `None`, in the live
+    /// classification and the session state
+    async fn record_keystroke(&self, ch: Option<char>, physical: Option<char>) -> Result<()> {
+        let keystroke = Keystroke {
+            timestamp: chrono::Utc::now(),
+            char_typed: ch,
+            is_correction: ch.is_none(),
+            cursor_pos: 0, // Updated by core
+            physical_char: physical,
+            dwell: None,
+            flight: None,
+        };
+
+        self.core.process_keystroke(ch, ch.is_none())?;
+        self.update_session_state(StateUpdate::AddKeystroke(keystroke))
+            .await
+    }
+
+    /// Skip the rest of the current word. The skipped characters and the
+    /// word's separator are recorded as typed, so the session state stays
+    /// lined up with the target; false within the last word.
+    async fn skip_word(&self) -> Result<bool> {
+        let Some(skipped) = self.core.skip_word()? else {
+            return Ok(false);
+        };
+        let timestamp = chrono::Utc::now();
+        for ch in skipped.chars() {
+            let keystroke = Keystroke {
+                timestamp,
+                char_typed: Some(ch),
+                is_correction: false,
+                cursor_pos: 0,
+                physical_char: None,
+                dwell: None,
+                flight: None,
+            };
+            self.update_session_state(StateUpdate::AddKeystroke(keystroke))
+                .await?;
+        }
+        Ok(true)
     }
 
-    async fn is_session_complete(&self, _target_text: &str) -> Result<bool> {
-        // Check if user has completed typing the target text
-        // This would compare current session state with target
-        // For now, return false to keep session running
-        Ok(false)
+    /// The session ends once as many characters are typed as the text has
+    async fn is_session_complete(&self) -> Result<bool> {
+        let state = self.core.session_manager().read().current_state()?;
+        Ok(state.typed_text.chars().count() >= state.target_text.chars().count())
     }
 
     async fn handle_pause(&self) -> Result<()> {
-        let state_update = StateUpdate::SetPaused(true);
-        self.update_session_state(state_update).await?;
+        // The core stops the session clock until resumed
+        self.core
+            .session_manager()
+            .write()
+            .update_state(StateUpdate::SetPaused(true))?;
         info!("Session paused");
         Ok(())
     }
 
     async fn handle_resume(&self) -> Result<()> {
-        let state_update = StateUpdate::SetPaused(false);
-        self.update_session_state(state_update).await?;
+        self.core
+            .session_manager()
+            .write()
+            .update_state(StateUpdate::SetPaused(false))?;
         info!("Session resumed");
         Ok(())
     }
 
+    /// Redraw for a new terminal size. The session is paused while no
+    /// layout fits and resumed once one does, unless it was already paused
+    /// before the terminal got too small.
+    async fn handle_resize(
+        &self,
+        width: u16,
+        height: u16,
+        paused: &mut bool,
+        too_small: &mut Option<bool>,
+    ) -> Result<()> {
+        match (LayoutMode::for_size(width, height).fits(), *too_small) {
+            (false, None) => {
+                info!("Terminal {}x{} too small, session paused", width, height);
+                *too_small = Some(!*paused);
+                if !*paused {
+                    self.handle_pause().await?;
+                    *paused = true;
+                }
+            }
+            (true, Some(resume)) => {
+                info!("Terminal resized to {}x{}", width, height);
+                *too_small = None;
+                if resume {
+                    self.handle_resume().await?;
+                    *paused = false;
+                }
+            }
+            _ => debug!("Terminal resized to {}x{}", width, height),
+        }
+        self.render_current_state().await
+    }
+
+    /// Pause the session, give the terminal back and stop until continued
+    async fn suspend(&self, paused: &mut bool) -> Result<()> {
+        if !*paused {
+            self.handle_pause().await?;
+            *paused = true;
+        }
+        self.tty_manager.write().exit_typing_mode()?;
+        info!("Session suspended");
+        signals::suspend_process();
+        info!("Session continued");
+        self.reenter_typing_mode().await
+    }
+
+    /// Set the terminal up again after a stop, which may have let the shell
+    /// reset it, and draw the whole screen
+    async fn reenter_typing_mode(&self) -> Result<()> {
+        self.reset_terminal()?;
+        self.render_current_state().await
+    }
+
+    fn reset_terminal(&self) -> Result<()> {
+        {
+            let mut tty = self.tty_manager.write();
+            tty.exit_typing_mode()?;
+            tty.enter_typing_mode()?;
+        }
+        self.renderer.write().clear()
+    }
+
+    /// Keep the session a termination signal is cutting short
+    fn save_interrupted_session(&self) {
+        let state = self.core.session_manager().read().current_state();
+        match state.and_then(|state| self.persistence.save_interrupted_session(&state)) {
+            Ok(()) => info!("Interrupted session saved"),
+            Err(e) => warn!("Failed to save interrupted session: {}", e),
+        }
+    }
+
     /// Render current session state to TUI
     async fn render_current_state(&self) -> Result<()> {
-        // Get current session state from core
-        let session_state = self.get_session_state().await?;
-        let live_metrics = self.get_live_metrics().await?;
+        self.update_render_state().await?;
 
-        // Update renderer with current state
         {
             let mut renderer = self.renderer.write();
-            renderer.update_state(&session_state, &live_metrics);
             let render_time = renderer.render_frame()?;
 
             // Performance monitoring for render times
@@ -477,59 +887,61 @@ impl CentotypeEngine {
         Ok(())
     }
 
-    /// Get current session state (placeholder implementation)
+    /// Hand the core's session state and live metrics to the renderer
+    async fn update_render_state(&self) -> Result<()> {
+        let session_state = self.get_session_state().await?;
+        let live_metrics = self.get_live_metrics().await?;
+        self.renderer.write().update_state(&session_state, &live_metrics);
+        Ok(())
+    }
+
     async fn get_session_state(&self) -> Result<SessionState> {
-        // This would integrate with core session state management
-        // For now, return a minimal session state
-        Ok(SessionState {
-            session_id: uuid::Uuid::new_v4(),
-            mode: TrainingMode::Arcade { level: LevelId::new(1).unwrap() },
-            target_text: "function calculateSum(arr) { return arr.reduce((a,b) => a+b, 0); }".to_string(),
-            typed_text: "function calcul".to_string(),
-            cursor_position: 14,
-            started_at: chrono::Utc::now(),
-            paused_duration: Duration::ZERO,
-            is_paused: false,
-            is_completed: false,
-            keystrokes: vec![],
-        })
+        self.core.session_manager().read().current_state()
     }
 
-    /// Get current live metrics (placeholder implementation)
     async fn get_live_metrics(&self) -> Result<LiveMetrics> {
-        // This would integrate with core metrics calculation
-        // For now, return sample metrics
-        Ok(LiveMetrics {
-            raw_wpm: 47.3,
-            effective_wpm: 45.2,
-            accuracy: 94.7,
-            current_streak: 12,
-            longest_streak: 24,
-            errors: ErrorStats {
-                substitution: 2,
-                insertion: 1,
-                deletion: 0,
-                transposition: 0,
-                backspace_count: 3,
-                idle_events: 0,
-            },
-            elapsed_seconds: 83.5,
-        })
+        self.core.live_metrics()
     }
 }
 
 /// Actions derived from input processing
 #[derive(Debug, Clone)]
 enum InputAction {
-    Character(char),
+    Character { ch: char, physical: Option<char> },
     Backspace,
+    KeyReleased(char),
     Quit,
+    /// Ctrl+Z: stop the process like any terminal job
+    Suspend,
     Pause,
     Resume,
+    Restart,
+    SkipWord,
     ToggleHelp,
+    ToggleMetrics,
+    ToggleChart,
+    ToggleKeyboard,
+    /// The terminal has a new size
+    Resize { width: u16, height: u16 },
     Ignore,
 }
 
+impl InputAction {
+    /// Whether the action is dropped in the current state. Nothing is typed
+    /// while paused, where the clock is stopped, and nothing at all is done
+    /// blind while the session can't be shown.
+    fn is_blocked(&self, paused: bool, too_small: bool) -> bool {
+        if too_small {
+            return !matches!(self, Self::Quit | Self::Suspend | Self::Resize { .. });
+        }
+        paused
+            && matches!(
+                self,
+                Self::Character { .. } | Self::Backspace | Self::SkipWord
+            )
+    }
+}
+
 /// Comprehensive engine performance metrics
 #[derive(Debug, Clone)]
 pub struct EnginePerformanceMetrics {
@@ -538,3 +950,208 @@ pub struct EnginePerformanceMetrics {
     pub filtered_sequences: u64,
     pub total_inputs: u64,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn engine() -> CentotypeEngine {
+        let core = Arc::new(CentotypeCore::new());
+        let platform = Arc::new(PlatformManager::new().unwrap());
+        CentotypeEngine::new(core, platform).await.unwrap()
+    }
+
+    fn keystroke(ch: char) -> Keystroke {
+        Keystroke {
+            timestamp: chrono::Utc::now(),
+            char_typed: Some(ch),
+            is_correction: false,
+            cursor_pos: 0,
+            physical_char: None,
+            dwell: None,
+            flight: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_keystrokes_and_releases_reach_the_session() {
+        let engine = engine().await;
+        let mode = TrainingMode::Arcade { level: LevelId(1) };
+        engine.core.start_session(mode, "fj fj".to_string()).unwrap();
+
+        engine
+            .update_session_state(StateUpdate::AddKeystroke(keystroke('f')))
+            .await
+            .unwrap();
+        engine
+            .update_session_state(StateUpdate::KeyReleased {
+                key: 'f',
+                released_at: chrono::Utc::now() + chrono::Duration::milliseconds(80),
+            })
+            .await
+            .unwrap();
+
+        let state = engine.core.session_manager().read().current_state().unwrap();
+        assert_eq!(state.typed_text, "f");
+        assert_eq!(state.keystrokes.len(), 1);
+        assert!(state.keystrokes[0].dwell.unwrap() >= Duration::from_millis(80));
+    }
+
+    #[tokio::test]
+    async fn test_short_session_result() {
+        let engine = engine().await;
+        let mode = TrainingMode::Arcade { level: LevelId(3) };
+        let session_id = engine.core.start_session(mode, "fj".to_string()).unwrap();
+
+        engine.record_keystroke(Some('f'), None).await.unwrap();
+        engine.record_keystroke(Some('k'), None).await.unwrap();
+        engine.record_keystroke(None, None).await.unwrap();
+        assert!(!engine.is_session_complete().await.unwrap());
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        engine.record_keystroke(Some('j'), None).await.unwrap();
+        assert!(engine.is_session_complete().await.unwrap());
+
+        let (result, analysis) = engine.finish_session().unwrap();
+        assert_eq!(result.session_id, session_id);
+        assert_eq!(analysis.session_id, session_id);
+        assert_eq!(result.mode, mode);
+        assert!(result.duration_seconds >= 0.02);
+        assert_eq!(result.metrics.accuracy, 100.0);
+        assert_eq!(result.metrics.corrections.corrected_errors, 1);
+        assert!(result.metrics.raw_wpm > 0.0);
+        assert_eq!(result.grade, Grade::from_skill_index(result.skill_index, Tier(1)));
+        assert!(result.stars <= 3);
+        assert!(!result.unverified);
+    }
+
+    #[tokio::test]
+    async fn test_skipped_word_keeps_the_text_lined_up() {
+        let engine = engine().await;
+        let mode = TrainingMode::Arcade { level: LevelId(1) };
+        engine.core.start_session(mode, "fjd kf".to_string()).unwrap();
+
+        engine.record_keystroke(Some('f'), None).await.unwrap();
+        assert!(engine.skip_word().await.unwrap());
+        let state = engine.core.session_manager().read().current_state().unwrap();
+        assert_eq!(state.typed_text.chars().count(), 4);
+        assert!(!engine.is_session_complete().await.unwrap());
+
+        // Nothing to skip within the last word; it is typed to the end
+        engine.record_keystroke(Some('k'), None).await.unwrap();
+        assert!(!engine.skip_word().await.unwrap());
+        engine.record_keystroke(Some('f'), None).await.unwrap();
+        assert!(engine.is_session_complete().await.unwrap());
+
+        let (result, _) = engine.finish_session().unwrap();
+        // "jd" was missed; the rest was typed where it belongs
+        assert!((result.metrics.accuracy - 100.0 * 4.0 / 6.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_paused_session_takes_no_typing() {
+        let typed = InputAction::Character { ch: 'f', physical: None };
+        assert!(!typed.is_blocked(false, false));
+        assert!(typed.is_blocked(true, false));
+        assert!(InputAction::Backspace.is_blocked(true, false));
+        assert!(InputAction::SkipWord.is_blocked(true, false));
+        // Resuming and quitting still work
+        assert!(!InputAction::Resume.is_blocked(true, false));
+        assert!(!InputAction::Quit.is_blocked(true, false));
+
+        // A terminal too small to show the session only lets it end or wait
+        assert!(typed.is_blocked(true, true));
+        assert!(InputAction::Resume.is_blocked(true, true));
+        assert!(!InputAction::Resize { width: 80, height: 24 }.is_blocked(true, true));
+    }
+
+    #[tokio::test]
+    async fn test_renderer_shows_the_session() {
+        let engine = engine().await;
+        let mode = TrainingMode::Arcade { level: LevelId(1) };
+        engine.core.start_session(mode, "fjf".to_string()).unwrap();
+
+        engine.record_keystroke(Some('f'), None).await.unwrap();
+        engine.record_keystroke(Some('k'), None).await.unwrap();
+        engine.update_render_state().await.unwrap();
+
+        let renderer = engine.renderer.read();
+        let metrics = renderer.live_metrics();
+        assert_eq!(metrics.accuracy, 50.0);
+        assert_eq!(metrics.errors.substitution, 1);
+        assert!(metrics.raw_wpm > 0.0);
+        assert_eq!(renderer.side_panel().key_count('j').errors, 1);
+    }
+
+    #[tokio::test]
+    async fn test_side_panel_keeps_counts_across_frames() {
+        let engine = engine().await;
+        let mode = TrainingMode::Arcade { level: LevelId(1) };
+        engine.core.start_session(mode, "fjfj".to_string()).unwrap();
+
+        engine.record_keystroke(Some('f'), None).await.unwrap();
+        engine.update_render_state().await.unwrap();
+        engine.record_keystroke(Some('x'), None).await.unwrap();
+        engine.update_render_state().await.unwrap();
+        // A frame without new keystrokes replays nothing; one in the next
+        // second adds a sample to the ones before
+        engine.update_render_state().await.unwrap();
+        tokio::time::sleep(Duration::from_millis(1100)).await;
+        engine.update_render_state().await.unwrap();
+
+        let renderer = engine.renderer.read();
+        let panel = renderer.side_panel();
+        assert_eq!(panel.key_count('f').attempts, 1);
+        assert_eq!(panel.key_count('j').attempts, 1);
+        assert_eq!(panel.key_count('j').errors, 1);
+        assert_eq!(panel.accuracy().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_keybinding_clash_stops_before_the_session() {
+        let mut engine = engine().await;
+        engine
+            .set_keybindings(KeyBindings {
+                skip_word: vec!["n".parse().unwrap()],
+                ..KeyBindings::default()
+            })
+            .unwrap();
+        let letters = TrainingMode::Arcade { level: LevelId(30) };
+        let numbers = TrainingMode::Drill {
+            category: DrillCategory::Numbers,
+            duration_secs: 60,
+        };
+
+        assert!(engine.check_keybindings(numbers).is_ok());
+        let error = engine.check_keybindings(letters).unwrap_err();
+        assert!(error.to_string().contains("'n' for skip_word"));
+
+        // The clash is reported without starting a session or taking over
+        // the terminal
+        assert!(engine.run(letters, "no".to_string()).await.is_err());
+        assert!(engine.core.session_manager().read().current_state().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_layout_drill_follows_the_curriculum() {
+        let engine = engine().await;
+        let layout = TrainingMode::Drill {
+            category: DrillCategory::LayoutKeys,
+            duration_secs: 60,
+        };
+        assert!(engine.load_session_content(&layout).await.is_err());
+
+        let registry = centotype_core::LayoutRegistry::new();
+        let curriculum = LayoutCurriculum::new(registry.get("dvorak").unwrap());
+        engine.set_curriculum(curriculum.clone());
+
+        // A new player drills the home row anchors
+        let text = engine.load_session_content(&layout).await.unwrap();
+        assert!(text.chars().all(|ch| "aoeuhtns ".contains(ch)));
+
+        engine.set_furthest_level(LevelId(12));
+        let unlocked = curriculum.unlocked_keys(curriculum.stage_for_level(LevelId(12)));
+        let text = engine.load_session_content(&layout).await.unwrap();
+        assert!(text.chars().filter(|&ch| ch != ' ').all(|ch| unlocked.contains(&ch)));
+        assert!(text.chars().any(|ch| !"aoeuhtns ".contains(ch)));
+    }
+}
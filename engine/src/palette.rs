@@ -0,0 +1,534 @@
+//! Colour resolution from RGB theme colours to what the terminal can show
+//!
+//! Themes are defined in 24-bit RGB. On terminals with less colour depth each
+//! colour is mapped to the nearest xterm-256 or ANSI-16 entry, and text
+//! colours are then re-checked for WCAG AA contrast against the background
+//! they will actually be drawn on, since downsampling can pull a legible pair
+//! together.
+use centotype_core::types::{CentotypeError, Result, Theme};
+use centotype_platform::ColorSupport;
+use ratatui::style::{Color, Modifier};
+use std::fmt;
+use std::str::FromStr;
+
+/// WCAG AA minimum contrast for normal text
+pub const MIN_TEXT_CONTRAST: f64 = 4.5;
+/// WCAG AA minimum contrast for graphics such as the progress bar
+pub const MIN_GRAPHICS_CONTRAST: f64 = 3.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+impl Rgb {
+    pub const BLACK: Rgb = Rgb(0, 0, 0);
+    pub const WHITE: Rgb = Rgb(255, 255, 255);
+
+    /// WCAG relative luminance
+    pub fn relative_luminance(self) -> f64 {
+        let channel = |value: u8| {
+            let c = value as f64 / 255.0;
+            if c <= 0.03928 {
+                c / 12.92
+            } else {
+                ((c + 0.055) / 1.055).powf(2.4)
+            }
+        };
+        0.2126 * channel(self.0) + 0.7152 * channel(self.1) + 0.0722 * channel(self.2)
+    }
+
+    /// WCAG contrast ratio, from 1.0 to 21.0
+    pub fn contrast_ratio(self, other: Rgb) -> f64 {
+        let (a, b) = (self.relative_luminance(), other.relative_luminance());
+        (a.max(b) + 0.05) / (a.min(b) + 0.05)
+    }
+
+    /// Perceptual distance using the "redmean" weighting
+    fn distance(self, other: Rgb) -> f64 {
+        let mean_red = (self.0 as f64 + other.0 as f64) / 2.0;
+        let dr = self.0 as f64 - other.0 as f64;
+        let dg = self.1 as f64 - other.1 as f64;
+        let db = self.2 as f64 - other.2 as f64;
+        ((2.0 + mean_red / 256.0) * dr * dr
+            + 4.0 * dg * dg
+            + (2.0 + (255.0 - mean_red) / 256.0) * db * db)
+            .sqrt()
+    }
+}
+
+/// Parses `#rrggbb` hex notation
+impl FromStr for Rgb {
+    type Err = CentotypeError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = || CentotypeError::Config(format!("Invalid colour '{}', expected #rrggbb", s));
+        let hex = s.strip_prefix('#').filter(|hex| hex.len() == 6).ok_or_else(invalid)?;
+        let channel = |i: usize| {
+            hex.get(i..i + 2)
+                .and_then(|pair| u8::from_str_radix(pair, 16).ok())
+                .ok_or_else(invalid)
+        };
+        Ok(Rgb(channel(0)?, channel(2)?, channel(4)?))
+    }
+}
+
+impl fmt::Display for Rgb {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "#{:02x}{:02x}{:02x}", self.0, self.1, self.2)
+    }
+}
+
+/// The 16 ANSI colours with xterm's default values. Terminals let users
+/// change these, so this is the best guess available.
+const ANSI_16: [(Color, Rgb); 16] = [
+    (Color::Black, Rgb(0, 0, 0)),
+    (Color::Red, Rgb(205, 0, 0)),
+    (Color::Green, Rgb(0, 205, 0)),
+    (Color::Yellow, Rgb(205, 205, 0)),
+    (Color::Blue, Rgb(0, 0, 238)),
+    (Color::Magenta, Rgb(205, 0, 205)),
+    (Color::Cyan, Rgb(0, 205, 205)),
+    (Color::Gray, Rgb(229, 229, 229)),
+    (Color::DarkGray, Rgb(127, 127, 127)),
+    (Color::LightRed, Rgb(255, 0, 0)),
+    (Color::LightGreen, Rgb(0, 255, 0)),
+    (Color::LightYellow, Rgb(255, 255, 0)),
+    (Color::LightBlue, Rgb(92, 92, 255)),
+    (Color::LightMagenta, Rgb(255, 0, 255)),
+    (Color::LightCyan, Rgb(0, 255, 255)),
+    (Color::White, Rgb(255, 255, 255)),
+];
+
+/// Channel levels of the xterm 6x6x6 colour cube
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+/// RGB value of an xterm-256 palette entry
+pub fn xterm_256_rgb(index: u8) -> Rgb {
+    match index {
+        0..=15 => ANSI_16[index as usize].1,
+        16..=231 => {
+            let i = (index - 16) as usize;
+            Rgb(CUBE_LEVELS[i / 36], CUBE_LEVELS[(i / 6) % 6], CUBE_LEVELS[i % 6])
+        }
+        _ => {
+            let level = 8 + (index - 232) * 10;
+            Rgb(level, level, level)
+        }
+    }
+}
+
+/// Text attributes a theme adds to its roles
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RoleModifiers {
+    pub pending: Modifier,
+    pub correct: Modifier,
+    pub incorrect: Modifier,
+    pub caret: Modifier,
+    pub header: Modifier,
+    pub metric: Modifier,
+}
+
+impl Default for RoleModifiers {
+    fn default() -> Self {
+        Self {
+            pending: Modifier::empty(),
+            correct: Modifier::empty(),
+            incorrect: Modifier::empty(),
+            caret: Modifier::BOLD,
+            header: Modifier::BOLD,
+            metric: Modifier::empty(),
+        }
+    }
+}
+
+/// RGB colours for each role in the typing interface
+#[derive(Debug, Clone, PartialEq)]
+pub struct ThemePalette {
+    /// Background the theme is designed for; the terminal's own background
+    /// is left in place, so this is only used for contrast checks
+    pub background: Rgb,
+    pub normal_text: Rgb,
+    /// Target text not typed yet
+    pub pending_text: Rgb,
+    pub dimmed_text: Rgb,
+    pub correct_text: Rgb,
+    pub incorrect_text: Rgb,
+    pub cursor: Rgb,
+    pub error_bg: Rgb,
+    /// Underline colour for mistyped characters; none leaves them plain
+    pub error_underline: Option<Rgb>,
+    pub cursor_bg: Rgb,
+    pub status_bg: Rgb,
+    /// Live metrics in the status bar
+    pub metric: Rgb,
+    pub progress_complete: Rgb,
+    pub progress_remaining: Rgb,
+    /// Header text
+    pub accent: Rgb,
+    pub warning: Rgb,
+    pub success: Rgb,
+    pub modifiers: RoleModifiers,
+    /// Draw with text attributes only, whatever the terminal supports
+    pub monochrome: bool,
+}
+
+impl ThemePalette {
+    pub fn for_theme(theme: Theme) -> Self {
+        match theme {
+            Theme::Default => Self::default(),
+            Theme::HighContrast => Self {
+                background: Rgb::BLACK,
+                normal_text: Rgb::WHITE,
+                pending_text: Rgb::WHITE,
+                dimmed_text: Rgb(200, 200, 200),
+                correct_text: Rgb(0, 255, 0),
+                incorrect_text: Rgb::WHITE,
+                cursor: Rgb::BLACK,
+                error_bg: Rgb(180, 0, 0),
+                error_underline: None,
+                cursor_bg: Rgb(255, 255, 0),
+                status_bg: Rgb::BLACK,
+                metric: Rgb::WHITE,
+                progress_complete: Rgb(0, 255, 255),
+                progress_remaining: Rgb(128, 128, 128),
+                accent: Rgb(0, 255, 255),
+                warning: Rgb(255, 255, 0),
+                success: Rgb(0, 255, 0),
+                modifiers: RoleModifiers::default(),
+                monochrome: false,
+            },
+            Theme::Mono => Self {
+                monochrome: true,
+                ..Self::default()
+            },
+            // Red and green look alike: correct is blue, mistakes orange and
+            // underlined, so they differ in lightness and shape too
+            Theme::Deuteranopia => Self {
+                correct_text: Rgb(86, 180, 233),     // Sky blue
+                incorrect_text: Rgb(230, 159, 0),    // Orange
+                error_bg: Rgb(60, 40, 0),
+                error_underline: Some(Rgb(230, 159, 0)),
+                cursor: Rgb::BLACK,
+                cursor_bg: Rgb(240, 228, 66),        // Yellow
+                progress_complete: Rgb(86, 180, 233),
+                accent: Rgb(86, 180, 233),
+                warning: Rgb(240, 228, 66),
+                success: Rgb(86, 180, 233),
+                modifiers: RoleModifiers {
+                    incorrect: Modifier::BOLD,
+                    ..RoleModifiers::default()
+                },
+                ..Self::default()
+            },
+            // Reds look dark as well as green-like, so mistakes are a
+            // bright yellow rather than orange
+            Theme::Protanopia => Self {
+                incorrect_text: Rgb(240, 228, 66),   // Yellow
+                error_bg: Rgb(50, 50, 0),
+                error_underline: Some(Rgb(240, 228, 66)),
+                cursor_bg: Rgb(230, 159, 0),         // Orange
+                warning: Rgb(230, 159, 0),
+                ..Self::for_theme(Theme::Deuteranopia)
+            },
+            // Blue and green, yellow and pink look alike: correct is cyan,
+            // mistakes vermillion and the caret reddish purple
+            Theme::Tritanopia => Self {
+                correct_text: Rgb(0, 204, 204),      // Cyan
+                incorrect_text: Rgb(255, 120, 90),   // Vermillion
+                error_bg: Rgb(70, 0, 0),
+                error_underline: Some(Rgb(255, 120, 90)),
+                cursor: Rgb::BLACK,
+                cursor_bg: Rgb(204, 121, 167),       // Reddish purple
+                progress_complete: Rgb(0, 204, 204),
+                accent: Rgb(0, 204, 204),
+                warning: Rgb(255, 120, 90),
+                success: Rgb(0, 204, 204),
+                modifiers: RoleModifiers {
+                    incorrect: Modifier::BOLD,
+                    ..RoleModifiers::default()
+                },
+                ..Self::default()
+            },
+        }
+    }
+
+    /// Foreground colours with the backgrounds each is drawn on and the
+    /// contrast they need
+    pub fn contrast_roles(&self) -> [(&'static str, Rgb, Vec<Rgb>, f64); 12] {
+        let background = self.background;
+        let text = MIN_TEXT_CONTRAST;
+        let graphics = MIN_GRAPHICS_CONTRAST;
+        [
+            ("normal_text", self.normal_text, vec![background, self.status_bg], text),
+            ("pending_text", self.pending_text, vec![background], text),
+            ("dimmed_text", self.dimmed_text, vec![background], text),
+            ("correct_text", self.correct_text, vec![background], text),
+            ("incorrect_text", self.incorrect_text, vec![background, self.error_bg], text),
+            ("cursor", self.cursor, vec![self.cursor_bg], text),
+            ("metric", self.metric, vec![self.status_bg], text),
+            ("progress_complete", self.progress_complete, vec![background], graphics),
+            ("progress_remaining", self.progress_remaining, vec![background], graphics),
+            ("accent", self.accent, vec![background], text),
+            ("warning", self.warning, vec![background], text),
+            ("success", self.success, vec![background], text),
+        ]
+    }
+
+    /// Check every colour meets WCAG AA against its backgrounds at full
+    /// colour depth. Monochrome themes draw no colours and always pass.
+    pub fn validate(&self) -> Result<()> {
+        if self.monochrome {
+            return Ok(());
+        }
+
+        let failures: Vec<String> = self
+            .contrast_roles()
+            .iter()
+            .filter_map(|(role, foreground, backgrounds, minimum)| {
+                let ratio = backgrounds
+                    .iter()
+                    .map(|bg| foreground.contrast_ratio(*bg))
+                    .fold(f64::INFINITY, f64::min);
+                (ratio < *minimum).then(|| format!("{} {:.1}:1 (needs {}:1)", role, ratio, minimum))
+            })
+            .collect();
+
+        if failures.is_empty() {
+            Ok(())
+        } else {
+            Err(CentotypeError::Config(format!(
+                "Theme colours below WCAG AA contrast: {}",
+                failures.join(", ")
+            )))
+        }
+    }
+}
+
+impl Default for ThemePalette {
+    fn default() -> Self {
+        Self {
+            background: Rgb::BLACK,
+            normal_text: Rgb(220, 220, 220),       // Light Gray
+            pending_text: Rgb(220, 220, 220),      // Light Gray
+            dimmed_text: Rgb(160, 160, 160),       // Medium Gray
+            correct_text: Rgb(144, 238, 144),      // Light Green
+            incorrect_text: Rgb(255, 182, 193),    // Light Pink
+            cursor: Rgb(255, 255, 0),              // Yellow
+            error_bg: Rgb(139, 0, 0),              // Dark Red
+            error_underline: None,
+            cursor_bg: Rgb(75, 0, 130),            // Indigo
+            status_bg: Rgb(47, 79, 79),            // Dark Slate Gray
+            metric: Rgb(220, 220, 220),            // Light Gray
+            progress_complete: Rgb(70, 130, 180),  // Steel Blue
+            progress_remaining: Rgb(105, 105, 105), // Dim Gray
+            accent: Rgb(100, 149, 237),            // Cornflower Blue
+            warning: Rgb(255, 215, 0),             // Gold
+            success: Rgb(50, 205, 50),             // Lime Green
+            modifiers: RoleModifiers::default(),
+            monochrome: false,
+        }
+    }
+}
+
+/// A text colour that stays under WCAG AA even after resolution
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContrastIssue {
+    pub role: &'static str,
+    /// Worst ratio against the backgrounds the role is drawn on
+    pub ratio: f64,
+}
+
+/// Maps theme colours onto the terminal's colour depth
+#[derive(Debug)]
+pub struct ColorResolver {
+    support: ColorSupport,
+    issues: Vec<ContrastIssue>,
+}
+
+impl ColorResolver {
+    pub fn new(support: ColorSupport) -> Self {
+        Self {
+            support,
+            issues: Vec::new(),
+        }
+    }
+
+    pub fn support(&self) -> ColorSupport {
+        self.support
+    }
+
+    /// Resolve a background colour to its nearest output colour, returning
+    /// the RGB value the terminal will show for contrast checks
+    pub fn background(&self, rgb: Rgb) -> (Color, Rgb) {
+        self.candidates(rgb)
+            .into_iter()
+            .min_by(|a, b| rgb.distance(a.1).total_cmp(&rgb.distance(b.1)))
+            .unwrap_or((Color::Reset, rgb))
+    }
+
+    /// Resolve a text colour to the nearest output colour that meets WCAG AA
+    /// against every background it is drawn on. When none does, the most
+    /// legible candidate is used and the shortfall recorded.
+    pub fn foreground(&mut self, role: &'static str, rgb: Rgb, backgrounds: &[Rgb]) -> Color {
+        self.resolve_contrasting(role, rgb, backgrounds, MIN_TEXT_CONTRAST)
+    }
+
+    /// Like `foreground`, for graphics that need less contrast than text
+    pub fn graphic(&mut self, role: &'static str, rgb: Rgb, backgrounds: &[Rgb]) -> Color {
+        self.resolve_contrasting(role, rgb, backgrounds, MIN_GRAPHICS_CONTRAST)
+    }
+
+    fn resolve_contrasting(
+        &mut self,
+        role: &'static str,
+        rgb: Rgb,
+        backgrounds: &[Rgb],
+        minimum: f64,
+    ) -> Color {
+        let worst_contrast = |candidate: Rgb| {
+            backgrounds
+                .iter()
+                .map(|bg| candidate.contrast_ratio(*bg))
+                .fold(f64::INFINITY, f64::min)
+        };
+
+        let mut candidates = self.candidates(rgb);
+        if candidates.is_empty() {
+            return Color::Reset;
+        }
+        candidates.sort_by(|a, b| rgb.distance(a.1).total_cmp(&rgb.distance(b.1)));
+
+        if let Some((color, _)) = candidates
+            .iter()
+            .find(|(_, candidate)| worst_contrast(*candidate) >= minimum)
+        {
+            return *color;
+        }
+
+        // Truecolor keeps the theme's choice; the shortfall is the theme's
+        let (color, output) = if self.support == ColorSupport::TrueColor {
+            candidates[0]
+        } else {
+            candidates
+                .into_iter()
+                .max_by(|a, b| worst_contrast(a.1).total_cmp(&worst_contrast(b.1)))
+                .unwrap_or((Color::Reset, rgb))
+        };
+        self.issues.push(ContrastIssue {
+            role,
+            ratio: worst_contrast(output),
+        });
+        color
+    }
+
+    /// Text colours that could not be made to meet WCAG AA
+    pub fn issues(&self) -> &[ContrastIssue] {
+        &self.issues
+    }
+
+    fn candidates(&self, rgb: Rgb) -> Vec<(Color, Rgb)> {
+        match self.support {
+            ColorSupport::TrueColor => vec![(Color::Rgb(rgb.0, rgb.1, rgb.2), rgb)],
+            // Entries 0-15 are user-configurable, so only the fixed cube
+            // and grey ramp are predictable
+            ColorSupport::Ansi256 => (16..=255)
+                .map(|index| (Color::Indexed(index), xterm_256_rgb(index)))
+                .collect(),
+            ColorSupport::Ansi16 => ANSI_16.to_vec(),
+            ColorSupport::Monochrome => Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_contrast_ratio() {
+        assert!((Rgb::WHITE.contrast_ratio(Rgb::BLACK) - 21.0).abs() < 1e-9);
+        assert!((Rgb::BLACK.contrast_ratio(Rgb::BLACK) - 1.0).abs() < 1e-9);
+        // #777 on white is the classic just-below-AA grey
+        assert!(Rgb(119, 119, 119).contrast_ratio(Rgb::WHITE) < MIN_TEXT_CONTRAST);
+    }
+
+    #[test]
+    fn test_xterm_256_palette() {
+        assert_eq!(xterm_256_rgb(16), Rgb(0, 0, 0));
+        assert_eq!(xterm_256_rgb(196), Rgb(255, 0, 0));
+        assert_eq!(xterm_256_rgb(231), Rgb(255, 255, 255));
+        assert_eq!(xterm_256_rgb(232), Rgb(8, 8, 8));
+        assert_eq!(xterm_256_rgb(255), Rgb(238, 238, 238));
+    }
+
+    #[test]
+    fn test_downsampling() {
+        let gold = Rgb(255, 215, 0);
+
+        let truecolor = ColorResolver::new(ColorSupport::TrueColor);
+        assert_eq!(truecolor.background(gold).0, Color::Rgb(255, 215, 0));
+
+        let xterm = ColorResolver::new(ColorSupport::Ansi256);
+        assert_eq!(xterm.background(gold), (Color::Indexed(220), Rgb(255, 215, 0)));
+
+        let ansi = ColorResolver::new(ColorSupport::Ansi16);
+        assert_eq!(ansi.background(Rgb(139, 0, 0)).0, Color::Red);
+
+        let mut mono = ColorResolver::new(ColorSupport::Monochrome);
+        assert_eq!(mono.foreground("text", gold, &[Rgb::BLACK]), Color::Reset);
+    }
+
+    #[test]
+    fn test_downsampled_contrast() {
+        let palette = ThemePalette::default();
+        for support in [ColorSupport::Ansi256, ColorSupport::Ansi16] {
+            let mut resolver = ColorResolver::new(support);
+            let (_, error_bg) = resolver.background(palette.error_bg);
+            let color = resolver.foreground(
+                "incorrect_text",
+                palette.incorrect_text,
+                &[palette.background, error_bg],
+            );
+            assert!(resolver.issues().is_empty(), "{:?}", resolver.issues());
+
+            let output = match color {
+                Color::Indexed(index) => xterm_256_rgb(index),
+                named => ANSI_16.iter().find(|(c, _)| *c == named).unwrap().1,
+            };
+            assert!(output.contrast_ratio(error_bg) >= MIN_TEXT_CONTRAST);
+        }
+
+        // Nothing can rescue black on black; the best effort is reported
+        let mut resolver = ColorResolver::new(ColorSupport::Ansi16);
+        resolver.foreground("text", Rgb::BLACK, &[Rgb::BLACK, Rgb::WHITE]);
+        assert_eq!(resolver.issues()[0].role, "text");
+    }
+
+    #[test]
+    fn test_builtin_themes_meet_contrast() {
+        for theme in Theme::ALL {
+            let palette = ThemePalette::for_theme(theme);
+            assert!(palette.validate().is_ok(), "{}: {:?}", theme.id(), palette.validate());
+        }
+
+        // Colour-blind presets keep mistakes apart from correct text
+        let protanopia = ThemePalette::for_theme(Theme::Protanopia);
+        assert!(protanopia.error_underline.is_some());
+        assert_ne!(protanopia.correct_text, protanopia.incorrect_text);
+
+        let low_contrast = ThemePalette {
+            metric: Rgb(60, 80, 80),
+            ..ThemePalette::default()
+        };
+        let error = low_contrast.validate().unwrap_err().to_string();
+        assert!(error.contains("metric"), "{}", error);
+    }
+
+    #[test]
+    fn test_hex_colours() {
+        assert_eq!("#ff8000".parse::<Rgb>().unwrap(), Rgb(255, 128, 0));
+        assert_eq!("#A0B1C2".parse::<Rgb>().unwrap().to_string(), "#a0b1c2");
+        for invalid in ["ff8000", "#ff80", "#gg0000", "#ff80000", "#ff800é"] {
+            assert!(invalid.parse::<Rgb>().is_err(), "{}", invalid);
+        }
+    }
+}
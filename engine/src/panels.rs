@@ -0,0 +1,464 @@
+//! Live side panel drawn beside the typing pane
+//!
+//! The panel has two parts, each toggled on its own while typing: sparklines
+//! of WPM and accuracy over a rolling window, sampled once a second, and an
+//! on-screen keyboard for the practised layout that marks the next key and
+//! colours every key by the share of its keystrokes that went wrong. Both
+//! are fed from the keystroke stream as it grows, so a frame only replays
+//! the keystrokes typed since the last one.
+use centotype_core::types::{KeyboardLayout, LiveMetrics, SessionState};
+use centotype_core::KeyboardGeometry;
+use chrono::{DateTime, Utc};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::Style,
+    widgets::{Block, Borders, Sparkline, Widget},
+};
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use std::time::Duration;
+use uuid::Uuid;
+
+/// Columns taken by the panel, enough for the keyboard's longest row
+pub const PANEL_WIDTH: u16 = 30;
+/// Samples kept for the sparklines, one per second of typing
+const HISTORY_LEN: usize = 240;
+/// Keystrokes behind each WPM and accuracy sample
+const ROLLING_WINDOW: Duration = Duration::from_secs(10);
+/// Accuracy at the foot of the accuracy sparkline; anything lower draws empty
+const ACCURACY_FLOOR: f64 = 80.0;
+/// Error rates at which a key turns from clean to shaky, and shaky to weak
+const SHAKY_ERROR_RATE: f64 = 0.05;
+const WEAK_ERROR_RATE: f64 = 0.15;
+/// Cells per key width on the on-screen keyboard
+const KEY_CELLS: f32 = 2.0;
+/// Key widths covered by the space bar
+const SPACE_BAR_KEYS: f32 = 6.0;
+/// Title row plus the four key rows and the space bar
+const KEYBOARD_HEIGHT: u16 = 6;
+
+/// Keystrokes and mistakes on one key
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct KeyCount {
+    pub attempts: u32,
+    pub errors: u32,
+}
+
+impl KeyCount {
+    /// Share of the key's keystrokes that were wrong (0.0-1.0)
+    pub fn error_rate(&self) -> f64 {
+        if self.attempts == 0 {
+            0.0
+        } else {
+            self.errors as f64 / self.attempts as f64
+        }
+    }
+}
+
+/// Chart and keyboard state carried from frame to frame
+#[derive(Debug, Clone)]
+pub struct SidePanel {
+    /// Rolling WPM and accuracy sparklines
+    pub show_chart: bool,
+    /// Keyboard heatmap
+    pub show_keyboard: bool,
+    geometry: Arc<KeyboardGeometry>,
+    session_id: Option<Uuid>,
+    /// Keystrokes already replayed, and the target position they reached
+    replayed: usize,
+    cursor: usize,
+    /// Time and correctness of the character keystrokes in the window
+    recent: VecDeque<(DateTime<Utc>, bool)>,
+    wpm: VecDeque<f64>,
+    accuracy: VecDeque<f64>,
+    sampled_second: Option<u64>,
+    /// Counts per base-level key character
+    keys: HashMap<char, KeyCount>,
+    next_key: Option<char>,
+}
+
+impl SidePanel {
+    /// Hidden panel drawing the given layout's keyboard
+    pub fn new(geometry: Arc<KeyboardGeometry>) -> Self {
+        Self {
+            show_chart: false,
+            show_keyboard: false,
+            geometry,
+            session_id: None,
+            replayed: 0,
+            cursor: 0,
+            recent: VecDeque::new(),
+            wpm: VecDeque::with_capacity(HISTORY_LEN),
+            accuracy: VecDeque::with_capacity(HISTORY_LEN),
+            sampled_second: None,
+            keys: HashMap::new(),
+            next_key: None,
+        }
+    }
+
+    /// Whether either part is shown
+    pub fn is_visible(&self) -> bool {
+        self.show_chart || self.show_keyboard
+    }
+
+    /// Draw another layout's keyboard; counts start over
+    pub fn set_keyboard(&mut self, geometry: Arc<KeyboardGeometry>) {
+        self.geometry = geometry;
+        self.reset();
+    }
+
+    /// WPM samples, oldest first
+    pub fn wpm(&self) -> &VecDeque<f64> {
+        &self.wpm
+    }
+
+    /// Accuracy samples in percent, oldest first
+    pub fn accuracy(&self) -> &VecDeque<f64> {
+        &self.accuracy
+    }
+
+    /// Counts for the key whose base-level character is `key`
+    pub fn key_count(&self, key: char) -> KeyCount {
+        self.keys.get(&key).copied().unwrap_or_default()
+    }
+
+    /// Catch up with the session: replay new keystrokes and take a sample
+    /// when a new second of typing has started. A new session, or one that
+    /// lost keystrokes to a restart, starts from empty.
+    pub fn update(&mut self, session: &SessionState, metrics: &LiveMetrics) {
+        if self.session_id != Some(session.session_id) || session.keystrokes.len() < self.replayed {
+            self.reset();
+            self.session_id = Some(session.session_id);
+        }
+
+        for keystroke in &session.keystrokes[self.replayed..] {
+            let ch = match keystroke.char_typed {
+                Some(ch) if !keystroke.is_correction => ch,
+                _ => {
+                    self.cursor = self.cursor.saturating_sub(1);
+                    continue;
+                }
+            };
+
+            let expected = session.target_text.chars().nth(self.cursor);
+            self.cursor += 1;
+            let correct = expected == Some(ch);
+            self.recent.push_back((keystroke.timestamp, correct));
+
+            // Mistakes count against the key that should have been pressed
+            if let Some(key) = expected.and_then(|expected| self.geometry.base_char(expected)) {
+                let count = self.keys.entry(key).or_default();
+                count.attempts += 1;
+                if !correct {
+                    count.errors += 1;
+                }
+            }
+        }
+        self.replayed = session.keystrokes.len();
+        self.next_key = session
+            .target_text
+            .chars()
+            .nth(session.cursor_position)
+            .and_then(|next| self.geometry.base_char(next));
+
+        let second = metrics.elapsed_seconds as u64;
+        if self.sampled_second.is_some_and(|sampled| second <= sampled) {
+            return;
+        }
+        self.sampled_second = Some(second);
+        self.sample(session, metrics);
+    }
+
+    fn sample(&mut self, session: &SessionState, metrics: &LiveMetrics) {
+        // Typing time runs on the wall clock with the pauses left out
+        let elapsed = Duration::from_secs_f64(metrics.elapsed_seconds.max(0.0));
+        let now = session.started_at
+            + chrono::Duration::from_std(elapsed + session.paused_duration).unwrap_or_default();
+        let window = elapsed.min(ROLLING_WINDOW);
+        let window_start = now - chrono::Duration::from_std(window).unwrap_or_default();
+        while self
+            .recent
+            .front()
+            .is_some_and(|&(at, _)| at < window_start)
+        {
+            self.recent.pop_front();
+        }
+
+        let typed = self.recent.len();
+        let wpm = if window.is_zero() {
+            0.0
+        } else {
+            typed as f64 / 5.0 / (window.as_secs_f64() / 60.0)
+        };
+        let accuracy = if typed == 0 {
+            self.accuracy.back().copied().unwrap_or(100.0)
+        } else {
+            let correct = self.recent.iter().filter(|&&(_, correct)| correct).count();
+            correct as f64 / typed as f64 * 100.0
+        };
+
+        if self.wpm.len() == HISTORY_LEN {
+            self.wpm.pop_front();
+            self.accuracy.pop_front();
+        }
+        self.wpm.push_back(wpm);
+        self.accuracy.push_back(accuracy);
+    }
+
+    fn reset(&mut self) {
+        let shown = (self.show_chart, self.show_keyboard);
+        *self = Self::new(Arc::clone(&self.geometry));
+        (self.show_chart, self.show_keyboard) = shown;
+    }
+}
+
+impl Default for SidePanel {
+    fn default() -> Self {
+        Self::new(KeyboardGeometry::shared(KeyboardLayout::Qwerty))
+    }
+}
+
+/// Styles for the parts of the side panel
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PanelStyles {
+    pub title: Style,
+    pub wpm: Style,
+    pub accuracy: Style,
+    /// Keys not pressed yet this session
+    pub untried: Style,
+    /// Keys below the shaky error rate
+    pub clean: Style,
+    pub shaky: Style,
+    pub weak: Style,
+    /// The key that types the next character
+    pub next: Style,
+}
+
+/// Draw the visible parts of the panel, the keyboard under the chart
+pub fn draw(panel: &SidePanel, styles: &PanelStyles, area: Rect, buf: &mut Buffer) {
+    // One blank column between the typing pane and the panel
+    let area = Rect {
+        x: area.x + 1,
+        width: area.width.saturating_sub(1),
+        ..area
+    };
+    let keyboard_height = if panel.show_keyboard {
+        KEYBOARD_HEIGHT
+    } else {
+        0
+    };
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(0), Constraint::Length(keyboard_height)])
+        .split(area);
+
+    if panel.show_chart {
+        draw_chart(panel, styles, chunks[0], buf);
+    }
+    if panel.show_keyboard {
+        draw_keyboard(panel, styles, chunks[1], buf);
+    }
+}
+
+/// WPM over accuracy, each with its latest value in the title
+fn draw_chart(panel: &SidePanel, styles: &PanelStyles, area: Rect, buf: &mut Buffer) {
+    let halves = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Ratio(1, 2), Constraint::Ratio(1, 2)])
+        .split(area);
+    let width = area.width as usize;
+    let latest = |samples: &VecDeque<f64>| samples.back().copied().unwrap_or_default();
+
+    let wpm: Vec<u64> = last(panel.wpm(), width)
+        .map(|wpm| wpm.round() as u64)
+        .collect();
+    Sparkline::default()
+        .block(title_block(
+            format!("WPM {:.0}", latest(panel.wpm())),
+            styles,
+        ))
+        .data(&wpm)
+        .max(wpm.iter().copied().max().unwrap_or_default().max(1))
+        .style(styles.wpm)
+        .render(halves[0], buf);
+
+    // Tenths of a percent above the floor, so small drops still show
+    let accuracy: Vec<u64> = last(panel.accuracy(), width)
+        .map(|accuracy| ((accuracy - ACCURACY_FLOOR).max(0.0) * 10.0).round() as u64)
+        .collect();
+    Sparkline::default()
+        .block(title_block(
+            format!("Accuracy {:.1}%", latest(panel.accuracy())),
+            styles,
+        ))
+        .data(&accuracy)
+        .max(((100.0 - ACCURACY_FLOOR) * 10.0) as u64)
+        .style(styles.accuracy)
+        .render(halves[1], buf);
+}
+
+/// Keys at their board positions, two cells per key width
+fn draw_keyboard(panel: &SidePanel, styles: &PanelStyles, area: Rect, buf: &mut Buffer) {
+    let block = title_block(format!("Keys {}", panel.geometry.name()), styles);
+    let inner = block.inner(area);
+    block.render(area, buf);
+
+    let style_for = |key: char| {
+        let count = panel.key_count(key);
+        if panel.next_key == Some(key) {
+            styles.next
+        } else if count.attempts == 0 {
+            styles.untried
+        } else if count.error_rate() >= WEAK_ERROR_RATE {
+            styles.weak
+        } else if count.error_rate() >= SHAKY_ERROR_RATE {
+            styles.shaky
+        } else {
+            styles.clean
+        }
+    };
+
+    for (key, position) in panel.geometry.base_keys() {
+        let y = inner.y + u16::from(position.row);
+        if y >= inner.bottom() {
+            continue;
+        }
+        let style = style_for(key);
+
+        if key == ' ' {
+            let start = ((position.x - SPACE_BAR_KEYS / 2.0) * KEY_CELLS).max(0.0) as u16;
+            let end = (((position.x + SPACE_BAR_KEYS / 2.0) * KEY_CELLS) as u16).min(inner.width);
+            for x in start..end {
+                buf.get_mut(inner.x + x, y).set_symbol("─").set_style(style);
+            }
+            continue;
+        }
+
+        let x = ((position.x - 0.5) * KEY_CELLS) as u16;
+        if x < inner.width {
+            buf.get_mut(inner.x + x, y).set_char(key).set_style(style);
+        }
+    }
+}
+
+fn title_block(title: String, styles: &PanelStyles) -> Block<'static> {
+    Block::default()
+        .borders(Borders::TOP)
+        .title(title)
+        .title_style(styles.title)
+}
+
+/// The last `count` samples
+fn last(samples: &VecDeque<f64>, count: usize) -> impl Iterator<Item = &f64> {
+    samples.iter().skip(samples.len().saturating_sub(count))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use centotype_core::types::{Keystroke, LevelId, TrainingMode};
+
+    fn session(target: &str, keys: &[(u64, Option<char>)]) -> SessionState {
+        let started_at = DateTime::<Utc>::default();
+        SessionState {
+            session_id: Uuid::nil(),
+            mode: TrainingMode::Arcade {
+                level: LevelId::new(1).unwrap(),
+            },
+            target_text: target.to_string(),
+            typed_text: String::new(),
+            cursor_position: keys.iter().fold(0, |cursor, (_, key)| match key {
+                Some(_) => cursor + 1,
+                None => cursor - 1,
+            }),
+            started_at,
+            paused_duration: Duration::ZERO,
+            is_paused: false,
+            is_completed: false,
+            keystrokes: keys
+                .iter()
+                .map(|&(millis, char_typed)| Keystroke {
+                    timestamp: started_at + chrono::Duration::milliseconds(millis as i64),
+                    char_typed,
+                    is_correction: char_typed.is_none(),
+                    cursor_pos: 0,
+                    physical_char: None,
+                    dwell: None,
+                    flight: None,
+                })
+                .collect(),
+        }
+    }
+
+    fn at(seconds: f64) -> LiveMetrics {
+        LiveMetrics {
+            elapsed_seconds: seconds,
+            ..LiveMetrics::default()
+        }
+    }
+
+    #[test]
+    fn test_key_heat_replay() {
+        let mut panel = SidePanel::default();
+        // "Sad" typed as "Sqd", corrected to "Sad"
+        let keys = [
+            (100, Some('S')),
+            (200, Some('q')),
+            (300, None),
+            (400, Some('a')),
+        ];
+        panel.update(&session("Sad", &keys[..2]), &at(0.2));
+        assert_eq!(
+            panel.key_count('s'),
+            KeyCount {
+                attempts: 1,
+                errors: 0
+            }
+        );
+
+        // Only the new keystrokes are replayed
+        panel.update(&session("Sad", &keys), &at(0.4));
+        assert_eq!(
+            panel.key_count('a'),
+            KeyCount {
+                attempts: 2,
+                errors: 1
+            }
+        );
+        assert_eq!(panel.key_count('a').error_rate(), 0.5);
+        assert_eq!(panel.next_key, Some('d'));
+
+        // A restart with fewer keystrokes starts over
+        panel.update(&session("Sad", &keys[..1]), &at(0.1));
+        assert_eq!(panel.key_count('a'), KeyCount::default());
+    }
+
+    #[test]
+    fn test_rolling_samples() {
+        let mut panel = SidePanel::default();
+        let target = "a".repeat(200);
+        // Five keystrokes a second for 20 seconds, all wrong in the last 5
+        let keys: Vec<(u64, Option<char>)> = (0..100)
+            .map(|i| (i * 200, Some(if i < 75 { 'a' } else { 'x' })))
+            .collect();
+
+        for second in 1..=20 {
+            let typed = (second * 5) as usize;
+            panel.update(&session(&target, &keys[..typed]), &at(second as f64));
+            // Frames within the same second add no sample
+            panel.update(&session(&target, &keys[..typed]), &at(second as f64 + 0.5));
+        }
+
+        assert_eq!(panel.wpm().len(), 20);
+        // 5 characters a second is 60 WPM once the window is full
+        assert!((panel.wpm()[1] - 60.0).abs() < 1.0, "{:?}", panel.wpm());
+        assert!((panel.wpm()[19] - 60.0).abs() < 1.0);
+        assert_eq!(panel.accuracy()[14], 100.0);
+        assert!(
+            (panel.accuracy()[19] - 50.0).abs() < 1.0,
+            "{:?}",
+            panel.accuracy()
+        );
+    }
+}
@@ -1,16 +1,25 @@
 //! Comprehensive TUI render system for typing interface using ratatui
 //! Provides real-time typing feedback with accessibility compliance (WCAG AA)
+use crate::input::LayoutEmulation;
+use crate::layout::{LayoutMode, MIN_HEIGHT, MIN_WIDTH};
+use crate::palette::{ColorResolver, ContrastIssue, Rgb, ThemePalette};
+use crate::panels::{self, PanelStyles, SidePanel, PANEL_WIDTH};
+use crate::results::{self, ResultsStyles, SessionReport};
+use crate::text_view::{self, TextStyles, TypingText};
 use centotype_core::types::*;
+use centotype_core::{KeyBindings, KeyboardGeometry, SessionCommand};
+use centotype_platform::ColorSupport;
 use ratatui::{
     backend::CrosstermBackend,
+    buffer::{Buffer, Cell},
     Terminal,
-    Frame,
     layout::{Layout, Direction, Constraint, Rect, Alignment},
-    widgets::{Block, Borders, Paragraph, Gauge, Clear},
+    widgets::{Block, Borders, Paragraph, Gauge, Clear, Widget},
     style::{Color, Style, Modifier, Stylize},
-    text::{Line, Span, Text},
+    text::{Line, Span},
 };
 use std::io::{self, Stdout};
+use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tracing::{debug, warn, error};
 
@@ -26,15 +35,23 @@ pub struct Render {
     last_render_time: Option<Instant>,
     /// UI color scheme (terminal-safe with WCAG AA compliance)
     colors: UiColors,
+    /// Theme the colors are resolved from
+    palette: ThemePalette,
+    /// Colour depth of the terminal
+    color_support: ColorSupport,
     /// Help overlay state
     show_help: bool,
     /// ANSI renderer for batched output
     ansi_renderer: AnsiRenderer,
+    /// Cells on screen after the last frame, diffed against the next one
+    previous_frame: Buffer,
     /// Pre-composed line cache
     line_cache: std::collections::HashMap<String, PrecomposedLine>,
     /// Cache hit statistics
     cache_hits: u64,
     cache_misses: u64,
+    /// Active layout emulation, used for the on-screen key hint
+    layout_emulation: Option<LayoutEmulation>,
 }
 
 /// State needed for rendering the typing interface
@@ -48,7 +65,17 @@ struct RenderState {
     is_completed: bool,
     level_info: LevelInfo,
     session_duration: Duration,
-    error_positions: Vec<usize>,
+    results: Option<SessionReport>,
+    /// Where to find the next key when emulating another layout
+    layout_hint: Option<String>,
+    /// Keys shown in the help bar and overlay
+    keybindings: KeyBindings,
+    /// Live WPM, accuracy and error counts in the status bar
+    show_metrics: bool,
+    /// How the text is laid out in the typing pane
+    text_display: TextDisplay,
+    /// Live chart and keyboard heatmap beside the typing pane
+    side_panel: SidePanel,
 }
 
 /// Level information for header display
@@ -112,8 +139,8 @@ impl AnsiRenderer {
     pub fn new() -> Self {
         Self {
             batch_buffer: String::with_capacity(8192), // 8KB initial capacity
-            current_row: 0,
-            current_col: 0,
+            current_row: u16::MAX,
+            current_col: u16::MAX,
             current_style: None,
             buffer_capacity: 8192,
         }
@@ -122,87 +149,155 @@ impl AnsiRenderer {
     /// Begin a new frame - clear buffers and reset state
     pub fn begin_frame(&mut self) {
         self.batch_buffer.clear();
-        self.current_row = 0;
-        self.current_col = 0;
+        // The cursor may have been moved since the last frame
+        self.current_row = u16::MAX;
+        self.current_col = u16::MAX;
         self.current_style = None;
     }
 
+    /// Add raw control output, such as a one-off screen clear
+    pub fn add_control(&mut self, sequence: &str) {
+        self.batch_buffer.push_str(sequence);
+    }
+
     /// Add a positioned styled span to the batch
     pub fn add_span(&mut self, row: u16, col: u16, content: &str, style: Style) {
-        // Optimize cursor movement by batching sequential operations
+        self.move_to(row, col);
+        self.set_style(style);
+        self.batch_buffer.push_str(content);
+        self.current_col = col.saturating_add(content.chars().count() as u16);
+    }
+
+    /// Add one buffer cell. The cursor is assumed to advance one column, so
+    /// the cell after a wide or combining symbol gets an explicit move.
+    pub fn add_cell(&mut self, row: u16, col: u16, cell: &Cell) {
+        self.move_to(row, col);
+        self.set_style(cell.style());
+        self.batch_buffer.push_str(&cell.symbol);
+        self.current_col = col.saturating_add(1);
+    }
+
+    fn move_to(&mut self, row: u16, col: u16) {
+        // Sequential cells need no cursor movement
         if row != self.current_row || col != self.current_col {
-            self.batch_buffer.push_str(&format!("\x1b[{};{}H", row + 1, col + 1));
+            self.batch_buffer
+                .push_str(&format!("\x1b[{};{}H", row + 1, col + 1));
             self.current_row = row;
             self.current_col = col;
         }
+    }
 
-        // Optimize style changes by only applying differences
-        if self.current_style.as_ref() != Some(&style) {
+    fn set_style(&mut self, style: Style) {
+        // Only style changes are written
+        if self.current_style != Some(style) {
             self.apply_style_optimized(&style);
             self.current_style = Some(style);
         }
-
-        // Add content
-        self.batch_buffer.push_str(content);
-        self.current_col += content.chars().count() as u16;
     }
 
-    /// Apply style with optimization for minimal ANSI output
+    /// Write a style as a single SGR sequence, starting from a reset so no
+    /// attribute of the previous style carries over
     fn apply_style_optimized(&mut self, style: &Style) {
-        // Start with reset only if necessary
-        let mut needs_reset = false;
+        let mut sgr = String::from("\x1b[0");
 
-        if let Some(current) = &self.current_style {
-            // Check if we need to reset based on previous style
-            if current.fg != style.fg || current.bg != style.bg {
-                needs_reset = true;
-            }
+        if let Some(fg) = style.fg {
+            Self::push_color(&mut sgr, fg, false);
         }
-
-        if needs_reset || self.current_style.is_none() {
-            self.batch_buffer.push_str("\x1b[0m"); // Reset
+        if let Some(bg) = style.bg {
+            Self::push_color(&mut sgr, bg, true);
+        }
+        if let Some(underline) = style.underline_color {
+            Self::push_underline_color(&mut sgr, underline);
         }
 
-        // Apply foreground color
-        if let Some(fg) = style.fg {
-            match fg {
-                Color::Rgb(r, g, b) => {
-                    self.batch_buffer.push_str(&format!("\x1b[38;2;{};{};{}m", r, g, b));
-                }
-                Color::Indexed(i) => {
-                    self.batch_buffer.push_str(&format!("\x1b[38;5;{}m", i));
-                }
-                _ => {}
+        let modifiers = style.add_modifier - style.sub_modifier;
+        for (modifier, code) in [
+            (Modifier::BOLD, ";1"),
+            (Modifier::DIM, ";2"),
+            (Modifier::ITALIC, ";3"),
+            (Modifier::UNDERLINED, ";4"),
+            (Modifier::REVERSED, ";7"),
+            (Modifier::CROSSED_OUT, ";9"),
+        ] {
+            if modifiers.contains(modifier) {
+                sgr.push_str(code);
             }
         }
 
-        // Apply background color
-        if let Some(bg) = style.bg {
-            match bg {
-                Color::Rgb(r, g, b) => {
-                    self.batch_buffer.push_str(&format!("\x1b[48;2;{};{};{}m", r, g, b));
-                }
-                Color::Indexed(i) => {
-                    self.batch_buffer.push_str(&format!("\x1b[48;5;{}m", i));
-                }
-                _ => {}
+        sgr.push('m');
+        self.batch_buffer.push_str(&sgr);
+    }
+
+    fn push_color(sgr: &mut String, color: Color, background: bool) {
+        let (base, bright) = if background { (40, 100) } else { (30, 90) };
+        let code = match color {
+            Color::Reset => return,
+            Color::Rgb(r, g, b) => {
+                let layer = if background { 48 } else { 38 };
+                sgr.push_str(&format!(";{};2;{};{};{}", layer, r, g, b));
+                return;
             }
-        }
+            Color::Indexed(i) => {
+                let layer = if background { 48 } else { 38 };
+                sgr.push_str(&format!(";{};5;{}", layer, i));
+                return;
+            }
+            Color::Black => base,
+            Color::Red => base + 1,
+            Color::Green => base + 2,
+            Color::Yellow => base + 3,
+            Color::Blue => base + 4,
+            Color::Magenta => base + 5,
+            Color::Cyan => base + 6,
+            Color::Gray => base + 7,
+            Color::DarkGray => bright,
+            Color::LightRed => bright + 1,
+            Color::LightGreen => bright + 2,
+            Color::LightYellow => bright + 3,
+            Color::LightBlue => bright + 4,
+            Color::LightMagenta => bright + 5,
+            Color::LightCyan => bright + 6,
+            Color::White => bright + 7,
+        };
+        sgr.push_str(&format!(";{}", code));
+    }
 
-        // Apply modifiers
-        if style.add_modifier.contains(Modifier::BOLD) {
-            self.batch_buffer.push_str("\x1b[1m");
-        }
-        if style.add_modifier.contains(Modifier::ITALIC) {
-            self.batch_buffer.push_str("\x1b[3m");
-        }
-        if style.add_modifier.contains(Modifier::UNDERLINED) {
-            self.batch_buffer.push_str("\x1b[4m");
-        }
+    /// Underline colours only come in indexed and RGB forms
+    fn push_underline_color(sgr: &mut String, color: Color) {
+        let index = match color {
+            Color::Reset => return,
+            Color::Rgb(r, g, b) => {
+                sgr.push_str(&format!(";58;2;{};{};{}", r, g, b));
+                return;
+            }
+            Color::Indexed(i) => i,
+            Color::Black => 0,
+            Color::Red => 1,
+            Color::Green => 2,
+            Color::Yellow => 3,
+            Color::Blue => 4,
+            Color::Magenta => 5,
+            Color::Cyan => 6,
+            Color::Gray => 7,
+            Color::DarkGray => 8,
+            Color::LightRed => 9,
+            Color::LightGreen => 10,
+            Color::LightYellow => 11,
+            Color::LightBlue => 12,
+            Color::LightMagenta => 13,
+            Color::LightCyan => 14,
+            Color::White => 15,
+        };
+        sgr.push_str(&format!(";58;5;{}", index));
     }
 
-    /// Finalize the batch and return the complete ANSI sequence
+    /// Finalize the batch and return the complete ANSI sequence, empty
+    /// when nothing changed
     pub fn finalize_batch(&mut self) -> String {
+        if self.batch_buffer.is_empty() {
+            return String::new();
+        }
+
         // Add final reset and ensure buffer is ready for next frame
         self.batch_buffer.push_str("\x1b[0m");
 
@@ -224,29 +319,33 @@ impl AnsiRenderer {
     }
 }
 
-/// WCAG AA compliant color scheme
+/// WCAG AA compliant color scheme, resolved for the terminal's colour depth
 #[derive(Debug, Clone)]
 struct UiColors {
     // Text colors (4.5:1 contrast minimum)
-    correct_text: Color,
-    incorrect_text: Color,
-    cursor: Color,
-    normal_text: Color,
-    dimmed_text: Color,
+    correct_text: Style,
+    incorrect_text: Style,
+    cursor: Style,
+    normal_text: Style,
+    pending_text: Style,
+    dimmed_text: Style,
+    metric: Style,
 
     // Background colors
-    error_bg: Color,
-    cursor_bg: Color,
-    status_bg: Color,
+    error_bg: Style,
+    cursor_bg: Style,
+    status_bg: Style,
 
     // Progress colors
-    progress_complete: Color,
-    progress_remaining: Color,
+    progress_complete: Style,
+    progress_remaining: Style,
 
     // Status colors
-    accent: Color,
-    warning: Color,
-    success: Color,
+    accent: Style,
+    /// Attributes added to the header whatever its colour
+    header: Modifier,
+    warning: Style,
+    success: Style,
 }
 
 impl Default for RenderState {
@@ -260,35 +359,108 @@ impl Default for RenderState {
             is_completed: false,
             level_info: LevelInfo::default(),
             session_duration: Duration::ZERO,
-            error_positions: Vec::new(),
+            results: None,
+            layout_hint: None,
+            keybindings: KeyBindings::default(),
+            show_metrics: true,
+            text_display: TextDisplay::default(),
+            side_panel: SidePanel::default(),
         }
     }
 }
 
-impl Default for UiColors {
-    fn default() -> Self {
-        Self {
-            // WCAG AA compliant colors (4.5:1 contrast on dark background)
-            correct_text: Color::Rgb(144, 238, 144),     // Light Green
-            incorrect_text: Color::Rgb(255, 182, 193),   // Light Pink
-            cursor: Color::Rgb(255, 255, 0),            // Yellow
-            normal_text: Color::Rgb(220, 220, 220),      // Light Gray
-            dimmed_text: Color::Rgb(160, 160, 160),      // Medium Gray
+impl UiColors {
+    /// Resolve a theme for the terminal. Text colours are checked against the
+    /// backgrounds they are drawn on after downsampling; on monochrome
+    /// terminals roles are told apart by text attributes instead.
+    fn resolve(palette: &ThemePalette, support: ColorSupport) -> (Self, Vec<ContrastIssue>) {
+        if palette.monochrome || support == ColorSupport::Monochrome {
+            return (Self::monochrome(), Vec::new());
+        }
 
-            error_bg: Color::Rgb(139, 0, 0),            // Dark Red
-            cursor_bg: Color::Rgb(75, 0, 130),           // Indigo
-            status_bg: Color::Rgb(47, 79, 79),           // Dark Slate Gray
+        let mut resolver = ColorResolver::new(support);
+        let (error_bg, error_rgb) = resolver.background(palette.error_bg);
+        let (cursor_bg, cursor_rgb) = resolver.background(palette.cursor_bg);
+        let (status_bg, status_rgb) = resolver.background(palette.status_bg);
+        let background = palette.background;
+
+        let modifiers = palette.modifiers;
+        let mut incorrect_text = Style::default().add_modifier(modifiers.incorrect);
+        if let Some(underline) = palette.error_underline {
+            incorrect_text = incorrect_text
+                .add_modifier(Modifier::UNDERLINED)
+                .underline_color(resolver.background(underline).0);
+        }
 
-            progress_complete: Color::Rgb(70, 130, 180), // Steel Blue
-            progress_remaining: Color::Rgb(105, 105, 105), // Dim Gray
+        let mut fg = |role, rgb, backgrounds: &[Rgb]| {
+            Style::default().fg(resolver.foreground(role, rgb, backgrounds))
+        };
+        let colors = Self {
+            correct_text: fg("correct_text", palette.correct_text, &[background])
+                .add_modifier(modifiers.correct),
+            incorrect_text: incorrect_text.patch(fg(
+                "incorrect_text",
+                palette.incorrect_text,
+                &[background, error_rgb],
+            )),
+            // The caret is always drawn on its own background
+            cursor: fg("cursor", palette.cursor, &[cursor_rgb]).add_modifier(modifiers.caret),
+            normal_text: fg("normal_text", palette.normal_text, &[background, status_rgb]),
+            pending_text: fg("pending_text", palette.pending_text, &[background])
+                .add_modifier(modifiers.pending),
+            dimmed_text: fg("dimmed_text", palette.dimmed_text, &[background]),
+            metric: fg("metric", palette.metric, &[status_rgb]).add_modifier(modifiers.metric),
+            accent: fg("accent", palette.accent, &[background]),
+            warning: fg("warning", palette.warning, &[background]),
+            success: fg("success", palette.success, &[background]),
+            header: modifiers.header,
+            progress_complete: Style::default().fg(resolver.graphic(
+                "progress_complete",
+                palette.progress_complete,
+                &[background],
+            )),
+            progress_remaining: Style::default().fg(resolver.graphic(
+                "progress_remaining",
+                palette.progress_remaining,
+                &[background],
+            )),
+            error_bg: Style::default().bg(error_bg),
+            cursor_bg: Style::default().bg(cursor_bg),
+            status_bg: Style::default().bg(status_bg),
+        };
+        (colors, resolver.issues().to_vec())
+    }
 
-            accent: Color::Rgb(100, 149, 237),          // Cornflower Blue
-            warning: Color::Rgb(255, 215, 0),           // Gold
-            success: Color::Rgb(50, 205, 50),           // Lime Green
+    /// Bold, underline and reverse video in place of colour
+    fn monochrome() -> Self {
+        let plain = Style::default();
+        Self {
+            correct_text: plain,
+            incorrect_text: plain.add_modifier(Modifier::UNDERLINED | Modifier::BOLD),
+            cursor: plain.add_modifier(Modifier::BOLD),
+            normal_text: plain,
+            pending_text: plain,
+            dimmed_text: plain.add_modifier(Modifier::DIM),
+            metric: plain,
+            error_bg: plain.add_modifier(Modifier::REVERSED),
+            cursor_bg: plain.add_modifier(Modifier::REVERSED),
+            status_bg: plain.add_modifier(Modifier::REVERSED),
+            progress_complete: plain.add_modifier(Modifier::BOLD),
+            progress_remaining: plain.add_modifier(Modifier::DIM),
+            accent: plain.add_modifier(Modifier::BOLD),
+            warning: plain.add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
+            success: plain.add_modifier(Modifier::BOLD),
+            header: Modifier::BOLD,
         }
     }
 }
 
+impl Default for UiColors {
+    fn default() -> Self {
+        Self::resolve(&ThemePalette::default(), ColorSupport::TrueColor).0
+    }
+}
+
 impl Render {
     /// Create new render system with accessibility support and performance optimizations
     pub fn new() -> Result<Self> {
@@ -298,11 +470,15 @@ impl Render {
             frame_count: 0,
             last_render_time: None,
             colors: UiColors::default(),
+            palette: ThemePalette::default(),
+            color_support: ColorSupport::TrueColor,
             show_help: false,
             ansi_renderer: AnsiRenderer::new(),
+            previous_frame: Buffer::empty(Rect::default()),
             line_cache: std::collections::HashMap::with_capacity(256),
             cache_hits: 0,
             cache_misses: 0,
+            layout_emulation: None,
         })
     }
 
@@ -313,6 +489,8 @@ impl Render {
             .map_err(|e| CentotypeError::Platform(format!("Failed to create terminal: {}", e)))?;
 
         self.terminal = Some(terminal);
+        // A fresh screen: the first frame draws everything
+        self.previous_frame = Buffer::empty(Rect::default());
         debug!("Render system initialized");
         Ok(())
     }
@@ -343,11 +521,8 @@ impl Render {
             chrono::Utc::now()
         ).to_std().unwrap_or(Duration::ZERO) - session_state.paused_duration;
 
-        // Identify error positions for highlighting
-        let error_positions = self.calculate_error_positions(
-            &session_state.target_text,
-            &session_state.typed_text
-        );
+        let mut side_panel = std::mem::take(&mut self.render_state.side_panel);
+        side_panel.update(session_state, live_metrics);
 
         self.render_state = RenderState {
             target_text: session_state.target_text.clone(),
@@ -358,10 +533,126 @@ impl Render {
             is_completed: session_state.is_completed,
             level_info,
             session_duration,
-            error_positions,
+            // Keep the results only while the session stays completed
+            results: if session_state.is_completed {
+                self.render_state.results.take()
+            } else {
+                None
+            },
+            layout_hint: self.layout_emulation.as_ref().map(|emulation| {
+                let next = session_state
+                    .target_text
+                    .chars()
+                    .nth(session_state.cursor_position);
+                Self::layout_hint(emulation, next)
+            }),
+            // Display settings outlive the session state
+            keybindings: std::mem::take(&mut self.render_state.keybindings),
+            show_metrics: self.render_state.show_metrics,
+            text_display: self.render_state.text_display,
+            side_panel,
         };
     }
 
+    /// Draw with a different theme
+    pub fn set_theme(&mut self, palette: ThemePalette) {
+        self.palette = palette;
+        self.resolve_colors();
+    }
+
+    /// Match colours to what the terminal can show
+    pub fn set_color_support(&mut self, support: ColorSupport) {
+        self.color_support = support;
+        self.resolve_colors();
+    }
+
+    fn resolve_colors(&mut self) {
+        let (colors, issues) = UiColors::resolve(&self.palette, self.color_support);
+        for issue in issues {
+            warn!(
+                "Theme color {} has contrast {:.1}:1 at {:?}, below WCAG AA",
+                issue.role, issue.ratio, self.color_support
+            );
+        }
+        self.colors = colors;
+    }
+
+    /// Keys to show for session commands
+    pub fn set_keybindings(&mut self, keybindings: KeyBindings) {
+        self.render_state.keybindings = keybindings;
+    }
+
+    /// Lay the text out in a different display mode
+    pub fn set_text_display(&mut self, display: TextDisplay) {
+        self.render_state.text_display = display;
+    }
+
+    /// Show or hide the live metrics in the status bar
+    pub fn toggle_metrics(&mut self) {
+        self.render_state.show_metrics = !self.render_state.show_metrics;
+        debug!("Live metrics toggled: {}", self.render_state.show_metrics);
+    }
+
+    /// Show or hide the live WPM and accuracy chart
+    pub fn toggle_chart(&mut self) {
+        let panel = &mut self.render_state.side_panel;
+        panel.show_chart = !panel.show_chart;
+        debug!("Live chart toggled: {}", panel.show_chart);
+    }
+
+    /// Show or hide the keyboard heatmap
+    pub fn toggle_keyboard(&mut self) {
+        let panel = &mut self.render_state.side_panel;
+        panel.show_keyboard = !panel.show_keyboard;
+        debug!("Keyboard heatmap toggled: {}", panel.show_keyboard);
+    }
+
+    /// Layout drawn by the keyboard heatmap
+    pub fn set_keyboard(&mut self, geometry: Arc<KeyboardGeometry>) {
+        self.render_state.side_panel.set_keyboard(geometry);
+    }
+
+    /// Show which physical key types the next character of an emulated layout
+    pub fn set_layout_emulation(&mut self, emulation: Option<LayoutEmulation>) {
+        self.render_state.layout_hint = emulation
+            .as_ref()
+            .map(|emulation| Self::layout_hint(emulation, None));
+        self.layout_emulation = emulation;
+    }
+
+    fn layout_hint(emulation: &LayoutEmulation, next: Option<char>) -> String {
+        let key_name = |ch: char| match ch {
+            ' ' => "space".to_string(),
+            ch => format!("'{}'", ch),
+        };
+
+        match next.and_then(|ch| emulation.physical_key_for(ch).map(|key| (ch, key))) {
+            Some((ch, key)) if ch != key => format!(
+                "{} │ next {} is your {} key",
+                emulation.label(),
+                key_name(ch),
+                key_name(key)
+            ),
+            _ => emulation.label(),
+        }
+    }
+
+    /// Live metrics as of the last state update
+    pub fn live_metrics(&self) -> &LiveMetrics {
+        &self.render_state.live_metrics
+    }
+
+    /// Chart and keyboard state as of the last state update
+    pub fn side_panel(&self) -> &SidePanel {
+        &self.render_state.side_panel
+    }
+
+    /// Attach final session results for the completion screen
+    pub fn show_results(&mut self, report: SessionReport) {
+        self.render_state.is_completed = true;
+        self.render_state.results = Some(report);
+    }
+
     /// Render current frame with performance monitoring and ANSI batching
     pub fn render_frame(&mut self) -> Result<Duration> {
         let render_start = std::time::Instant::now();
@@ -401,42 +692,39 @@ impl Render {
 
     /// Render using optimized ANSI batching for maximum performance
     fn render_with_ansi_batching(&mut self) -> Result<String> {
-        self.ansi_renderer.begin_frame();
-
         // Get terminal size for layout calculation
         let (width, height) = self.check_terminal_size()?;
+        Ok(self.render_diff(Rect::new(0, 0, width, height)))
+    }
 
-        // Clear screen efficiently
-        self.ansi_renderer.add_span(0, 0, "\x1b[2J\x1b[H", Style::default());
-
-        // Calculate layout proportions
-        let header_row = 0;
-        let typing_start_row = 1;
-        let typing_height = (height as f32 * 0.6) as u16;
-        let status_row = typing_start_row + typing_height;
-        let progress_row = status_row + 1;
-        let help_row = progress_row + 1;
-
-        // Render each component with batched ANSI
-        self.render_header_ansi(header_row, width)?;
-        self.render_typing_pane_ansi(typing_start_row, width, typing_height)?;
-        self.render_status_bar_ansi(status_row, width)?;
-        self.render_progress_bar_ansi(progress_row, width)?;
-        self.render_help_bar_ansi(help_row, width)?;
+    /// Draw the UI into a cell buffer and return the ANSI output for the
+    /// cells that differ from the previous frame. The screen is only
+    /// cleared when its size changed, never while typing.
+    pub fn render_diff(&mut self, area: Rect) -> String {
+        let mut frame = Buffer::empty(area);
+        Self::draw_comprehensive_ui(&self.render_state, &self.colors, self.show_help, &mut frame);
 
-        if self.show_help {
-            self.render_help_overlay_ansi(width, height)?;
+        self.ansi_renderer.begin_frame();
+        if self.previous_frame.area != area {
+            // Old content may have reflowed anywhere; start from blank
+            self.ansi_renderer.add_control("\x1b[0m\x1b[2J");
+            self.previous_frame = Buffer::empty(area);
+        }
+        for (x, y, cell) in self.previous_frame.diff(&frame) {
+            self.ansi_renderer.add_cell(y, x, cell);
         }
+        self.previous_frame = frame;
 
-        Ok(self.ansi_renderer.finalize_batch())
+        self.ansi_renderer.finalize_batch()
     }
 
-    /// Clear the screen
+    /// Clear the screen; the next frame redraws every cell
     pub fn clear(&mut self) -> Result<()> {
         if let Some(terminal) = &mut self.terminal {
             terminal.clear()
                 .map_err(|e| CentotypeError::Platform(format!("Clear failed: {}", e)))?;
         }
+        self.previous_frame = Buffer::empty(Rect::default());
         Ok(())
     }
 
@@ -462,10 +750,12 @@ impl Render {
             let size = terminal.size()
                 .map_err(|e| CentotypeError::Platform(format!("Failed to get terminal size: {}", e)))?;
 
-            // Minimum size check (80x24)
-            if size.width < 80 || size.height < 24 {
-                warn!("Terminal size {}x{} below recommended minimum 80x24",
-                    size.width, size.height);
+            match LayoutMode::for_size(size.width, size.height) {
+                LayoutMode::Full => {}
+                LayoutMode::Compact => debug!("Terminal size {}x{} uses the compact layout",
+                    size.width, size.height),
+                LayoutMode::TooSmall => warn!("Terminal size {}x{} below minimum {}x{}",
+                    size.width, size.height, MIN_WIDTH, MIN_HEIGHT),
             }
 
             Ok((size.width, size.height))
@@ -474,32 +764,40 @@ impl Render {
         }
     }
 
-    /// Calculate error positions for highlighting
-    fn calculate_error_positions(&self, target: &str, typed: &str) -> Vec<usize> {
-        let target_chars: Vec<char> = target.chars().collect();
-        let typed_chars: Vec<char> = typed.chars().collect();
-        let mut errors = Vec::new();
-
-        for (i, (&target_char, typed_char)) in target_chars.iter()
-            .zip(typed_chars.iter().chain(std::iter::repeat(&'\0')))
-            .enumerate() {
-            if typed_char != &'\0' && *typed_char != target_char {
-                errors.push(i);
-            }
-        }
-
-        errors
-    }
-
     // Private rendering methods
 
-    /// Comprehensive UI layout with accessibility compliance
+    /// Comprehensive UI layout with accessibility compliance, chosen by the
+    /// size of the buffer
     fn draw_comprehensive_ui(
         render_state: &RenderState,
         colors: &UiColors,
         show_help: bool,
-        frame: &mut Frame
+        buf: &mut Buffer
     ) {
+        let mode = LayoutMode::for_size(buf.area.width, buf.area.height);
+        // A finished session's results take the whole screen
+        if let (Some(report), true) = (&render_state.results, mode.fits()) {
+            Self::draw_results_screen(report, colors, buf);
+            return;
+        }
+
+        match mode {
+            LayoutMode::Full => Self::draw_full_layout(render_state, colors, buf),
+            LayoutMode::Compact => Self::draw_compact_layout(render_state, colors, buf),
+            LayoutMode::TooSmall => {
+                Self::draw_too_small_static(colors, buf);
+                return;
+            }
+        }
+
+        // Draw help overlay if requested
+        if show_help {
+            Self::draw_help_overlay_static(&render_state.keybindings, colors, buf);
+        }
+    }
+
+    /// Layout for terminals of 80x24 and up
+    fn draw_full_layout(render_state: &RenderState, colors: &UiColors, buf: &mut Buffer) {
         // Create main layout with proper proportions
         let chunks = Layout::default()
             .direction(Direction::Vertical)
@@ -510,34 +808,84 @@ impl Render {
                 Constraint::Length(1),      // Progress bar
                 Constraint::Length(1),      // Help bar
             ])
-            .split(frame.size());
+            .split(buf.area);
 
         // Draw header with level info
-        Self::draw_header_static(render_state, colors, frame, chunks[0]);
-
-        // Draw main typing pane with cursor positioning
-        Self::draw_typing_pane_static(render_state, colors, frame, chunks[1]);
+        Self::draw_header_static(render_state, colors, buf, chunks[0]);
+
+        // Draw main typing pane with cursor positioning, with the side panel
+        // beside it when shown
+        let panel = &render_state.side_panel;
+        if panel.is_visible() {
+            let columns = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Min(0), Constraint::Length(PANEL_WIDTH)])
+                .split(chunks[1]);
+            Self::draw_typing_pane_static(render_state, colors, Borders::ALL, buf, columns[0]);
+            Self::draw_side_panel_static(panel, colors, buf, columns[1]);
+        } else {
+            Self::draw_typing_pane_static(render_state, colors, Borders::ALL, buf, chunks[1]);
+        }
 
         // Draw real-time status bar
-        Self::draw_status_bar_static(render_state, colors, frame, chunks[2]);
+        Self::draw_status_bar_static(render_state, colors, buf, chunks[2]);
 
         // Draw progress indicator
-        Self::draw_progress_bar_static(render_state, colors, frame, chunks[3]);
+        Self::draw_progress_bar_static(render_state, colors, buf, chunks[3]);
 
         // Draw help/keymap bar
-        Self::draw_help_bar_static(render_state, colors, frame, chunks[4]);
+        Self::draw_help_bar_static(render_state, colors, buf, chunks[4]);
+    }
 
-        // Draw help overlay if requested
-        if show_help {
-            Self::draw_help_overlay_static(colors, frame);
-        }
+    /// Layout for narrow or short terminals: the metrics move into the
+    /// header, the help bar goes and the panes lose their side borders
+    fn draw_compact_layout(render_state: &RenderState, colors: &UiColors, buf: &mut Buffer) {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(1), // Header with metrics
+                Constraint::Min(0),    // Typing pane
+                Constraint::Length(1), // Progress bar
+            ])
+            .split(buf.area);
+
+        Self::draw_compact_header_static(render_state, colors, buf, chunks[0]);
+
+        Self::draw_typing_pane_static(render_state, colors, Borders::TOP, buf, chunks[1]);
+
+        Self::draw_progress_bar_static(render_state, colors, buf, chunks[2]);
+    }
+
+    /// Shown instead of the session when no layout fits
+    fn draw_too_small_static(colors: &UiColors, buf: &mut Buffer) {
+        let area = buf.area;
+        let message = vec![
+            Line::from("Terminal too small"),
+            Line::from(format!(
+                "{}x{}, need {}x{}",
+                area.width, area.height, MIN_WIDTH, MIN_HEIGHT
+            )),
+            Line::from("Paused until resized"),
+        ];
+
+        let top = area.height.saturating_sub(message.len() as u16) / 2;
+        let message_area = Rect {
+            y: area.y + top,
+            height: area.height - top,
+            ..area
+        };
+        Paragraph::new(message)
+            .style(colors.warning.add_modifier(Modifier::BOLD))
+            .alignment(Alignment::Center)
+            .wrap(ratatui::widgets::Wrap { trim: true })
+            .render(message_area, buf);
     }
 
     /// Header with level information
     fn draw_header_static(
         render_state: &RenderState,
         colors: &UiColors,
-        frame: &mut Frame,
+        buf: &mut Buffer,
         area: Rect
     ) {
         let header_text = if render_state.level_info.level_id > 0 {
@@ -552,122 +900,115 @@ impl Render {
         };
 
         let header_style = if render_state.is_paused {
-            Style::default().fg(colors.warning).add_modifier(Modifier::BOLD)
+            colors.warning
         } else if render_state.is_completed {
-            Style::default().fg(colors.success).add_modifier(Modifier::BOLD)
+            colors.success
         } else {
-            Style::default().fg(colors.accent).add_modifier(Modifier::BOLD)
-        };
+            colors.accent
+        }
+        .add_modifier(colors.header);
 
         let header = Paragraph::new(header_text)
             .style(header_style)
             .alignment(Alignment::Center);
 
-        frame.render_widget(header, area);
+        header.render(area, buf);
     }
 
 
-    /// Typing pane with real-time cursor and highlighting
-    fn draw_typing_pane_static(
+    /// One-line header for the compact layout with the live metrics and
+    /// the state the help bar would show
+    fn draw_compact_header_static(
         render_state: &RenderState,
         colors: &UiColors,
-        frame: &mut Frame,
+        buf: &mut Buffer,
         area: Rect
     ) {
-        // Split typing area for target and input display
-        let typing_chunks = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints([
-                Constraint::Percentage(50), // Target text
-                Constraint::Percentage(50), // Input text with cursor
-            ])
-            .split(area);
+        let metrics = &render_state.live_metrics;
+        let duration_secs = render_state.session_duration.as_secs();
 
-        // Draw target text
-        Self::draw_target_text(render_state, colors, frame, typing_chunks[0]);
+        let mut parts = vec![if render_state.level_info.level_id > 0 {
+            format!("Level {}", render_state.level_info.level_id)
+        } else {
+            render_state.level_info.description.clone()
+        }];
+        if render_state.show_metrics {
+            parts.push(format!("{:.0} wpm", metrics.effective_wpm));
+            parts.push(format!("{:.1}%", metrics.accuracy));
+            parts.push(format!("err {}", metrics.errors.total_errors()));
+        }
+        parts.push(format!("{}:{:02}", duration_secs / 60, duration_secs % 60));
 
-        // Draw input text with cursor
-        Self::draw_input_text(render_state, colors, frame, typing_chunks[1]);
+        let header_style = if render_state.is_paused {
+            parts.push("PAUSED".to_string());
+            colors.warning
+        } else if render_state.is_completed {
+            parts.push("DONE".to_string());
+            colors.success
+        } else {
+            colors.accent
+        }
+        .add_modifier(colors.header);
+
+        Paragraph::new(parts.join(" │ "))
+            .style(header_style)
+            .alignment(Alignment::Center)
+            .render(area, buf);
     }
 
-    /// Target text display
-    fn draw_target_text(
+    /// Typing pane with the typed text drawn over the target in the
+    /// configured display mode
+    fn draw_typing_pane_static(
         render_state: &RenderState,
         colors: &UiColors,
-        frame: &mut Frame,
+        borders: Borders,
+        buf: &mut Buffer,
         area: Rect
     ) {
-        let target_text = Text::from(Line::from(vec![
-            Span::raw("Target: "),
-            Span::styled(&render_state.target_text, Style::default().fg(colors.normal_text)),
-        ]));
-
-        let target_paragraph = Paragraph::new(target_text)
-            .block(Block::default().borders(Borders::ALL).title("Target Text"))
-            .wrap(ratatui::widgets::Wrap { trim: false });
-
-        frame.render_widget(target_paragraph, area);
+        let block = Block::default().borders(borders).title("Text");
+        let text_area = block.inner(area);
+        block.render(area, buf);
+
+        let text = TypingText {
+            target: &render_state.target_text,
+            typed: &render_state.typed_text,
+            cursor: render_state.cursor_position,
+        };
+        let styles = TextStyles {
+            untyped: colors.pending_text,
+            correct: colors.correct_text,
+            incorrect: colors.incorrect_text.patch(colors.error_bg),
+            caret: colors.cursor.patch(colors.cursor_bg),
+            line_number: colors.dimmed_text,
+        };
+        text_view::draw(render_state.text_display, &text, &styles, text_area, buf);
     }
 
-    /// Input text with cursor positioning and error highlighting
-    fn draw_input_text(
-        render_state: &RenderState,
+    /// Live chart and keyboard heatmap
+    fn draw_side_panel_static(
+        panel: &SidePanel,
         colors: &UiColors,
-        frame: &mut Frame,
+        buf: &mut Buffer,
         area: Rect
     ) {
-        let mut spans = vec![Span::raw("Input:  ")];
-
-        // Create styled spans for typed text with error highlighting
-        let target_chars: Vec<char> = render_state.target_text.chars().collect();
-        let typed_chars: Vec<char> = render_state.typed_text.chars().collect();
-
-        for (i, &typed_char) in typed_chars.iter().enumerate() {
-            let style = if i < target_chars.len() {
-                let target_char = target_chars[i];
-                if typed_char == target_char {
-                    Style::default().fg(colors.correct_text)
-                } else {
-                    Style::default().fg(colors.incorrect_text).bg(colors.error_bg)
-                }
-            } else {
-                // Extra characters (insertion errors)
-                Style::default().fg(colors.incorrect_text).bg(colors.error_bg)
-            };
-
-            spans.push(Span::styled(typed_char.to_string(), style));
-        }
-
-        // Add cursor if within bounds
-        if render_state.cursor_position < target_chars.len() {
-            let cursor_char = target_chars.get(render_state.cursor_position)
-                .unwrap_or(&' ')
-                .to_string();
-            spans.push(Span::styled(
-                cursor_char,
-                Style::default().fg(colors.cursor).bg(colors.cursor_bg).add_modifier(Modifier::BOLD)
-            ));
-        } else if render_state.cursor_position == target_chars.len() {
-            // End of text cursor
-            spans.push(Span::styled(
-                "█",
-                Style::default().fg(colors.cursor).add_modifier(Modifier::BOLD)
-            ));
-        }
-
-        let input_text = Text::from(Line::from(spans));
-        let input_paragraph = Paragraph::new(input_text)
-            .block(Block::default().borders(Borders::ALL).title("Your Input"))
-            .wrap(ratatui::widgets::Wrap { trim: false });
-
-        frame.render_widget(input_paragraph, area);
+        let styles = PanelStyles {
+            title: colors.accent,
+            wpm: colors.progress_complete,
+            accuracy: colors.success,
+            untried: colors.dimmed_text,
+            clean: colors.correct_text,
+            shaky: colors.warning,
+            weak: colors.incorrect_text.patch(colors.error_bg),
+            next: colors.cursor.patch(colors.cursor_bg),
+        };
+        panels::draw(panel, &styles, area, buf);
     }
 
     /// Status bar with real-time metrics
     fn draw_status_bar_static(
         render_state: &RenderState,
         colors: &UiColors,
-        frame: &mut Frame,
+        buf: &mut Buffer,
         area: Rect
     ) {
         let metrics = &render_state.live_metrics;
@@ -675,32 +1016,54 @@ impl Render {
         let minutes = duration_secs / 60;
         let seconds = duration_secs % 60;
 
-        let status_text = format!(
-            " WPM: {:.0} │ ACC: {:.1}% │ COMBO: x{} │ ⏱️  {}:{:02} │ ERR: {} ",
-            metrics.effective_wpm,
-            metrics.accuracy,
-            metrics.current_streak,
-            minutes,
-            seconds,
-            metrics.errors.total_errors()
-        );
+        let status_text = if render_state.show_metrics {
+            format!(
+                " WPM: {:.0} │ ACC: {:.1}% │ COMBO: x{} │ ⏱️  {}:{:02} │ ERR: {} ",
+                metrics.effective_wpm,
+                metrics.accuracy,
+                metrics.current_streak,
+                minutes,
+                seconds,
+                metrics.errors.total_errors()
+            )
+        } else {
+            format!(" ⏱️  {}:{:02} ", minutes, seconds)
+        };
 
-        let status_style = Style::default()
-            .fg(colors.normal_text)
-            .bg(colors.status_bg);
+        let status_style = colors.metric.patch(colors.status_bg);
 
         let status_bar = Paragraph::new(status_text)
             .style(status_style)
             .alignment(Alignment::Left);
 
-        frame.render_widget(status_bar, area);
+        status_bar.render(area, buf);
+    }
+
+    /// Full-screen results of the finished session
+    fn draw_results_screen(report: &SessionReport, colors: &UiColors, buf: &mut Buffer) {
+        let styles = ResultsStyles {
+            text: colors.normal_text,
+            heading: colors.accent.add_modifier(colors.header),
+            dimmed: colors.dimmed_text,
+            met: colors.success,
+            short: colors.warning,
+            sparkline: colors.progress_complete,
+            correct: colors.correct_text,
+            substitution: colors.incorrect_text.patch(colors.error_bg),
+            insertion: colors.incorrect_text.add_modifier(Modifier::CROSSED_OUT),
+            deletion: colors.warning.add_modifier(Modifier::UNDERLINED),
+            transposition: colors.warning.patch(colors.error_bg).add_modifier(Modifier::ITALIC),
+            untyped: colors.dimmed_text,
+            key: colors.accent.add_modifier(Modifier::BOLD),
+        };
+        results::draw(report, &styles, buf.area, buf);
     }
 
     /// Progress bar with completion percentage
     fn draw_progress_bar_static(
         render_state: &RenderState,
         colors: &UiColors,
-        frame: &mut Frame,
+        buf: &mut Buffer,
         area: Rect
     ) {
         let progress_percent = if render_state.target_text.is_empty() {
@@ -721,78 +1084,99 @@ impl Render {
         let filled_blocks = ((progress_percent / 100.0) * bar_width as f64) as usize;
         let empty_blocks = bar_width.saturating_sub(filled_blocks);
 
-        let progress_visual = format!(
-            "{}{}",
-            "█".repeat(filled_blocks),
-            "░".repeat(empty_blocks)
-        );
-
         let progress_line = Line::from(vec![
             Span::styled(
-                progress_visual,
-                Style::default().fg(colors.progress_complete)
+                "█".repeat(filled_blocks),
+                colors.progress_complete
+            ),
+            Span::styled(
+                "░".repeat(empty_blocks),
+                colors.progress_remaining
             ),
             Span::styled(
                 progress_text,
-                Style::default().fg(colors.normal_text)
+                colors.normal_text
             ),
         ]);
 
         let progress_paragraph = Paragraph::new(progress_line)
             .alignment(Alignment::Left);
 
-        frame.render_widget(progress_paragraph, area);
+        progress_paragraph.render(area, buf);
     }
 
     /// Help bar with key mappings
     fn draw_help_bar_static(
         render_state: &RenderState,
         colors: &UiColors,
-        frame: &mut Frame,
+        buf: &mut Buffer,
         area: Rect
     ) {
+        let keys = &render_state.keybindings;
+        let quit_help = format!(
+            "{}:quit │ {}:help",
+            keys.label(SessionCommand::Quit),
+            keys.label(SessionCommand::Help)
+        );
         let help_text = if render_state.is_paused {
-            " PAUSED - Ctrl+P:resume │ ESC:quit │ F1:help "
+            format!(
+                " PAUSED - {}:resume │ {} ",
+                keys.label(SessionCommand::PauseResume),
+                quit_help
+            )
         } else if render_state.is_completed {
-            " COMPLETED! - Enter:continue │ ESC:quit │ F1:help "
+            format!(" COMPLETED! - Enter:continue │ {} ", quit_help)
+        } else if let Some(hint) = &render_state.layout_hint {
+            format!(" {} │ {} ", hint, quit_help)
         } else {
-            " ESC:quit │ F1:help │ Ctrl+R:restart │ Tab:stats "
+            format!(
+                " {} │ {}:restart │ {}:stats ",
+                quit_help,
+                keys.label(SessionCommand::Restart),
+                keys.label(SessionCommand::ToggleMetrics)
+            )
         };
 
-        let help_style = Style::default()
-            .fg(colors.dimmed_text)
+        let help_style = colors.dimmed_text
             .add_modifier(Modifier::ITALIC);
 
         let help_bar = Paragraph::new(help_text)
             .style(help_style)
             .alignment(Alignment::Center);
 
-        frame.render_widget(help_bar, area);
+        help_bar.render(area, buf);
     }
 
     /// Help overlay with comprehensive key mappings
-    fn draw_help_overlay_static(colors: &UiColors, frame: &mut Frame) {
+    fn draw_help_overlay_static(keys: &KeyBindings, colors: &UiColors, buf: &mut Buffer) {
         // Calculate centered popup area
-        let popup_area = Self::centered_rect(60, 70, frame.size());
+        let popup_area = Self::centered_rect(60, 70, buf.area);
 
         // Clear background
-        frame.render_widget(Clear, popup_area);
+        Clear.render(popup_area, buf);
+
+        let binding = |command, description| {
+            Line::from(format!("  {:<12} {}", keys.label(command), description))
+        };
+        let help_label = keys.label(SessionCommand::Help);
 
         let help_content = vec![
             Line::from("CENTOTYPE HELP").alignment(Alignment::Center),
             Line::from(""),
             Line::from("Typing Commands:"),
             Line::from("  Backspace    Delete previous character"),
-            Line::from("  Ctrl+C       Quit application"),
-            Line::from("  ESC          Quit current session"),
+            binding(SessionCommand::Quit, "Quit current session"),
+            binding(SessionCommand::SkipWord, "Skip the rest of the word"),
             Line::from(""),
             Line::from("Session Controls:"),
-            Line::from("  Ctrl+P       Pause/Resume session"),
-            Line::from("  Ctrl+R       Restart current level"),
-            Line::from("  Tab          Show detailed statistics"),
+            binding(SessionCommand::PauseResume, "Pause/Resume session"),
+            binding(SessionCommand::Restart, "Restart current level"),
+            binding(SessionCommand::ToggleMetrics, "Show or hide live statistics"),
             Line::from(""),
             Line::from("Display:"),
-            Line::from("  F1           Toggle this help"),
+            binding(SessionCommand::Help, "Toggle this help"),
+            binding(SessionCommand::ToggleChart, "Show or hide the live chart"),
+            binding(SessionCommand::ToggleKeyboard, "Show or hide the keyboard heatmap"),
             Line::from("  Green text   Correct characters"),
             Line::from("  Red text     Incorrect characters"),
             Line::from("  Yellow       Current cursor position"),
@@ -802,20 +1186,21 @@ impl Render {
             Line::from("  Mono font fallback supported"),
             Line::from("  Screen reader compatible"),
             Line::from(""),
-            Line::from("Press F1 again to close this help").alignment(Alignment::Center),
+            Line::from(format!("Press {} again to close this help", help_label))
+                .alignment(Alignment::Center),
         ];
 
         let help_popup = Paragraph::new(help_content)
             .block(
                 Block::default()
                     .borders(Borders::ALL)
-                    .title("Help - F1 to close")
+                    .title(format!("Help - {} to close", help_label))
                     .title_alignment(Alignment::Center)
             )
-            .style(Style::default().fg(colors.normal_text))
+            .style(colors.normal_text)
             .wrap(ratatui::widgets::Wrap { trim: false });
 
-        frame.render_widget(help_popup, popup_area);
+        help_popup.render(popup_area, buf);
     }
 
     /// Calculate centered rectangle for popups
@@ -838,38 +1223,6 @@ impl Render {
             ])
             .split(popup_layout[1])[1]
     }
-
-    // ANSI render methods (placeholder implementations for now)
-    fn render_header_ansi(&mut self, _row: u16, _width: u16) -> Result<()> {
-        // Placeholder implementation
-        Ok(())
-    }
-
-    fn render_typing_pane_ansi(&mut self, _row: u16, _width: u16, _height: u16) -> Result<()> {
-        // Placeholder implementation
-        Ok(())
-    }
-
-    fn render_status_bar_ansi(&mut self, _row: u16, _width: u16) -> Result<()> {
-        // Placeholder implementation
-        Ok(())
-    }
-
-    fn render_progress_bar_ansi(&mut self, _row: u16, _width: u16) -> Result<()> {
-        // Placeholder implementation
-        Ok(())
-    }
-
-    fn render_help_bar_ansi(&mut self, _row: u16, _width: u16) -> Result<()> {
-        // Placeholder implementation
-        Ok(())
-    }
-
-    fn render_help_overlay_ansi(&mut self, _width: u16, _height: u16) -> Result<()> {
-        // Placeholder implementation
-        Ok(())
-    }
-
 }
 
 impl Default for Render {
@@ -880,11 +1233,371 @@ impl Default for Render {
             frame_count: 0,
             last_render_time: None,
             colors: UiColors::default(),
+            palette: ThemePalette::default(),
+            color_support: ColorSupport::TrueColor,
             show_help: false,
             ansi_renderer: AnsiRenderer::new(),
+            previous_frame: Buffer::empty(Rect::default()),
             line_cache: std::collections::HashMap::new(),
             cache_hits: 0,
             cache_misses: 0,
+            layout_emulation: None,
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use centotype_core::{IncrementalClassifier, LevelManager};
+    use ratatui::backend::TestBackend;
+
+    fn typing_state() -> RenderState {
+        RenderState {
+            target_text: "the quick brown fox jumps over the lazy dog".to_string(),
+            typed_text: "the quack".to_string(),
+            cursor_position: 9,
+            live_metrics: LiveMetrics {
+                effective_wpm: 42.0,
+                accuracy: 96.5,
+                current_streak: 7,
+                ..LiveMetrics::default()
+            },
+            level_info: LevelInfo {
+                level_id: 3,
+                tier: 1,
+                description: "Home row".to_string(),
+            },
+            session_duration: Duration::from_secs(75),
+            ..RenderState::default()
+        }
+    }
+
+    fn session_result() -> SessionResult {
+        SessionResult {
+            session_id: uuid::Uuid::nil(),
+            mode: TrainingMode::Arcade {
+                level: LevelId::new(3).unwrap(),
+            },
+            completed_at: chrono::DateTime::default(),
+            duration_seconds: 75.0,
+            metrics: FinalMetrics {
+                raw_wpm: 48.0,
+                effective_wpm: 45.0,
+                accuracy: 97.5,
+                consistency: 88.0,
+                longest_streak: 31,
+                errors: ErrorStats::default(),
+                latency_p99: Duration::from_millis(20),
+                keystroke_accuracy: 95.0,
+                corrections: CorrectionStats::default(),
+                slowest_words: Vec::new(),
+                layout_errors: LayoutErrorStats::default(),
+                finger_usage: FingerUsageStats::default(),
+                key_timing: KeyTimingStats::default(),
+            },
+            skill_index: 712.0,
+            grade: Grade::B,
+            stars: 2,
+            unverified: false,
+        }
+    }
+
+    fn session_report() -> SessionReport {
+        let target = "the quick brown fox jumps over the lazy dog";
+        let typed = "the quack brwn fox jumps";
+        let mut classifier = IncrementalClassifier::new(target);
+        typed.chars().for_each(|ch| classifier.push_char(ch));
+        let errors = classifier.analysis().error_positions;
+        SessionReport {
+            result: session_result(),
+            targets: Some(LevelManager::new().target_metrics(LevelId::new(3).unwrap())),
+            target_text: target.to_string(),
+            typed_text: typed.to_string(),
+            weak_keys: results::weak_keys(&errors),
+            slow_words: Vec::new(),
+            improvement_areas: Vec::new(),
+            strengths: Vec::new(),
+            errors,
+            wpm_timeline: vec![38.0, 44.0, 47.0, 41.0, 52.0, 49.0],
+        }
+    }
+
+    /// Draw a state through ratatui's test backend
+    fn draw(state: &RenderState, show_help: bool) -> Buffer {
+        draw_sized(state, show_help, 80, 24)
+    }
+
+    fn draw_sized(state: &RenderState, show_help: bool, width: u16, height: u16) -> Buffer {
+        let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+        terminal
+            .draw(|frame| {
+                Render::draw_comprehensive_ui(state, &UiColors::default(), show_help, frame.buffer_mut())
+            })
+            .unwrap();
+        terminal.backend().buffer().clone()
+    }
+
+    /// Compare the buffer's text with `tests/snapshots/<name>.txt`. Run with
+    /// `UPDATE_SNAPSHOTS=1` to write the snapshot after a deliberate change.
+    fn assert_snapshot(name: &str, buffer: &Buffer) {
+        let width = buffer.area.width as usize;
+        let text: String = buffer
+            .content()
+            .chunks(width)
+            .map(|row| {
+                let line: String = row.iter().map(|cell| cell.symbol.as_str()).collect();
+                format!("{}\n", line.trim_end())
+            })
+            .collect();
+
+        let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/snapshots")
+            .join(format!("{}.txt", name));
+        if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+            std::fs::write(&path, &text).unwrap();
+        }
+        let expected = std::fs::read_to_string(&path)
+            .unwrap_or_else(|e| panic!("{}: {}", path.display(), e));
+        assert_eq!(text, expected, "snapshot {} differs", name);
+    }
+
+    #[test]
+    fn test_layout_snapshots() {
+        let typing = typing_state();
+        let buffer = draw(&typing, false);
+        assert_snapshot("typing", &buffer);
+
+        // Mistyped characters carry the error background
+        let error_bg = UiColors::default().error_bg.bg;
+        let (x, y) = find_cell(&buffer, |cell| Some(cell.bg) == error_bg).unwrap();
+        assert_eq!(buffer.get(x, y).symbol, "i");
+
+        let paused = RenderState {
+            is_paused: true,
+            ..typing_state()
+        };
+        assert_snapshot("paused", &draw(&paused, false));
+
+        assert_snapshot("help", &draw(&typing, true));
+
+        let completed = RenderState {
+            is_completed: true,
+            results: Some(session_report()),
+            ..typing_state()
+        };
+        assert_snapshot("results", &draw(&completed, false));
+
+        assert_snapshot("compact", &draw_sized(&typing, false, 40, 12));
+        assert_snapshot("too_small", &draw_sized(&typing, false, 24, 6));
+
+        let ticker = RenderState {
+            text_display: TextDisplay::Ticker,
+            ..typing_state()
+        };
+        assert_snapshot("ticker", &draw(&ticker, false));
+
+        let code = "fn main() {\n    let total = 1 + 2;\n    println!(\"{}\", total);\n}";
+        let paragraph = RenderState {
+            text_display: TextDisplay::Paragraph,
+            target_text: code.to_string(),
+            typed_text: code[..20].to_string(),
+            cursor_position: 20,
+            ..typing_state()
+        };
+        assert_snapshot("paragraph", &draw(&paragraph, false));
+    }
+
+    fn find_cell(buffer: &Buffer, matches: impl Fn(&Cell) -> bool) -> Option<(u16, u16)> {
+        let area = buffer.area;
+        (0..area.height)
+            .flat_map(|y| (0..area.width).map(move |x| (x, y)))
+            .find(|&(x, y)| matches(buffer.get(x, y)))
+    }
+
+    #[test]
+    fn test_resize_keeps_caret_line() {
+        let target = "the quick brown fox jumps over the lazy dog ".repeat(20);
+        let state = RenderState {
+            typed_text: target[..200].to_string(),
+            cursor_position: 200,
+            target_text: target,
+            ..typing_state()
+        };
+
+        // The caret stays on the middle line of the window at every width
+        // of a layout, with the word it is in kept whole
+        let caret_bg = UiColors::default().cursor_bg.bg;
+        let mut caret_rows = Vec::new();
+        for width in [100, 80, 60, 40] {
+            let buffer = draw_sized(&state, false, width, 24);
+            let (x, y) = find_cell(&buffer, |cell| Some(cell.bg) == caret_bg).unwrap();
+            assert_eq!(buffer.get(x, y).symbol, "s");
+            assert_eq!(buffer.get(x - 4, y).symbol, "j", "width {}", width);
+            let text_rows = (0..24).filter(|&row| {
+                (row == y - 1 || row == y + 1) && buffer.get(1, row).symbol != " "
+            });
+            assert_eq!(text_rows.count(), 2, "width {}", width);
+            caret_rows.push(y);
+        }
+        assert_eq!(caret_rows[0], caret_rows[1]);
+        assert_eq!(caret_rows[2], caret_rows[3]);
+    }
+
+    #[test]
+    fn test_cell_diff_output() {
+        let mut render = Render {
+            render_state: typing_state(),
+            ..Render::default()
+        };
+        let area = Rect::new(0, 0, 80, 24);
+
+        // The first frame starts from a blank screen
+        let first = render.render_diff(area);
+        assert!(first.starts_with("\x1b[0m\x1b[2J"));
+        assert!(first.contains("brown fox jumps"));
+
+        // Nothing changed, nothing written
+        assert_eq!(render.render_diff(area), "");
+
+        // A keystroke rewrites a handful of cells and never clears
+        render.render_state.typed_text.push('!');
+        render.render_state.cursor_position += 1;
+        let update = render.render_diff(area);
+        assert!(!update.is_empty());
+        assert!(!update.contains("\x1b[2J"));
+        assert!(update.len() < first.len() / 10, "{} bytes", update.len());
+
+        // A new size redraws from blank
+        assert!(render.render_diff(Rect::new(0, 0, 100, 30)).contains("\x1b[2J"));
+    }
+
+    #[test]
+    fn test_side_panel() {
+        let target = "the quick brown fox jumps over the lazy dog";
+        let typed = "the quack b";
+        let started_at = chrono::DateTime::default();
+        let mut session = SessionState {
+            session_id: uuid::Uuid::nil(),
+            mode: TrainingMode::Arcade {
+                level: LevelId::new(3).unwrap(),
+            },
+            target_text: target.to_string(),
+            typed_text: String::new(),
+            cursor_position: 0,
+            started_at,
+            paused_duration: Duration::ZERO,
+            is_paused: false,
+            is_completed: false,
+            keystrokes: Vec::new(),
+        };
+
+        let mut render = Render::default();
+        render.toggle_chart();
+        render.toggle_keyboard();
+        for (i, ch) in typed.chars().enumerate() {
+            session.keystrokes.push(Keystroke {
+                timestamp: started_at + chrono::Duration::milliseconds(i as i64 * 250),
+                char_typed: Some(ch),
+                is_correction: false,
+                cursor_pos: i,
+                physical_char: None,
+                dwell: None,
+                flight: None,
+            });
+            session.typed_text.push(ch);
+            session.cursor_position += 1;
+            let metrics = LiveMetrics {
+                elapsed_seconds: (i + 1) as f64 * 0.25,
+                ..typing_state().live_metrics
+            };
+            render.update_state(&session, &metrics);
+        }
+        let buffer = draw(&render.render_state, false);
+        assert_snapshot("side_panel", &buffer);
+
+        // The next key carries the caret, the mistyped 'i' the error colours
+        let colors = UiColors::default();
+        let panel_key = |bg: Option<Color>| -> Vec<&str> {
+            (0..24)
+                .flat_map(|y| (80 - PANEL_WIDTH..80).map(move |x| (x, y)))
+                .map(|(x, y)| buffer.get(x, y))
+                .filter(|cell| Some(cell.bg) == bg)
+                .map(|cell| cell.symbol.as_str())
+                .collect()
+        };
+        assert_eq!(panel_key(colors.cursor_bg.bg), ["r"]);
+        assert_eq!(panel_key(colors.error_bg.bg), ["i"]);
+
+        // Hidden again, the typing pane takes the full width
+        render.toggle_chart();
+        render.toggle_keyboard();
+        let buffer = draw(&render.render_state, false);
+        assert_eq!(buffer.get(79, 5).symbol, "│");
+    }
+
+    #[test]
+    fn test_theme_roles() {
+        let palette = ThemePalette::for_theme(Theme::Deuteranopia);
+        let (colors, issues) = UiColors::resolve(&palette, ColorSupport::TrueColor);
+        assert!(issues.is_empty(), "{:?}", issues);
+
+        let mut buffer = Buffer::empty(Rect::new(0, 0, 80, 24));
+        Render::draw_comprehensive_ui(&typing_state(), &colors, false, &mut buffer);
+
+        // Mistakes are underlined in the theme's colour, not only tinted
+        let (x, y) = find_cell(&buffer, |cell| cell.bg == Color::Rgb(60, 40, 0)).unwrap();
+        let mistake = buffer.get(x, y);
+        assert_eq!(mistake.underline_color, Color::Rgb(230, 159, 0));
+        assert!(mistake.modifier.contains(Modifier::UNDERLINED | Modifier::BOLD));
+
+        let (x, y) = find_cell(&buffer, |cell| cell.bg == Color::Rgb(240, 228, 66)).unwrap();
+        assert_eq!(buffer.get(x, y).fg, Color::Rgb(0, 0, 0));
+        assert!(buffer.get(x, y).modifier.contains(Modifier::BOLD));
+
+        // The progress bar shows the typed and remaining parts in their roles
+        let (x, y) = find_cell(&buffer, |cell| cell.symbol == "█").unwrap();
+        assert_eq!(Some(buffer.get(x, y).fg), colors.progress_complete.fg);
+        let (x, y) = find_cell(&buffer, |cell| cell.symbol == "░").unwrap();
+        assert_eq!(Some(buffer.get(x, y).fg), colors.progress_remaining.fg);
+        assert_ne!(colors.progress_complete.fg, colors.progress_remaining.fg);
+    }
+
+    #[test]
+    fn test_sgr_sequences() {
+        let mut ansi = AnsiRenderer::new();
+        ansi.begin_frame();
+        let mut cell = Cell::default();
+        cell.set_symbol("x").set_style(
+            Style::default()
+                .fg(Color::LightRed)
+                .bg(Color::Indexed(52))
+                .add_modifier(Modifier::BOLD | Modifier::REVERSED),
+        );
+        ansi.add_cell(2, 4, &cell);
+        ansi.add_cell(2, 5, &cell);
+        cell.reset();
+        ansi.add_cell(3, 0, cell.set_symbol("x"));
+
+        assert_eq!(
+            ansi.finalize_batch(),
+            "\x1b[3;5H\x1b[0;91;48;5;52;1;7mxx\x1b[4;1H\x1b[0mx\x1b[0m"
+        );
+        assert_eq!(ansi.finalize_batch(), "");
+
+        // Themes with an error underline colour it separately
+        ansi.begin_frame();
+        cell.set_style(
+            Style::default()
+                .add_modifier(Modifier::UNDERLINED)
+                .underline_color(Color::Rgb(230, 159, 0)),
+        );
+        ansi.add_cell(0, 0, &cell);
+        cell.set_style(Style::default().underline_color(Color::LightYellow));
+        ansi.add_cell(0, 1, &cell);
+        assert_eq!(
+            ansi.finalize_batch(),
+            "\x1b[1;1H\x1b[0;58;2;230;159;0;4mx\x1b[0;58;5;11;4mx\x1b[0m"
+        );
+    }
+}
@@ -0,0 +1,684 @@
+//! Full-screen summary of a finished session
+//!
+//! Shows the grade and stars, the session's speed, accuracy and consistency
+//! against the level's targets, a sparkline of WPM over time and the target
+//! text with every error marked by its type. The footer lists what can be
+//! done next: retry, go on to the next level, drill the weakest keys or quit.
+use crate::layout::{is_line_break, line_of, scroll_offset, wrap_words, LayoutMode, TAB_WIDTH};
+use centotype_core::error::{ErrorPosition, ErrorType};
+use centotype_core::level::TargetMetrics;
+use centotype_core::types::{LevelId, SessionResult, TrainingMode};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::Style,
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, Sparkline, Widget},
+};
+use std::collections::BTreeMap;
+use std::time::Duration;
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Keys offered by the weak-key drill
+pub const WEAK_KEY_LIMIT: usize = 5;
+/// Most samples in the WPM sparkline
+const WPM_SAMPLES: u32 = 60;
+/// Shown for whitespace and line breaks that would otherwise be invisible
+const SPACE_MARK: &str = "·";
+const LINE_BREAK_MARK: &str = "⏎";
+
+/// What to do after the results screen
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResultsAction {
+    /// Play the same mode again
+    Retry,
+    NextLevel(LevelId),
+    /// Practise the keys missed most often
+    DrillWeakKeys(Vec<char>),
+    /// Practise the words that keep being slow
+    DrillSlowWords(Vec<String>),
+    Quit,
+}
+
+/// Everything the results screen shows about a session
+#[derive(Debug, Clone)]
+pub struct SessionReport {
+    pub result: SessionResult,
+    /// What the level asks for; none outside arcade mode
+    pub targets: Option<TargetMetrics>,
+    pub target_text: String,
+    pub typed_text: String,
+    /// Errors in the typed text, positioned as in `ErrorAnalysis`
+    pub errors: Vec<ErrorPosition>,
+    /// Raw WPM per sample interval
+    pub wpm_timeline: Vec<f64>,
+    /// Keys missed most often, worst first
+    pub weak_keys: Vec<char>,
+    /// Words slow across sessions, from the profile
+    pub slow_words: Vec<String>,
+    /// What the session analysis suggests working on
+    pub improvement_areas: Vec<String>,
+    /// What the session analysis found done well
+    pub strengths: Vec<String>,
+}
+
+/// A finished session and the action picked on its results screen
+#[derive(Debug, Clone)]
+pub struct SessionOutcome {
+    pub report: SessionReport,
+    pub action: ResultsAction,
+}
+
+impl SessionReport {
+    /// The level after this one, for arcade sessions below the last level
+    pub fn next_level(&self) -> Option<LevelId> {
+        match self.result.mode {
+            TrainingMode::Arcade { level } => LevelId::new(level.0 + 1).ok(),
+            _ => None,
+        }
+    }
+
+    /// Actions available on the screen with their keys and labels
+    pub fn actions(&self) -> Vec<(char, String, ResultsAction)> {
+        let mut actions = vec![('r', "Retry".to_string(), ResultsAction::Retry)];
+        if let Some(level) = self.next_level() {
+            actions.push((
+                'n',
+                format!("Level {}", level.0),
+                ResultsAction::NextLevel(level),
+            ));
+        }
+        if !self.weak_keys.is_empty() {
+            let keys: String = self.weak_keys.iter().collect();
+            actions.push((
+                'd',
+                format!("Drill {}", keys),
+                ResultsAction::DrillWeakKeys(self.weak_keys.clone()),
+            ));
+        }
+        if !self.slow_words.is_empty() {
+            actions.push((
+                'w',
+                "Drill slow words".to_string(),
+                ResultsAction::DrillSlowWords(self.slow_words.clone()),
+            ));
+        }
+        actions.push(('q', "Quit".to_string(), ResultsAction::Quit));
+        actions
+    }
+
+    /// The action bound to `key`, if it is available for this session
+    pub fn action_for_key(&self, key: char) -> Option<ResultsAction> {
+        self.actions()
+            .into_iter()
+            .find(|(bound, _, _)| *bound == key.to_ascii_lowercase())
+            .map(|(_, _, action)| action)
+    }
+}
+
+/// Interval between WPM samples for a session lasting `span`, so the
+/// sparkline stays readable however long the session ran
+pub fn sample_interval(span: Duration) -> Duration {
+    (span / WPM_SAMPLES).max(Duration::from_secs(1))
+}
+
+/// The target keys behind the most errors, worst first. Whitespace is left
+/// out since it needs no drilling.
+pub fn weak_keys(errors: &[ErrorPosition]) -> Vec<char> {
+    let mut counts: BTreeMap<char, usize> = BTreeMap::new();
+    for error in errors {
+        if error.error_type == ErrorType::Insertion {
+            continue;
+        }
+        if let Some(expected) = error.expected_char.filter(|ch| !ch.is_whitespace()) {
+            *counts.entry(expected).or_default() += 1;
+        }
+    }
+
+    let mut keys: Vec<(char, usize)> = counts.into_iter().collect();
+    keys.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+    keys.into_iter()
+        .take(WEAK_KEY_LIMIT)
+        .map(|(key, _)| key)
+        .collect()
+}
+
+/// Styles for the parts of the results screen
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ResultsStyles {
+    pub text: Style,
+    pub heading: Style,
+    pub dimmed: Style,
+    /// A metric that reached its target
+    pub met: Style,
+    /// A metric short of its target
+    pub short: Style,
+    pub sparkline: Style,
+    pub correct: Style,
+    pub substitution: Style,
+    pub insertion: Style,
+    pub deletion: Style,
+    pub transposition: Style,
+    /// Target text the session ended before reaching
+    pub untyped: Style,
+    pub key: Style,
+}
+
+/// How a transcript grapheme was typed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Mark {
+    Correct,
+    Error(ErrorType),
+    Untyped,
+}
+
+/// One grapheme of the transcript
+#[derive(Debug, Clone, PartialEq)]
+struct Glyph {
+    text: String,
+    mark: Mark,
+}
+
+impl Glyph {
+    fn new(text: &str, mark: Mark) -> Self {
+        Self {
+            text: text.to_string(),
+            mark,
+        }
+    }
+
+    fn symbol(&self) -> String {
+        let error = matches!(self.mark, Mark::Error(_));
+        match self.text.as_str() {
+            text if is_line_break(text) => if error { LINE_BREAK_MARK } else { " " }.to_string(),
+            "\t" if !error => " ".repeat(TAB_WIDTH),
+            text if error && text.chars().all(char::is_whitespace) => SPACE_MARK.to_string(),
+            text => text.to_string(),
+        }
+    }
+
+    fn style(&self, styles: &ResultsStyles) -> Style {
+        match self.mark {
+            Mark::Correct => styles.correct,
+            Mark::Untyped => styles.untyped,
+            Mark::Error(ErrorType::Substitution) => styles.substitution,
+            Mark::Error(ErrorType::Insertion) => styles.insertion,
+            Mark::Error(ErrorType::Deletion) => styles.deletion,
+            Mark::Error(ErrorType::Transposition) => styles.transposition,
+        }
+    }
+}
+
+/// Line the target and typed text up along the errors: extra characters are
+/// inserted where they were typed, missed ones keep their place
+fn transcript(report: &SessionReport) -> Vec<Glyph> {
+    let target: Vec<&str> = report.target_text.graphemes(true).collect();
+    let typed: Vec<&str> = report.typed_text.graphemes(true).collect();
+    // Insertions are positioned in the typed text, the rest in the target
+    let at = |position: usize, error_type: ErrorType| {
+        report
+            .errors
+            .iter()
+            .any(|error| error.position == position && error.error_type == error_type)
+    };
+
+    let mut glyphs = Vec::with_capacity(target.len());
+    let (mut i, mut j) = (0, 0);
+    while i < target.len() && (j < typed.len() || at(i, ErrorType::Deletion)) {
+        if j < typed.len() && at(j, ErrorType::Insertion) {
+            glyphs.push(Glyph::new(typed[j], Mark::Error(ErrorType::Insertion)));
+            j += 1;
+        } else if at(i, ErrorType::Deletion) {
+            glyphs.push(Glyph::new(target[i], Mark::Error(ErrorType::Deletion)));
+            i += 1;
+        } else if at(i, ErrorType::Transposition) && i + 1 < target.len() {
+            let mark = Mark::Error(ErrorType::Transposition);
+            glyphs.push(Glyph::new(target[i], mark));
+            glyphs.push(Glyph::new(target[i + 1], mark));
+            i += 2;
+            j += 2;
+        } else {
+            let mark = if at(i, ErrorType::Substitution) {
+                Mark::Error(ErrorType::Substitution)
+            } else {
+                Mark::Correct
+            };
+            glyphs.push(Glyph::new(target[i], mark));
+            i += 1;
+            j += 1;
+        }
+    }
+    // Extra characters typed past the end of the target
+    glyphs.extend(
+        typed
+            .iter()
+            .skip(j)
+            .map(|grapheme| Glyph::new(grapheme, Mark::Error(ErrorType::Insertion))),
+    );
+    glyphs.extend(
+        target
+            .iter()
+            .skip(i)
+            .map(|grapheme| Glyph::new(grapheme, Mark::Untyped)),
+    );
+    glyphs
+}
+
+/// Draw the results screen into `area`
+pub fn draw(report: &SessionReport, styles: &ResultsStyles, area: Rect, buf: &mut Buffer) {
+    let full = LayoutMode::for_size(area.width, area.height) == LayoutMode::Full;
+    let block = Block::default()
+        .borders(if full { Borders::ALL } else { Borders::TOP })
+        .title("Session Results");
+    let inner = block.inner(area);
+    block.render(area, buf);
+
+    let details = detail_lines(report, styles);
+    let constraints: Vec<Constraint> = if full {
+        vec![
+            Constraint::Length(1),                    // Grade
+            Constraint::Length(3),                    // Metrics against targets
+            Constraint::Length(details.len() as u16), // Details
+            Constraint::Length(4),                    // WPM sparkline
+            Constraint::Min(2),                       // Transcript
+            Constraint::Length(1),                    // Legend
+            Constraint::Length(1),                    // Actions
+        ]
+    } else {
+        vec![
+            Constraint::Length(1),
+            Constraint::Length(3),
+            Constraint::Min(1),
+            Constraint::Length(1),
+        ]
+    };
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(constraints)
+        .split(inner);
+
+    Paragraph::new(headline(report, styles))
+        .alignment(Alignment::Center)
+        .render(chunks[0], buf);
+    Paragraph::new(metric_lines(report, styles)).render(chunks[1], buf);
+
+    if full {
+        Paragraph::new(details).render(chunks[2], buf);
+        draw_sparkline(report, styles, chunks[3], buf);
+
+        let block = Block::default().borders(Borders::TOP).title("Transcript");
+        let text_area = block.inner(chunks[4]);
+        block.render(chunks[4], buf);
+        draw_transcript(report, styles, text_area, buf);
+
+        Paragraph::new(legend(styles)).render(chunks[5], buf);
+        Paragraph::new(action_line(report, styles)).render(chunks[6], buf);
+    } else {
+        draw_transcript(report, styles, chunks[2], buf);
+        Paragraph::new(action_line(report, styles)).render(chunks[3], buf);
+    }
+}
+
+fn headline<'a>(report: &SessionReport, styles: &ResultsStyles) -> Line<'a> {
+    let result = &report.result;
+    let mut spans = vec![Span::styled(
+        format!(
+            "Grade {:?}  {}{}  Skill index {:.0}",
+            result.grade,
+            "★".repeat(result.stars as usize),
+            "☆".repeat(3usize.saturating_sub(result.stars as usize)),
+            result.skill_index
+        ),
+        styles.heading,
+    )];
+    if result.unverified {
+        spans.push(Span::styled("  Unverified", styles.short));
+    }
+    Line::from(spans)
+}
+
+/// WPM, accuracy and consistency, each against the level's target when
+/// there is one; whether it was reached is spelt out, not only coloured
+fn metric_lines<'a>(report: &SessionReport, styles: &ResultsStyles) -> Vec<Line<'a>> {
+    let metrics = &report.result.metrics;
+    let targets = report.targets.as_ref();
+    let rows = [
+        (
+            "WPM",
+            metrics.effective_wpm,
+            targets.map(|t| t.target_wpm),
+            "",
+        ),
+        (
+            "Accuracy",
+            metrics.accuracy,
+            targets.map(|t| t.target_accuracy),
+            "%",
+        ),
+        (
+            "Consistency",
+            metrics.consistency,
+            targets.map(|t| t.target_consistency),
+            "%",
+        ),
+    ];
+
+    rows.into_iter()
+        .map(|(label, value, target, unit)| {
+            let mut spans = vec![Span::styled(
+                format!("{:<12}{:>6.1}{:<1}", label, value, unit),
+                styles.text,
+            )];
+            if let Some(target) = target {
+                let (mark, style) = if value >= target {
+                    ("✓ met", styles.met)
+                } else {
+                    ("✗ short", styles.short)
+                };
+                spans.push(Span::styled(
+                    format!("  target {:>5.1}{:<1}  ", target, unit),
+                    styles.dimmed,
+                ));
+                spans.push(Span::styled(mark, style));
+            }
+            Line::from(spans)
+        })
+        .collect()
+}
+
+fn detail_lines<'a>(report: &SessionReport, styles: &ResultsStyles) -> Vec<Line<'a>> {
+    let metrics = &report.result.metrics;
+    let corrections = &metrics.corrections;
+    let mut lines = vec![
+        Line::styled(
+            format!(
+                "Raw WPM {:.0}  │  Keystroke accuracy {:.1}%  │  Backspaces {}",
+                metrics.raw_wpm, metrics.keystroke_accuracy, metrics.errors.backspace_count
+            ),
+            styles.dimmed,
+        ),
+        Line::styled(
+            format!(
+                "Errors: {} corrected, {} uncorrected  │  Wasted keystrokes {:.1}%  │  Avg fix {}ms",
+                corrections.corrected_errors,
+                corrections.uncorrected_errors,
+                corrections.wasted_keystroke_ratio() * 100.0,
+                corrections.avg_correction_latency.as_millis()
+            ),
+            styles.dimmed,
+        ),
+    ];
+
+    if !metrics.slowest_words.is_empty() {
+        let slowest = metrics
+            .slowest_words
+            .iter()
+            .take(WEAK_KEY_LIMIT)
+            .map(|timing| format!("{} ({:.0})", timing.word, timing.wpm))
+            .collect::<Vec<_>>()
+            .join("  ");
+        lines.push(Line::styled(
+            format!("Slowest words (WPM): {}", slowest),
+            styles.dimmed,
+        ));
+    }
+    for strength in &report.strengths {
+        lines.push(Line::styled(format!("✓ {}", strength), styles.met));
+    }
+    for area in &report.improvement_areas {
+        lines.push(Line::styled(format!("→ {}", area), styles.short));
+    }
+    lines
+}
+
+fn draw_sparkline(report: &SessionReport, styles: &ResultsStyles, area: Rect, buf: &mut Buffer) {
+    let samples: Vec<u64> = report
+        .wpm_timeline
+        .iter()
+        .map(|wpm| wpm.round().max(0.0) as u64)
+        .collect();
+    let peak = samples.iter().copied().max().unwrap_or(0);
+
+    let block = Block::default()
+        .borders(Borders::TOP)
+        .title(format!("WPM over time (peak {})", peak));
+    Sparkline::default()
+        .block(block)
+        .data(&samples)
+        .max(peak.max(1))
+        .style(styles.sparkline)
+        .render(area, buf);
+}
+
+/// The transcript wrapped on words and scrolled so the first error shows
+fn draw_transcript(report: &SessionReport, styles: &ResultsStyles, area: Rect, buf: &mut Buffer) {
+    if area.width == 0 || area.height == 0 {
+        return;
+    }
+
+    let glyphs = transcript(report);
+    let text: String = glyphs.iter().map(|glyph| glyph.text.as_str()).collect();
+    let lines = wrap_words(&text, area.width as usize);
+
+    // Char offset of each glyph, to find the glyphs of each wrapped line
+    let mut starts = Vec::with_capacity(glyphs.len());
+    let mut offset = 0;
+    for glyph in &glyphs {
+        starts.push(offset);
+        offset += glyph.text.chars().count();
+    }
+
+    let first_error = glyphs
+        .iter()
+        .position(|glyph| matches!(glyph.mark, Mark::Error(_)))
+        .map_or(0, |index| starts[index]);
+    let rows = area.height as usize;
+    let top = scroll_offset(line_of(&lines, first_error), lines.len(), rows);
+
+    let drawn: Vec<Line> = lines
+        .iter()
+        .skip(top)
+        .take(rows)
+        .map(|line| {
+            let spans: Vec<Span> = glyphs
+                .iter()
+                .zip(&starts)
+                .filter(|(_, start)| line.contains(start))
+                .map(|(glyph, _)| Span::styled(glyph.symbol(), glyph.style(styles)))
+                .collect();
+            Line::from(spans)
+        })
+        .collect();
+    Paragraph::new(drawn).render(area, buf);
+}
+
+fn legend<'a>(styles: &ResultsStyles) -> Line<'a> {
+    Line::from(vec![
+        Span::styled("wrong", styles.substitution),
+        Span::styled("  ", styles.dimmed),
+        Span::styled("extra", styles.insertion),
+        Span::styled("  ", styles.dimmed),
+        Span::styled("missed", styles.deletion),
+        Span::styled("  ", styles.dimmed),
+        Span::styled("swapped", styles.transposition),
+    ])
+}
+
+fn action_line<'a>(report: &SessionReport, styles: &ResultsStyles) -> Line<'a> {
+    let mut spans = Vec::new();
+    for (key, label, _) in report.actions() {
+        if !spans.is_empty() {
+            spans.push(Span::raw("  "));
+        }
+        spans.push(Span::styled(format!("[{}]", key), styles.key));
+        spans.push(Span::styled(format!(" {}", label), styles.text));
+    }
+    Line::from(spans)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use centotype_core::types::{FinalMetrics, Grade};
+
+    fn error(position: usize, error_type: ErrorType, expected: Option<char>) -> ErrorPosition {
+        ErrorPosition {
+            position,
+            error_type,
+            expected_char: expected,
+            actual_char: None,
+        }
+    }
+
+    pub(crate) fn report(target: &str, typed: &str, errors: Vec<ErrorPosition>) -> SessionReport {
+        SessionReport {
+            result: SessionResult {
+                session_id: uuid::Uuid::nil(),
+                mode: TrainingMode::Arcade {
+                    level: LevelId::new(7).unwrap(),
+                },
+                completed_at: chrono::Utc::now(),
+                duration_seconds: 30.0,
+                metrics: FinalMetrics {
+                    raw_wpm: 44.0,
+                    effective_wpm: 41.0,
+                    accuracy: 93.5,
+                    consistency: 81.0,
+                    longest_streak: 20,
+                    errors: Default::default(),
+                    latency_p99: Duration::from_millis(20),
+                    keystroke_accuracy: 90.0,
+                    corrections: Default::default(),
+                    slowest_words: Vec::new(),
+                    layout_errors: Default::default(),
+                    finger_usage: Default::default(),
+                    key_timing: Default::default(),
+                },
+                skill_index: 512.0,
+                grade: Grade::B,
+                stars: 2,
+                unverified: false,
+            },
+            targets: Some(TargetMetrics {
+                target_wpm: 26.0,
+                target_accuracy: 95.0,
+                target_consistency: 73.0,
+            }),
+            target_text: target.to_string(),
+            typed_text: typed.to_string(),
+            weak_keys: weak_keys(&errors),
+            slow_words: Vec::new(),
+            improvement_areas: Vec::new(),
+            strengths: Vec::new(),
+            errors,
+            wpm_timeline: vec![30.0, 42.0, 45.0, 38.0, 50.0],
+        }
+    }
+
+    fn marks(report: &SessionReport) -> String {
+        transcript(report)
+            .iter()
+            .map(|glyph| match glyph.mark {
+                Mark::Correct => '.',
+                Mark::Untyped => '_',
+                Mark::Error(ErrorType::Substitution) => 's',
+                Mark::Error(ErrorType::Insertion) => 'i',
+                Mark::Error(ErrorType::Deletion) => 'd',
+                Mark::Error(ErrorType::Transposition) => 't',
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_transcript_alignment() {
+        // "the cat sat" typed as "teh cxat st" and stopped early
+        let errors = vec![
+            error(1, ErrorType::Transposition, Some('h')),
+            error(5, ErrorType::Insertion, None),
+            error(9, ErrorType::Deletion, Some('a')),
+        ];
+        let report = report("the cat sat on", "teh cxat st", errors);
+        let glyphs = transcript(&report);
+
+        assert_eq!(marks(&report), ".tt..i....d.___");
+        assert_eq!(glyphs[5].text, "x");
+        assert_eq!(glyphs[10].text, "a");
+
+        let report = super::tests::report(
+            "ab cd",
+            "xb cde",
+            vec![
+                error(0, ErrorType::Substitution, Some('a')),
+                error(5, ErrorType::Insertion, None),
+            ],
+        );
+        assert_eq!(marks(&report), "s....i");
+    }
+
+    #[test]
+    fn test_weak_keys_and_actions() {
+        let errors = vec![
+            error(0, ErrorType::Substitution, Some('q')),
+            error(3, ErrorType::Deletion, Some(' ')),
+            error(4, ErrorType::Substitution, Some('z')),
+            error(6, ErrorType::Insertion, None),
+            error(9, ErrorType::Substitution, Some('z')),
+        ];
+        assert_eq!(weak_keys(&errors), vec!['z', 'q']);
+
+        let mut report = report("", "", errors);
+        assert_eq!(report.action_for_key('r'), Some(ResultsAction::Retry));
+        assert_eq!(
+            report.action_for_key('N'),
+            Some(ResultsAction::NextLevel(LevelId::new(8).unwrap()))
+        );
+        assert_eq!(
+            report.action_for_key('d'),
+            Some(ResultsAction::DrillWeakKeys(vec!['z', 'q']))
+        );
+        assert_eq!(report.action_for_key('x'), None);
+        assert_eq!(report.action_for_key('w'), None);
+
+        report.slow_words = vec!["rhythm".to_string()];
+        assert_eq!(
+            report.action_for_key('w'),
+            Some(ResultsAction::DrillSlowWords(vec!["rhythm".to_string()]))
+        );
+
+        // Nothing to drill and no level after the last
+        let mut last = super::tests::report("", "", Vec::new());
+        last.result.mode = TrainingMode::Arcade {
+            level: LevelId::new(100).unwrap(),
+        };
+        assert_eq!(last.action_for_key('d'), None);
+        assert_eq!(last.action_for_key('n'), None);
+
+        assert_eq!(
+            sample_interval(Duration::from_secs(30)),
+            Duration::from_secs(1)
+        );
+        assert_eq!(
+            sample_interval(Duration::from_secs(600)),
+            Duration::from_secs(10)
+        );
+    }
+
+    #[test]
+    fn test_details_show_the_session_analysis() {
+        let mut report = report("the cat", "the cat", Vec::new());
+        report.strengths = vec!["Rolling between keys".to_string()];
+        report.improvement_areas = vec!["Slow on the left pinky".to_string()];
+
+        let text: Vec<String> = detail_lines(&report, &ResultsStyles::default())
+            .iter()
+            .map(|line| {
+                line.spans
+                    .iter()
+                    .map(|span| span.content.as_ref())
+                    .collect()
+            })
+            .collect();
+        assert_eq!(text[text.len() - 2], "✓ Rolling between keys");
+        assert_eq!(text[text.len() - 1], "→ Slow on the left pinky");
+    }
+}
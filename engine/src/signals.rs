@@ -0,0 +1,296 @@
+//! Signal and panic handling that keeps the terminal usable
+//!
+//! Signal handlers only record what arrived; the typing loop picks the
+//! signals up with [`take_pending`] and pauses, suspends or shuts down with
+//! full access to the session. Two paths cannot wait for the loop: a panic,
+//! which with `panic = "abort"` runs no destructors, and a second
+//! termination signal while the first is unhandled. Both restore the
+//! terminal directly with [`restore_terminal`], which only needs the
+//! terminal modes [`TtyManager`] mirrors here. The handlers themselves are
+//! `signal-hook`'s; ours run on its listener thread, outside signal context.
+//!
+//! [`TtyManager`]: crate::tty::TtyManager
+use std::sync::atomic::{AtomicI32, AtomicU8, Ordering};
+use std::sync::Once;
+
+/// Terminal modes that need undoing, mirrored from the TTY manager
+pub(crate) mod mode {
+    pub const RAW: u8 = 1;
+    pub const ALTERNATE_SCREEN: u8 = 1 << 1;
+    pub const MOUSE_CAPTURE: u8 = 1 << 2;
+    pub const BRACKETED_PASTE: u8 = 1 << 3;
+    pub const KEYBOARD_ENHANCEMENT: u8 = 1 << 4;
+    pub const CURSOR_HIDDEN: u8 = 1 << 5;
+}
+
+static ACTIVE_MODES: AtomicU8 = AtomicU8::new(0);
+
+/// Signals recorded by the handlers
+mod pending {
+    pub const TERMINATE: u8 = 1;
+    pub const SUSPEND: u8 = 1 << 1;
+    pub const CONTINUE: u8 = 1 << 2;
+}
+
+static PENDING: AtomicU8 = AtomicU8::new(0);
+static TERMINATE_SIGNAL: AtomicI32 = AtomicI32::new(0);
+
+/// A signal the typing loop has to act on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SessionSignal {
+    /// SIGTERM, SIGHUP or SIGINT: save the session and exit
+    Terminate(i32),
+    /// SIGTSTP: pause, restore the terminal and stop
+    Suspend,
+    /// SIGCONT after a stop the loop did not initiate
+    Continue,
+}
+
+/// Record the terminal modes currently enabled
+pub(crate) fn set_active_modes(modes: u8) {
+    ACTIVE_MODES.store(modes, Ordering::SeqCst);
+}
+
+/// Signals received since the last call, termination first
+pub fn take_pending() -> Vec<SessionSignal> {
+    let pending = PENDING.swap(0, Ordering::SeqCst);
+    let mut signals = Vec::new();
+    if pending & pending::TERMINATE != 0 {
+        // Keep the flag so a second signal knows the first went unanswered
+        PENDING.fetch_or(pending::TERMINATE, Ordering::SeqCst);
+        signals.push(SessionSignal::Terminate(TERMINATE_SIGNAL.load(Ordering::SeqCst)));
+    }
+    if pending & pending::SUSPEND != 0 {
+        signals.push(SessionSignal::Suspend);
+    }
+    if pending & pending::CONTINUE != 0 {
+        signals.push(SessionSignal::Continue);
+    }
+    signals
+}
+
+/// Restore the terminal after a panic, then print the panic as usual.
+/// Installing more than once has no further effect.
+pub fn install_panic_hook() {
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| {
+        let previous = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            restore_terminal();
+            previous(info);
+        }));
+    });
+}
+
+/// Undo every terminal mode the TTY manager enabled. Safe to call from any
+/// thread and more than once; only the first call writes anything.
+pub fn restore_terminal() {
+    let modes = ACTIVE_MODES.swap(0, Ordering::SeqCst);
+    if modes & mode::CURSOR_HIDDEN != 0 {
+        write_stdout(b"\x1b[?25h");
+    }
+    if modes & mode::KEYBOARD_ENHANCEMENT != 0 {
+        write_stdout(b"\x1b[<1u");
+    }
+    if modes & mode::BRACKETED_PASTE != 0 {
+        write_stdout(b"\x1b[?2004l");
+    }
+    if modes & mode::MOUSE_CAPTURE != 0 {
+        write_stdout(b"\x1b[?1006l\x1b[?1015l\x1b[?1003l\x1b[?1002l\x1b[?1000l");
+    }
+    if modes & mode::ALTERNATE_SCREEN != 0 {
+        write_stdout(b"\x1b[?1049l");
+    }
+    if modes & mode::RAW != 0 {
+        platform::restore_termios();
+    }
+}
+
+fn write_stdout(bytes: &[u8]) {
+    platform::write_stdout(bytes);
+}
+
+pub use platform::{save_termios, suspend_process, SignalGuard};
+
+#[cfg(unix)]
+mod platform {
+    use super::{pending, restore_terminal, PENDING, TERMINATE_SIGNAL};
+    use centotype_core::types::*;
+    use nix::sys::termios::{self, SetArg, Termios};
+    use signal_hook::consts::{SIGCONT, SIGHUP, SIGINT, SIGTERM, SIGTSTP};
+    use signal_hook::iterator::Signals;
+    use signal_hook::low_level;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::OnceLock;
+
+    const HANDLED: [libc::c_int; 5] = [SIGTERM, SIGHUP, SIGINT, SIGTSTP, SIGCONT];
+
+    /// Terminal attributes from before raw mode
+    static ORIGINAL_TERMIOS: OnceLock<libc::termios> = OnceLock::new();
+
+    /// Whether a [`SignalGuard`] is alive; without one the signals act as
+    /// they would if never handled
+    static GUARDED: AtomicBool = AtomicBool::new(false);
+
+    /// The thread that receives the signals, started by the first guard
+    static LISTENER: OnceLock<std::result::Result<(), String>> = OnceLock::new();
+
+    /// Remember the cooked terminal attributes; call before entering raw mode
+    pub fn save_termios() {
+        if ORIGINAL_TERMIOS.get().is_some() {
+            return;
+        }
+        if let Ok(termios) = termios::tcgetattr(std::io::stdin()) {
+            let _ = ORIGINAL_TERMIOS.set(termios.into());
+        }
+    }
+
+    pub(super) fn restore_termios() {
+        if let Some(termios) = ORIGINAL_TERMIOS.get() {
+            let _ = termios::tcsetattr(
+                std::io::stdin(),
+                SetArg::TCSANOW,
+                &Termios::from(*termios),
+            );
+        }
+    }
+
+    /// Writes straight to the descriptor, so a stuck typing loop holding
+    /// the stdout lock cannot block the restore
+    pub(super) fn write_stdout(mut bytes: &[u8]) {
+        while let Ok(written @ 1..) = nix::unistd::write(libc::STDOUT_FILENO, bytes) {
+            bytes = bytes.get(written..).unwrap_or_default();
+        }
+    }
+
+    fn on_signal(signal: libc::c_int) {
+        if !GUARDED.load(Ordering::SeqCst) {
+            let _ = low_level::emulate_default_handler(signal);
+            return;
+        }
+        match signal {
+            SIGTSTP => {
+                PENDING.fetch_or(pending::SUSPEND, Ordering::SeqCst);
+            }
+            SIGCONT => {
+                PENDING.fetch_or(pending::CONTINUE, Ordering::SeqCst);
+            }
+            _ => {
+                if PENDING.load(Ordering::SeqCst) & pending::TERMINATE != 0 {
+                    // The loop never got to the first one; don't wait again
+                    restore_terminal();
+                    low_level::exit(128 + signal);
+                }
+                TERMINATE_SIGNAL.store(signal, Ordering::SeqCst);
+                PENDING.fetch_or(pending::TERMINATE, Ordering::SeqCst);
+            }
+        }
+    }
+
+    fn start_listener() -> Result<()> {
+        LISTENER
+            .get_or_init(|| {
+                let mut signals = Signals::new(HANDLED).map_err(|e| e.to_string())?;
+                std::thread::Builder::new()
+                    .name("centotype-signals".to_string())
+                    .spawn(move || signals.forever().for_each(on_signal))
+                    .map(drop)
+                    .map_err(|e| e.to_string())
+            })
+            .clone()
+            .map_err(|e| {
+                CentotypeError::Platform(format!("Failed to install signal handlers: {}", e))
+            })
+    }
+
+    /// Routes session signals to [`super::take_pending`] while alive; on
+    /// drop they get their default actions back
+    pub struct SignalGuard {
+        _private: (),
+    }
+
+    impl SignalGuard {
+        pub fn install() -> Result<Self> {
+            start_listener()?;
+            PENDING.store(0, Ordering::SeqCst);
+            GUARDED.store(true, Ordering::SeqCst);
+            Ok(Self { _private: () })
+        }
+    }
+
+    impl Drop for SignalGuard {
+        fn drop(&mut self) {
+            GUARDED.store(false, Ordering::SeqCst);
+            PENDING.store(0, Ordering::SeqCst);
+        }
+    }
+
+    /// Stop the process until it is continued. The default SIGTSTP action
+    /// stops with SIGSTOP, which unlike a re-raised SIGTSTP the kernel does
+    /// not discard for orphaned process groups.
+    pub fn suspend_process() {
+        let _ = low_level::emulate_default_handler(SIGTSTP);
+        // The SIGCONT that woke us is this suspend's, not a separate stop
+        PENDING.fetch_and(!pending::CONTINUE, Ordering::SeqCst);
+    }
+}
+
+#[cfg(not(unix))]
+mod platform {
+    use centotype_core::types::*;
+    use std::io::Write;
+
+    pub fn save_termios() {}
+
+    pub(super) fn restore_termios() {
+        let _ = crossterm::terminal::disable_raw_mode();
+    }
+
+    pub(super) fn write_stdout(bytes: &[u8]) {
+        let mut stdout = std::io::stdout();
+        let _ = stdout.write_all(bytes);
+        let _ = stdout.flush();
+    }
+
+    /// Console signals are delivered as key events on this platform
+    pub struct SignalGuard;
+
+    impl SignalGuard {
+        pub fn install() -> Result<Self> {
+            Ok(Self)
+        }
+    }
+
+    pub fn suspend_process() {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    #[test]
+    fn test_pending_signals() {
+        use signal_hook::consts::{SIGTERM, SIGTSTP};
+        use signal_hook::low_level;
+        use std::time::{Duration, Instant};
+
+        let guard = SignalGuard::install().unwrap();
+        low_level::raise(SIGTSTP).unwrap();
+        low_level::raise(SIGTERM).unwrap();
+        // The listener thread records them
+        let both = pending::TERMINATE | pending::SUSPEND;
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while PENDING.load(Ordering::SeqCst) != both && Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(1));
+        }
+        assert_eq!(
+            take_pending(),
+            vec![SessionSignal::Terminate(SIGTERM), SessionSignal::Suspend]
+        );
+        // Termination stays pending until the loop exits
+        assert_eq!(take_pending(), vec![SessionSignal::Terminate(SIGTERM)]);
+        drop(guard);
+        assert!(take_pending().is_empty());
+    }
+}
@@ -0,0 +1,413 @@
+//! The text being typed, drawn in one of the [`TextDisplay`] modes
+//!
+//! Typed characters are drawn over the target text rather than beside it:
+//! each grapheme of the target is styled by whether it was typed correctly,
+//! mistyped, is under the caret or is still to come. Positions and line
+//! lengths are measured in grapheme widths, so wide and combined characters
+//! keep the columns lined up in every mode.
+use crate::layout::{
+    grapheme_width, is_line_break, line_of, scroll_offset, wrap_words, TAB_WIDTH,
+};
+use centotype_core::types::TextDisplay;
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::Style,
+    text::{Line, Span},
+    widgets::{Paragraph, Widget},
+};
+use std::ops::Range;
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Rows shown by [`TextDisplay::ThreeLine`]
+const WINDOW_LINES: usize = 3;
+/// Shown for a line break that is under the caret or was mistyped
+const LINE_BREAK_MARK: &str = "⏎";
+
+/// Styles for the parts of the text
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TextStyles {
+    pub untyped: Style,
+    pub correct: Style,
+    pub incorrect: Style,
+    pub caret: Style,
+    pub line_number: Style,
+}
+
+/// The session's progress through its text
+#[derive(Debug, Clone, Copy)]
+pub struct TypingText<'a> {
+    pub target: &'a str,
+    pub typed: &'a str,
+    /// Char index of the next character to type
+    pub cursor: usize,
+}
+
+/// One grapheme as drawn
+#[derive(Debug, Clone)]
+struct Glyph {
+    /// Char indices in the target text; extra typed characters and the
+    /// caret after the end lie past it
+    chars: Range<usize>,
+    symbol: String,
+    width: usize,
+    style: Style,
+    line_break: bool,
+}
+
+impl Glyph {
+    fn span(&self) -> Span<'_> {
+        Span::styled(self.symbol.as_str(), self.style)
+    }
+}
+
+/// Draw `text` into `area`
+pub fn draw(
+    display: TextDisplay,
+    text: &TypingText,
+    styles: &TextStyles,
+    area: Rect,
+    buf: &mut Buffer,
+) {
+    if area.width == 0 || area.height == 0 {
+        return;
+    }
+    let glyphs = glyphs(text, styles);
+    match display {
+        TextDisplay::ThreeLine => draw_window(text, &glyphs, area, buf),
+        TextDisplay::Ticker => draw_ticker(text, &glyphs, area, buf),
+        TextDisplay::Paragraph => draw_paragraph(text, &glyphs, styles, area, buf),
+    }
+}
+
+/// Style and measure every grapheme of the target, then whatever was typed
+/// past its end and a caret once the end is reached
+fn glyphs(text: &TypingText, styles: &TextStyles) -> Vec<Glyph> {
+    let typed: Vec<char> = text.typed.chars().collect();
+    let mut glyphs = Vec::new();
+    let mut start = 0;
+
+    for grapheme in text.target.graphemes(true) {
+        let chars = start..start + grapheme.chars().count();
+        start = chars.end;
+
+        // Line breaks are marked where Enter is due or was missed
+        let (style, marked) = if chars.contains(&text.cursor) {
+            (styles.caret, true)
+        } else if typed.len() >= chars.end {
+            if typed[chars.clone()].iter().copied().eq(grapheme.chars()) {
+                (styles.correct, false)
+            } else {
+                (styles.incorrect, true)
+            }
+        } else {
+            (styles.untyped, false)
+        };
+
+        let line_break = is_line_break(grapheme);
+        let symbol = match grapheme {
+            _ if line_break && marked => LINE_BREAK_MARK.to_string(),
+            _ if line_break => " ".to_string(),
+            "\t" => " ".repeat(TAB_WIDTH),
+            _ => grapheme.to_string(),
+        };
+        glyphs.push(Glyph {
+            chars,
+            symbol,
+            width: grapheme_width(grapheme),
+            style,
+            line_break,
+        });
+    }
+
+    // Insertions past the end of the target
+    for (index, &ch) in typed.iter().enumerate().skip(start) {
+        let symbol = if ch.is_control() { ' ' } else { ch };
+        glyphs.push(Glyph {
+            chars: index..index + 1,
+            width: grapheme_width(symbol.encode_utf8(&mut [0; 4])).max(1),
+            symbol: symbol.to_string(),
+            style: styles.incorrect,
+            line_break: false,
+        });
+    }
+    if text.cursor >= start {
+        let at = text.cursor.max(typed.len());
+        glyphs.push(Glyph {
+            chars: at..at + 1,
+            symbol: " ".to_string(),
+            width: 1,
+            style: styles.caret,
+            line_break: false,
+        });
+    }
+    glyphs
+}
+
+/// Index of the glyph under the caret
+fn caret_glyph(text: &TypingText, glyphs: &[Glyph]) -> usize {
+    glyphs
+        .iter()
+        .position(|glyph| glyph.chars.contains(&text.cursor))
+        .unwrap_or(glyphs.len().saturating_sub(1))
+}
+
+/// Group glyphs into the wrapped lines of the target; anything past the
+/// target's end joins its last line
+fn glyph_lines<'a>(glyphs: &'a [Glyph], lines: &[Range<usize>]) -> Vec<&'a [Glyph]> {
+    let mut grouped = Vec::with_capacity(lines.len());
+    let mut first = 0;
+    for (index, line) in lines.iter().enumerate() {
+        let end = if index + 1 == lines.len() {
+            glyphs.len()
+        } else {
+            first + glyphs[first..]
+                .iter()
+                .take_while(|glyph| glyph.chars.start < line.end)
+                .count()
+        };
+        grouped.push(&glyphs[first..end]);
+        first = end;
+    }
+    grouped
+}
+
+/// Three wrapped lines with the caret on the middle one once past the
+/// first, so finishing a line scrolls the text up by one
+fn draw_window(text: &TypingText, glyphs: &[Glyph], area: Rect, buf: &mut Buffer) {
+    let lines = wrap_words(text.target, area.width as usize);
+    let rows = WINDOW_LINES.min(area.height as usize);
+    let caret_line = line_of(&lines, text.cursor);
+    let first = scroll_offset(caret_line, lines.len(), rows);
+
+    let window: Vec<Line> = glyph_lines(glyphs, &lines)
+        .into_iter()
+        .skip(first)
+        .take(rows)
+        .map(|line| Line::from(line.iter().map(Glyph::span).collect::<Vec<_>>()))
+        .collect();
+
+    // Centred in the pane
+    let top = (area.height as usize - rows) / 2;
+    let window_area = Rect {
+        y: area.y + top as u16,
+        height: rows as u16,
+        ..area
+    };
+    Paragraph::new(window).render(window_area, buf);
+}
+
+/// A single line through the middle of the pane, scrolled so the caret
+/// stays in its centre column
+fn draw_ticker(text: &TypingText, glyphs: &[Glyph], area: Rect, buf: &mut Buffer) {
+    let caret = caret_glyph(text, glyphs);
+    let centre = area.width as usize / 2;
+
+    // As much of what came before as fits left of the centre
+    let mut padding = centre;
+    let mut first = caret;
+    while first > 0 && glyphs[first - 1].width <= padding {
+        first -= 1;
+        padding -= glyphs[first].width;
+    }
+
+    let mut spans = vec![Span::raw(" ".repeat(padding))];
+    let mut columns = padding;
+    for glyph in &glyphs[first..] {
+        if columns + glyph.width > area.width as usize {
+            break;
+        }
+        columns += glyph.width;
+        // Line breaks are marked throughout, there is no next line to show them
+        spans.push(if glyph.line_break {
+            Span::styled(LINE_BREAK_MARK, glyph.style)
+        } else {
+            glyph.span()
+        });
+    }
+
+    let row = Rect {
+        y: area.y + area.height / 2,
+        height: 1,
+        ..area
+    };
+    Paragraph::new(Line::from(spans)).render(row, buf);
+}
+
+/// The text as written, line breaks and indentation kept, with a line
+/// number before the first row of each line. Lines wider than the pane
+/// continue on rows without a number.
+fn draw_paragraph(
+    text: &TypingText,
+    glyphs: &[Glyph],
+    styles: &TextStyles,
+    area: Rect,
+    buf: &mut Buffer,
+) {
+    let line_count = text.target.lines().count().max(1);
+    let number_width = line_count.to_string().len();
+    // Number, space, separator, space
+    let gutter = number_width + 3;
+    let text_width = (area.width as usize).saturating_sub(gutter).max(1);
+
+    let lines = wrap_words(text.target, text_width);
+    let rows = area.height as usize;
+    let caret_line = line_of(&lines, text.cursor);
+    let first = scroll_offset(caret_line, lines.len(), rows);
+
+    // Rows that start a line of the text get its number
+    let mut number = 0;
+    let mut starts_line = true;
+    let mut numbered = Vec::with_capacity(lines.len());
+    for line in glyph_lines(glyphs, &lines) {
+        if starts_line {
+            number += 1;
+        }
+        numbered.push((starts_line.then_some(number), line));
+        starts_line = line.last().is_some_and(|glyph| glyph.line_break);
+    }
+
+    let paragraph: Vec<Line> = numbered
+        .into_iter()
+        .skip(first)
+        .take(rows)
+        .map(|(number, line)| {
+            let gutter = match number {
+                Some(number) => format!("{:>width$} │ ", number, width = number_width),
+                None => format!("{:>width$} │ ", "", width = number_width),
+            };
+            let mut spans = vec![Span::styled(gutter, styles.line_number)];
+            spans.extend(line.iter().map(Glyph::span));
+            Line::from(spans)
+        })
+        .collect();
+
+    Paragraph::new(paragraph).render(area, buf);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::style::{Color, Modifier};
+
+    fn styles() -> TextStyles {
+        TextStyles {
+            untyped: Style::default(),
+            correct: Style::default().fg(Color::Green),
+            incorrect: Style::default().fg(Color::Red),
+            caret: Style::default().add_modifier(Modifier::REVERSED),
+            line_number: Style::default().fg(Color::DarkGray),
+        }
+    }
+
+    fn draw_text(display: TextDisplay, text: TypingText, width: u16, height: u16) -> Buffer {
+        let mut buf = Buffer::empty(Rect::new(0, 0, width, height));
+        draw(display, &text, &styles(), buf.area, &mut buf);
+        buf
+    }
+
+    fn row(buf: &Buffer, y: u16) -> String {
+        (0..buf.area.width)
+            .map(|x| buf.get(x, y).symbol.as_str())
+            .collect::<String>()
+            .trim_end()
+            .to_string()
+    }
+
+    #[test]
+    fn test_glyph_styles() {
+        let text = TypingText {
+            target: "cafe\u{301} 日本",
+            typed: "cafe\u{300}",
+            cursor: 5,
+        };
+        let glyphs = glyphs(&text, &styles());
+        let symbols: Vec<&str> = glyphs.iter().map(|glyph| glyph.symbol.as_str()).collect();
+        assert_eq!(symbols, ["c", "a", "f", "e\u{301}", " ", "日", "本"]);
+        assert_eq!(glyphs[0].style, styles().correct);
+        // The whole grapheme is wrong when its mark is
+        assert_eq!(glyphs[3].style, styles().incorrect);
+        assert_eq!(glyphs[4].style, styles().caret);
+        assert_eq!(glyphs[5].width, 2);
+
+        // Extra characters and the caret follow the end of the target
+        let text = TypingText {
+            target: "ab",
+            typed: "abcd",
+            cursor: 4,
+        };
+        let extra = super::glyphs(&text, &styles());
+        assert_eq!(extra.len(), 5);
+        assert_eq!(extra[2].style, styles().incorrect);
+        assert_eq!(extra[4].chars, 4..5);
+        assert_eq!(extra[4].style, styles().caret);
+    }
+
+    #[test]
+    fn test_three_line_window() {
+        let target = "one two three four five six seven eight nine ten";
+        let text = TypingText {
+            target,
+            typed: &target[..30],
+            cursor: 30,
+        };
+        let buf = draw_text(TextDisplay::ThreeLine, text, 10, 5);
+        // The caret line is in the middle with one finished line above
+        assert_eq!(
+            (0..5).map(|y| row(&buf, y)).collect::<Vec<_>>(),
+            ["", "five six", "seven", "eight nine", ""]
+        );
+        assert_eq!(buf.get(2, 2).style().add_modifier, Modifier::REVERSED);
+    }
+
+    #[test]
+    fn test_ticker_keeps_caret_centred() {
+        let target = "the quick brown fox jumps over the lazy dog";
+        for cursor in [0, 10, 25] {
+            let text = TypingText {
+                target,
+                typed: &target[..cursor],
+                cursor,
+            };
+            let buf = draw_text(TextDisplay::Ticker, text, 21, 3);
+            let caret = buf.get(10, 1);
+            assert_eq!(caret.symbol, &target[cursor..cursor + 1]);
+            assert_eq!(caret.style().add_modifier, Modifier::REVERSED);
+            assert_eq!(row(&buf, 0), "");
+        }
+
+        // A wide character left of the centre that doesn't fit is dropped
+        let text = TypingText {
+            target: "日本語abc",
+            typed: "日本語",
+            cursor: 3,
+        };
+        let buf = draw_text(TextDisplay::Ticker, text, 6, 1);
+        assert_eq!(buf.get(0, 0).symbol, " ");
+        assert_eq!(buf.get(1, 0).symbol, "語");
+        assert_eq!(buf.get(3, 0).symbol, "a");
+    }
+
+    #[test]
+    fn test_paragraph_keeps_code_layout() {
+        let target = "fn main() {\n\tlet x = 1;\n    println!(\"{}\", x);\n}";
+        let text = TypingText {
+            target,
+            typed: "fn main() {",
+            cursor: 11,
+        };
+        let buf = draw_text(TextDisplay::Paragraph, text, 22, 6);
+        assert_eq!(
+            (0..6).map(|y| row(&buf, y)).collect::<Vec<_>>(),
+            [
+                "1 │ fn main() {⏎",
+                "2 │     let x = 1;",
+                "3 │     println!(\"{}\",",
+                "  │ x);",
+                "4 │ }",
+                "",
+            ]
+        );
+        assert_eq!(buf.get(0, 0).fg, Color::DarkGray);
+    }
+}
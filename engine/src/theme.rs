@@ -0,0 +1,319 @@
+//! Colour themes: the built-in presets plus user themes loaded from TOML
+//!
+//! A user theme starts from a built-in palette and overrides any of its
+//! colours and text attributes. Every theme is checked for WCAG AA contrast
+//! when it is loaded, so an unreadable theme is reported instead of drawn.
+use crate::palette::{RoleModifiers, Rgb, ThemePalette};
+use centotype_core::types::{CentotypeError, Result, Theme};
+use ratatui::style::Modifier;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+use tracing::{debug, warn};
+
+/// A theme file as written by the user
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ThemeFile {
+    id: String,
+    /// Id of the built-in theme the file starts from
+    base: Option<String>,
+    background: Option<String>,
+    #[serde(default)]
+    colors: ThemeColors,
+    #[serde(default)]
+    modifiers: ThemeModifiers,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ThemeColors {
+    text: Option<String>,
+    pending: Option<String>,
+    correct: Option<String>,
+    incorrect: Option<String>,
+    error_background: Option<String>,
+    error_underline: Option<String>,
+    caret: Option<String>,
+    caret_background: Option<String>,
+    header: Option<String>,
+    metric: Option<String>,
+    dimmed: Option<String>,
+    status_background: Option<String>,
+    progress: Option<String>,
+    progress_remaining: Option<String>,
+    warning: Option<String>,
+    success: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ThemeModifiers {
+    pending: Option<Vec<ModifierName>>,
+    correct: Option<Vec<ModifierName>>,
+    incorrect: Option<Vec<ModifierName>>,
+    caret: Option<Vec<ModifierName>>,
+    header: Option<Vec<ModifierName>>,
+    metric: Option<Vec<ModifierName>>,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum ModifierName {
+    Bold,
+    Dim,
+    Italic,
+    Underlined,
+    Reversed,
+    CrossedOut,
+}
+
+impl ModifierName {
+    fn modifier(self) -> Modifier {
+        match self {
+            ModifierName::Bold => Modifier::BOLD,
+            ModifierName::Dim => Modifier::DIM,
+            ModifierName::Italic => Modifier::ITALIC,
+            ModifierName::Underlined => Modifier::UNDERLINED,
+            ModifierName::Reversed => Modifier::REVERSED,
+            ModifierName::CrossedOut => Modifier::CROSSED_OUT,
+        }
+    }
+}
+
+impl ThemeFile {
+    fn into_palette(self) -> Result<(String, ThemePalette)> {
+        let base = match self.base.as_deref() {
+            None => Theme::Default,
+            Some(base) => Theme::ALL
+                .into_iter()
+                .find(|theme| theme.id() == base)
+                .ok_or_else(|| CentotypeError::Config(format!("Unknown base theme '{}'", base)))?,
+        };
+        let mut palette = ThemePalette::for_theme(base);
+        let colors = self.colors;
+
+        let overrides = [
+            (self.background, &mut palette.background),
+            (colors.text, &mut palette.normal_text),
+            (colors.pending, &mut palette.pending_text),
+            (colors.correct, &mut palette.correct_text),
+            (colors.incorrect, &mut palette.incorrect_text),
+            (colors.error_background, &mut palette.error_bg),
+            (colors.caret, &mut palette.cursor),
+            (colors.caret_background, &mut palette.cursor_bg),
+            (colors.header, &mut palette.accent),
+            (colors.metric, &mut palette.metric),
+            (colors.dimmed, &mut palette.dimmed_text),
+            (colors.status_background, &mut palette.status_bg),
+            (colors.progress, &mut palette.progress_complete),
+            (colors.progress_remaining, &mut palette.progress_remaining),
+            (colors.warning, &mut palette.warning),
+            (colors.success, &mut palette.success),
+        ];
+        for (value, field) in overrides {
+            if let Some(value) = value {
+                *field = value.parse()?;
+            }
+        }
+        if let Some(underline) = colors.error_underline {
+            palette.error_underline = Some(underline.parse::<Rgb>()?);
+        }
+
+        let modifiers = self.modifiers;
+        let RoleModifiers {
+            pending,
+            correct,
+            incorrect,
+            caret,
+            header,
+            metric,
+        } = &mut palette.modifiers;
+        let overrides = [
+            (modifiers.pending, pending),
+            (modifiers.correct, correct),
+            (modifiers.incorrect, incorrect),
+            (modifiers.caret, caret),
+            (modifiers.header, header),
+            (modifiers.metric, metric),
+        ];
+        for (names, field) in overrides {
+            if let Some(names) = names {
+                *field = names
+                    .into_iter()
+                    .fold(Modifier::empty(), |all, name| all | name.modifier());
+            }
+        }
+
+        Ok((self.id, palette))
+    }
+}
+
+/// Parse a theme file into its id and palette, checking its contrast
+pub fn theme_from_toml(source: &str) -> Result<(String, ThemePalette)> {
+    let file: ThemeFile = toml::from_str(source)
+        .map_err(|e| CentotypeError::Config(format!("Failed to parse theme: {}", e)))?;
+    if file.id.trim().is_empty() {
+        return Err(CentotypeError::Config("Theme id must not be empty".to_string()));
+    }
+    let (id, palette) = file.into_palette()?;
+    palette.validate()?;
+    Ok((id, palette))
+}
+
+/// Every theme available to the application, keyed by id
+#[derive(Debug, Clone)]
+pub struct ThemeRegistry {
+    themes: BTreeMap<String, ThemePalette>,
+}
+
+impl ThemeRegistry {
+    /// Registry holding the built-in themes
+    pub fn new() -> Self {
+        let themes = Theme::ALL
+            .iter()
+            .map(|&theme| (theme.id().to_string(), ThemePalette::for_theme(theme)))
+            .collect();
+
+        Self { themes }
+    }
+
+    /// Add a theme, replacing any theme with the same id
+    pub fn register(&mut self, id: String, palette: ThemePalette) -> Result<()> {
+        palette.validate()?;
+        self.themes.insert(id, palette);
+        Ok(())
+    }
+
+    /// Load every `*.toml` file in `dir`. Invalid files are skipped with a
+    /// warning so one broken theme does not hide the others. Returns the ids
+    /// that were loaded; a missing directory loads nothing.
+    pub fn load_dir(&mut self, dir: &Path) -> Result<Vec<String>> {
+        if !dir.is_dir() {
+            return Ok(Vec::new());
+        }
+
+        let mut paths: Vec<_> = std::fs::read_dir(dir)?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == "toml"))
+            .collect();
+        paths.sort();
+
+        let mut loaded = Vec::new();
+        for path in paths {
+            let result = std::fs::read_to_string(&path)
+                .map_err(CentotypeError::from)
+                .and_then(|source| theme_from_toml(&source))
+                .and_then(|(id, palette)| self.register(id.clone(), palette).map(|_| id));
+
+            match result {
+                Ok(id) => {
+                    debug!(id = %id, path = %path.display(), "Loaded user theme");
+                    loaded.push(id);
+                }
+                Err(e) => warn!(path = %path.display(), "Skipping invalid theme: {}", e),
+            }
+        }
+
+        Ok(loaded)
+    }
+
+    pub fn get(&self, id: &str) -> Option<&ThemePalette> {
+        self.themes.get(id)
+    }
+
+    /// Registered theme ids in alphabetical order
+    pub fn ids(&self) -> impl Iterator<Item = &str> {
+        self.themes.keys().map(String::as_str)
+    }
+}
+
+impl Default for ThemeRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CUSTOM_THEME: &str = r##"
+id = "solarized-dark"
+background = "#002b36"
+
+[colors]
+text = "#eee8d5"
+pending = "#93a1a1"
+correct = "#2aa198"
+incorrect = "#fdf6e3"
+error_background = "#7a1f1d"
+error_underline = "#dc322f"
+caret = "#002b36"
+caret_background = "#b58900"
+header = "#6c9bd2"
+metric = "#eee8d5"
+dimmed = "#93a1a1"
+status_background = "#073642"
+progress_remaining = "#839496"
+
+[modifiers]
+incorrect = ["bold", "underlined"]
+caret = ["bold", "reversed"]
+header = []
+"##;
+
+    #[test]
+    fn test_user_theme_parsing() {
+        let (id, palette) = theme_from_toml(CUSTOM_THEME).unwrap();
+        assert_eq!(id, "solarized-dark");
+        assert_eq!(palette.background, Rgb(0, 43, 54));
+        assert_eq!(palette.error_underline, Some(Rgb(220, 50, 47)));
+        assert_eq!(palette.cursor_bg, Rgb(181, 137, 0));
+        assert_eq!(
+            palette.modifiers.incorrect,
+            Modifier::BOLD | Modifier::UNDERLINED
+        );
+        assert_eq!(palette.modifiers.header, Modifier::empty());
+        // Unset roles keep the base theme's values
+        assert_eq!(palette.modifiers.metric, Modifier::empty());
+        assert_eq!(palette.success, ThemePalette::default().success);
+
+        let (_, based) = theme_from_toml("id = \"mine\"\nbase = \"tritanopia\"").unwrap();
+        assert_eq!(based, ThemePalette::for_theme(Theme::Tritanopia));
+    }
+
+    #[test]
+    fn test_invalid_themes_are_rejected() {
+        // Dark grey text on the dark background fails WCAG AA
+        let low_contrast = CUSTOM_THEME.replace("pending = \"#93a1a1\"", "pending = \"#405050\"");
+        let error = theme_from_toml(&low_contrast).unwrap_err().to_string();
+        assert!(error.contains("pending_text"), "{}", error);
+
+        for (from, to) in [
+            ("#2aa198", "2aa198"),
+            ("text = ", "txt = "),
+            ("\"bold\", \"underlined\"", "\"blink\""),
+            ("id = \"solarized-dark\"", "id = \"\""),
+        ] {
+            let broken = CUSTOM_THEME.replace(from, to);
+            assert!(theme_from_toml(&broken).is_err(), "{} -> {}", from, to);
+        }
+    }
+
+    #[test]
+    fn test_load_dir_skips_invalid_files() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("solarized.toml"), CUSTOM_THEME).unwrap();
+        std::fs::write(dir.path().join("broken.toml"), "id = \"broken\"\nbase = \"neon\"").unwrap();
+        std::fs::write(dir.path().join("notes.txt"), "not a theme").unwrap();
+
+        let mut registry = ThemeRegistry::new();
+        let loaded = registry.load_dir(dir.path()).unwrap();
+        assert_eq!(loaded, vec!["solarized-dark".to_string()]);
+        assert!(registry.get("broken").is_none());
+        assert!(registry.get("deuteranopia").is_some());
+        assert_eq!(registry.ids().count(), Theme::ALL.len() + 1);
+        assert!(registry.load_dir(&dir.path().join("missing")).unwrap().is_empty());
+    }
+}
@@ -1,10 +1,18 @@
 //! Terminal state management with reliable cleanup and RAII pattern
+use crate::signals::{self, mode};
 use centotype_core::types::*;
 use crossterm::{
     cursor,
-    event::{DisableMouseCapture, EnableMouseCapture},
+    event::{
+        DisableBracketedPaste, DisableMouseCapture, EnableBracketedPaste, EnableMouseCapture,
+        KeyboardEnhancementFlags,
+        PopKeyboardEnhancementFlags, PushKeyboardEnhancementFlags,
+    },
     execute,
-    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+    terminal::{
+        disable_raw_mode, enable_raw_mode, supports_keyboard_enhancement, EnterAlternateScreen,
+        LeaveAlternateScreen,
+    },
 };
 use std::io::{self, Write};
 use tracing::{debug, error, info, warn};
@@ -17,6 +25,8 @@ pub struct TtyManager {
     current_state: TerminalState,
     /// Whether we're in an active session requiring cleanup
     needs_cleanup: bool,
+    /// Whether to ask the terminal for key release events
+    key_release_reporting: bool,
 }
 
 /// Terminal state tracking
@@ -25,6 +35,10 @@ struct TerminalState {
     raw_mode: bool,
     alternate_screen: bool,
     mouse_capture: bool,
+    /// Pasted text arrives as one paste event instead of key presses
+    bracketed_paste: bool,
+    /// Kitty keyboard protocol flags pushed
+    keyboard_enhancement: bool,
 }
 
 impl Default for TerminalState {
@@ -33,10 +47,21 @@ impl Default for TerminalState {
             raw_mode: false,
             alternate_screen: false,
             mouse_capture: false,
+            bracketed_paste: false,
+            keyboard_enhancement: false,
         }
     }
 }
 
+/// Report every key as an escape code with its event type, so text keys
+/// produce release events too
+fn key_release_flags() -> KeyboardEnhancementFlags {
+    KeyboardEnhancementFlags::DISAMBIGUATE_ESCAPE_CODES
+        | KeyboardEnhancementFlags::REPORT_EVENT_TYPES
+        | KeyboardEnhancementFlags::REPORT_ALL_KEYS_AS_ESCAPE_CODES
+        | KeyboardEnhancementFlags::REPORT_ALTERNATE_KEYS
+}
+
 impl TtyManager {
     /// Create new TTY manager and capture initial state
     pub fn new() -> Result<Self> {
@@ -48,15 +73,30 @@ impl TtyManager {
             original_state,
             current_state: original_state,
             needs_cleanup: false,
+            key_release_reporting: false,
         })
     }
 
-    /// Enter typing mode: raw mode + alternate screen + mouse capture
+    /// Opt in to key release events for dwell and flight times. Takes effect
+    /// on the next `enter_typing_mode`, and only on terminals that support
+    /// the kitty keyboard protocol.
+    pub fn set_key_release_reporting(&mut self, enabled: bool) {
+        self.key_release_reporting = enabled;
+    }
+
+    /// Whether the terminal is currently reporting key releases
+    pub fn reports_key_releases(&self) -> bool {
+        self.current_state.keyboard_enhancement
+    }
+
+    /// Enter typing mode: raw mode + alternate screen + mouse capture +
+    /// bracketed paste
     pub fn enter_typing_mode(&mut self) -> Result<()> {
         info!("Entering typing mode");
 
         // Enable raw mode for immediate input
         if !self.current_state.raw_mode {
+            signals::save_termios();
             enable_raw_mode().map_err(|e| {
                 CentotypeError::Platform(format!("Failed to enable raw mode: {}", e))
             })?;
@@ -82,12 +122,46 @@ impl TtyManager {
             debug!("Mouse capture enabled");
         }
 
+        // Bracketed paste keeps pasted text from posing as typing
+        if !self.current_state.bracketed_paste {
+            execute!(io::stdout(), EnableBracketedPaste).map_err(|e| {
+                CentotypeError::Platform(format!("Failed to enable bracketed paste: {}", e))
+            })?;
+            self.current_state.bracketed_paste = true;
+            debug!("Bracketed paste enabled");
+        }
+
+        // Key release events need the kitty keyboard protocol; other
+        // terminals keep working with presses only
+        if self.key_release_reporting && !self.current_state.keyboard_enhancement {
+            match supports_keyboard_enhancement() {
+                Ok(true) => {
+                    execute!(io::stdout(), PushKeyboardEnhancementFlags(key_release_flags()))
+                        .map_err(|e| {
+                            CentotypeError::Platform(format!(
+                                "Failed to enable key release reporting: {}",
+                                e
+                            ))
+                        })?;
+                    self.current_state.keyboard_enhancement = true;
+                    debug!("Key release reporting enabled");
+                }
+                Ok(false) => {
+                    info!("Terminal does not report key releases; dwell and flight times are off");
+                }
+                Err(e) => {
+                    warn!("Could not query keyboard enhancement support: {}", e);
+                }
+            }
+        }
+
         // Hide cursor for cleaner typing interface
         execute!(io::stdout(), cursor::Hide).map_err(|e| {
             CentotypeError::Platform(format!("Failed to hide cursor: {}", e))
         })?;
 
         self.needs_cleanup = true;
+        self.mirror_modes();
         info!("Typing mode active");
         Ok(())
     }
@@ -101,6 +175,26 @@ impl TtyManager {
             warn!("Failed to show cursor during cleanup: {}", e);
         }
 
+        // Stop key release reporting
+        if self.current_state.keyboard_enhancement {
+            if let Err(e) = execute!(io::stdout(), PopKeyboardEnhancementFlags) {
+                warn!("Failed to disable key release reporting: {}", e);
+            } else {
+                self.current_state.keyboard_enhancement = false;
+                debug!("Key release reporting disabled");
+            }
+        }
+
+        // Disable bracketed paste
+        if self.current_state.bracketed_paste {
+            if let Err(e) = execute!(io::stdout(), DisableBracketedPaste) {
+                warn!("Failed to disable bracketed paste: {}", e);
+            } else {
+                self.current_state.bracketed_paste = false;
+                debug!("Bracketed paste disabled");
+            }
+        }
+
         // Disable mouse capture
         if self.current_state.mouse_capture {
             if let Err(e) = execute!(io::stdout(), DisableMouseCapture) {
@@ -134,6 +228,7 @@ impl TtyManager {
         }
 
         self.needs_cleanup = false;
+        self.mirror_modes();
         info!("Terminal state restored");
         Ok(())
     }
@@ -145,6 +240,14 @@ impl TtyManager {
         // Best effort cleanup - don't propagate errors
         let _ = execute!(io::stdout(), cursor::Show);
 
+        if self.current_state.keyboard_enhancement {
+            let _ = execute!(io::stdout(), PopKeyboardEnhancementFlags);
+        }
+
+        if self.current_state.bracketed_paste {
+            let _ = execute!(io::stdout(), DisableBracketedPaste);
+        }
+
         if self.current_state.mouse_capture {
             let _ = execute!(io::stdout(), DisableMouseCapture);
         }
@@ -161,6 +264,8 @@ impl TtyManager {
         let _ = io::stdout().flush();
 
         self.needs_cleanup = false;
+        self.current_state = TerminalState::default();
+        self.mirror_modes();
         warn!("Emergency cleanup completed");
     }
 
@@ -201,6 +306,25 @@ impl TtyManager {
 
     // Private helper methods
 
+    /// Let signal and panic handlers know what to undo
+    fn mirror_modes(&self) {
+        let state = &self.current_state;
+        let modes = [
+            (state.raw_mode, mode::RAW),
+            (state.alternate_screen, mode::ALTERNATE_SCREEN),
+            (state.mouse_capture, mode::MOUSE_CAPTURE),
+            (state.bracketed_paste, mode::BRACKETED_PASTE),
+            (state.keyboard_enhancement, mode::KEYBOARD_ENHANCEMENT),
+            (self.needs_cleanup, mode::CURSOR_HIDDEN),
+        ];
+        signals::set_active_modes(
+            modes
+                .into_iter()
+                .filter(|(enabled, _)| *enabled)
+                .fold(0, |bits, (_, bit)| bits | bit),
+        );
+    }
+
     fn detect_current_state() -> Result<TerminalState> {
         // For now, assume terminal starts in normal state
         // In a full implementation, we might try to detect actual state
@@ -290,6 +414,9 @@ mod tests {
         assert!(!initial_state.raw_mode);
         assert!(!initial_state.alternate_screen);
         assert!(!initial_state.mouse_capture);
+        assert!(!initial_state.bracketed_paste);
+        assert!(!initial_state.keyboard_enhancement);
+        assert!(!tty.reports_key_releases());
     }
 
     #[test]
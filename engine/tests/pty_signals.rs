@@ -0,0 +1,288 @@
+// Terminal restoration under job control, termination and panics, and the
+// history a played session leaves behind
+// The test binary re-runs itself inside a pseudo-terminal as the child
+// session, so the real signal handlers and terminal modes are exercised.
+#![cfg(unix)]
+
+use centotype_core::types::*;
+use centotype_core::CentotypeCore;
+use centotype_engine::{signals, CentotypeEngine, TtyManager};
+use centotype_platform::PlatformManager;
+use nix::pty::{openpty, Winsize};
+use nix::sys::signal::{kill, Signal};
+use nix::sys::termios::{tcgetattr, LocalFlags};
+use nix::sys::wait::{waitpid, WaitPidFlag, WaitStatus};
+use nix::unistd::Pid;
+use std::fs::File;
+use std::io::{Read, Write};
+use std::os::unix::process::CommandExt;
+use std::path::Path;
+use std::process::{Child, Command, Stdio};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+const CHILD_ENV: &str = "CENTOTYPE_PTY_CHILD";
+const ENTER_ALTERNATE_SCREEN: &str = "\x1b[?1049h";
+const LEAVE_ALTERNATE_SCREEN: &str = "\x1b[?1049l";
+
+/// A child test process attached to a pty
+struct PtyChild {
+    /// Reaped with `waitpid`, which also reports stops
+    child: Child,
+    /// Kept open to read the terminal modes the child set
+    slave: File,
+    output: Arc<Mutex<Vec<u8>>>,
+    /// Input typed into the terminal
+    input: File,
+}
+
+impl PtyChild {
+    fn spawn(child_test: &str, home: &Path) -> Self {
+        let size = Winsize {
+            ws_row: 30,
+            ws_col: 100,
+            ws_xpixel: 0,
+            ws_ypixel: 0,
+        };
+        let pty = openpty(&size, None).expect("openpty failed");
+        let mut master = File::from(pty.master);
+        let slave = File::from(pty.slave);
+
+        let mut command = Command::new(std::env::current_exe().expect("test binary path"));
+        command
+            .args(["--exact", child_test, "--ignored", "--nocapture", "--test-threads=1"])
+            .env(CHILD_ENV, child_test)
+            .env("TERM", "xterm-256color")
+            .env("HOME", home)
+            .env("XDG_CONFIG_HOME", home.join("config"))
+            .env("XDG_DATA_HOME", home.join("data"))
+            .stdin(Stdio::from(slave.try_clone().expect("pty slave")))
+            .stdout(Stdio::from(slave.try_clone().expect("pty slave")))
+            .stderr(Stdio::from(slave.try_clone().expect("pty slave")))
+            // A process group of its own, away from the test runner's
+            .process_group(0);
+        let child = command.spawn().expect("failed to spawn the pty child");
+
+        let input = master.try_clone().expect("pty master");
+        let output = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&output);
+        std::thread::spawn(move || {
+            let mut buffer = [0u8; 4096];
+            while let Ok(read @ 1..) = master.read(&mut buffer) {
+                sink.lock().expect("output lock").extend_from_slice(&buffer[..read]);
+            }
+        });
+
+        Self {
+            child,
+            slave,
+            output,
+            input,
+        }
+    }
+
+    /// Wait for `pattern` to appear after byte `from`; returns where it ends
+    fn wait_for(&self, pattern: &str, from: usize) -> usize {
+        let deadline = Instant::now() + Duration::from_secs(30);
+        loop {
+            {
+                let output = self.output.lock().expect("output lock");
+                if let Some(found) = output[from.min(output.len())..]
+                    .windows(pattern.len())
+                    .position(|window| window == pattern.as_bytes())
+                {
+                    return from + found + pattern.len();
+                }
+            }
+            assert!(
+                Instant::now() < deadline,
+                "{:?} not seen; output: {:?}",
+                pattern,
+                String::from_utf8_lossy(&self.output.lock().expect("output lock"))
+            );
+            std::thread::sleep(Duration::from_millis(10));
+        }
+    }
+
+    fn type_keys(&mut self, keys: &str) {
+        self.input.write_all(keys.as_bytes()).expect("typing into the pty");
+    }
+
+    fn output_text(&self) -> String {
+        String::from_utf8_lossy(&self.output.lock().expect("output lock")).into_owned()
+    }
+
+    /// Whether the terminal is in canonical (cooked) mode
+    fn is_cooked(&self) -> bool {
+        let termios = tcgetattr(&self.slave).expect("tcgetattr failed");
+        termios.local_flags.contains(LocalFlags::ICANON)
+    }
+
+    fn wait_until_raw(&self) {
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while self.is_cooked() {
+            assert!(Instant::now() < deadline, "terminal never entered raw mode");
+            std::thread::sleep(Duration::from_millis(10));
+        }
+    }
+
+    fn pid(&self) -> Pid {
+        Pid::from_raw(self.child.id() as i32)
+    }
+
+    fn signal(&self, signal: Signal) {
+        kill(self.pid(), signal).expect("kill failed");
+    }
+
+    /// Wait for the child to stop or exit
+    fn wait_status(&self) -> WaitStatus {
+        waitpid(self.pid(), Some(WaitPidFlag::WUNTRACED)).expect("waitpid failed")
+    }
+}
+
+impl Drop for PtyChild {
+    fn drop(&mut self) {
+        // Reaped children are gone; this only matters when a test failed
+        if kill(self.pid(), Signal::SIGKILL).is_ok() {
+            let _ = waitpid(self.pid(), None);
+        }
+    }
+}
+
+#[test]
+fn test_suspend_resume_and_terminate() {
+    let home = tempfile::tempdir().unwrap();
+    let child = PtyChild::spawn("pty_child_session", home.path());
+
+    let entered = child.wait_for(ENTER_ALTERNATE_SCREEN, 0);
+    child.wait_until_raw();
+
+    // Ctrl+Z from the shell: the terminal is handed back before stopping
+    child.signal(Signal::SIGTSTP);
+    let status = child.wait_status();
+    assert!(
+        matches!(status, WaitStatus::Stopped(..)),
+        "child did not stop: {:?}",
+        status
+    );
+    let suspended = child.wait_for(LEAVE_ALTERNATE_SCREEN, entered);
+    assert!(child.is_cooked(), "raw mode left on while stopped");
+
+    // fg: typing mode comes back
+    child.signal(Signal::SIGCONT);
+    let resumed = child.wait_for(ENTER_ALTERNATE_SCREEN, suspended);
+    child.wait_until_raw();
+
+    // kill: the session is saved and the terminal restored on the way out
+    child.signal(Signal::SIGTERM);
+    let status = child.wait_status();
+    assert_eq!(
+        status,
+        WaitStatus::Exited(child.pid(), 128 + Signal::SIGTERM as i32)
+    );
+    child.wait_for(LEAVE_ALTERNATE_SCREEN, resumed);
+    assert!(child.is_cooked(), "raw mode left on after SIGTERM");
+
+    let saved = home
+        .path()
+        .join("data/centotype/interrupted_session.json");
+    let state: SessionState =
+        serde_json::from_str(&std::fs::read_to_string(saved).unwrap()).unwrap();
+    assert!(!state.target_text.is_empty());
+}
+
+#[test]
+fn test_panic_restores_terminal() {
+    let home = tempfile::tempdir().unwrap();
+    let child = PtyChild::spawn("pty_child_panic", home.path());
+
+    let status = child.wait_status();
+    assert!(matches!(status, WaitStatus::Exited(_, code) if code != 0));
+    child.wait_for("pty child panic", 0);
+
+    // The screen is restored before the message, so it stays readable
+    let output = child.output_text();
+    let entered = output.find(ENTER_ALTERNATE_SCREEN).unwrap();
+    let left = output.rfind(LEAVE_ALTERNATE_SCREEN).unwrap();
+    assert!(entered < left && left < output.find("pty child panic").unwrap());
+    assert!(child.is_cooked(), "raw mode left on after panic");
+}
+
+#[test]
+fn test_played_session_is_saved_once() {
+    let home = tempfile::tempdir().unwrap();
+    let mut child = PtyChild::spawn("pty_child_played_session", home.path());
+
+    child.wait_for(ENTER_ALTERNATE_SCREEN, 0);
+    child.wait_until_raw();
+
+    // Type a little, quit with Ctrl+C and leave the results screen
+    child.type_keys("ab");
+    std::thread::sleep(Duration::from_millis(100));
+    child.type_keys("\x03");
+    child.wait_for("Retry", 0);
+    child.type_keys("q");
+    let status = child.wait_status();
+    assert_eq!(status, WaitStatus::Exited(child.pid(), 0));
+
+    let history = home.path().join("data/centotype/sessions.jsonl");
+    let history = std::fs::read_to_string(history).unwrap();
+    assert_eq!(history.lines().count(), 1);
+    let result: SessionResult = serde_json::from_str(history.trim()).unwrap();
+    assert_eq!(result.mode, TrainingMode::Arcade { level: LevelId(1) });
+}
+
+/// The typing session run inside the pty
+#[tokio::test]
+#[ignore]
+async fn pty_child_session() {
+    if std::env::var(CHILD_ENV).as_deref() != Ok("pty_child_session") {
+        return;
+    }
+
+    let core = Arc::new(CentotypeCore::new());
+    let platform = Arc::new(PlatformManager::new().unwrap());
+    let mut engine = CentotypeEngine::new(core, platform).await.unwrap();
+    let mode = TrainingMode::Arcade {
+        level: LevelId::new(1).unwrap(),
+    };
+
+    match engine.run(mode, String::new()).await {
+        Err(CentotypeError::Interrupted(signal)) => std::process::exit(128 + signal),
+        other => panic!("session ended without a signal: {:?}", other.err()),
+    }
+}
+
+/// A session played to its results screen inside the pty
+#[tokio::test]
+#[ignore]
+async fn pty_child_played_session() {
+    if std::env::var(CHILD_ENV).as_deref() != Ok("pty_child_played_session") {
+        return;
+    }
+
+    let core = Arc::new(CentotypeCore::new());
+    let platform = Arc::new(PlatformManager::new().unwrap());
+    let mut engine = CentotypeEngine::new(core, platform).await.unwrap();
+    let mode = TrainingMode::Arcade {
+        level: LevelId::new(1).unwrap(),
+    };
+
+    engine.run(mode, String::new()).await.unwrap();
+}
+
+/// Panics in typing mode without unwinding the TTY manager, as with
+/// `panic = "abort"`
+#[test]
+#[ignore]
+fn pty_child_panic() {
+    if std::env::var(CHILD_ENV).as_deref() != Ok("pty_child_panic") {
+        return;
+    }
+
+    signals::install_panic_hook();
+    let mut tty = TtyManager::new().unwrap();
+    tty.enter_typing_mode().unwrap();
+    std::mem::forget(tty);
+    panic!("pty child panic");
+}
@@ -90,6 +90,9 @@ async fn demo_session_management() -> Result<(), Box<dyn std::error::Error>> {
             char_typed: Some(*ch),
             is_correction: false,
             cursor_pos: i,
+            physical_char: None,
+            dwell: None,
+            flight: None,
         };
 
         session_manager.update_state(StateUpdate::AddKeystroke(keystroke))?;
@@ -299,7 +302,7 @@ async fn demo_performance_monitoring() -> Result<(), Box<dyn std::error::Error>>
     for _ in 0..10 {
         let _ = core.start_session(TrainingMode::Arcade { level: LevelId::new(1)? }, "test text".to_string())?;
         core.process_keystroke(Some('t'), false)?;
-        let _ = core.complete_session()?;
+        let _ = core.complete_session(false)?;
     }
 
     println!("   ⚡ Core Performance:");
@@ -328,6 +331,9 @@ fn generate_sample_keystrokes() -> Vec<Keystroke> {
             char_typed: Some(ch),
             is_correction: false,
             cursor_pos: i,
+            physical_char: None,
+            dwell: None,
+            flight: None,
         })
         .collect()
 }
\ No newline at end of file
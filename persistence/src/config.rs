@@ -1,2 +1,363 @@
-//! config stub implementation
-pub struct Config {}
+//! Layered configuration
+//!
+//! The effective config is built from, lowest precedence first: built-in
+//! defaults, the system file, the user file, `CENTOTYPE_*` environment
+//! variables and `--set key=value` flags. Files use the config file format
+//! and are merged key by key, so a user file only needs the settings it
+//! changes; environment variables and flags take the values `config set`
+//! accepts. The origin of every setting is kept for `config show --origin`.
+use centotype_core::settings::UNSET;
+use centotype_core::types::*;
+use centotype_core::Setting;
+use std::collections::HashMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use tracing::warn;
+
+/// Where the effective value of a setting came from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigOrigin {
+    Default,
+    SystemFile(PathBuf),
+    UserFile(PathBuf),
+    /// Name of the environment variable
+    Environment(String),
+    Flag,
+}
+
+impl fmt::Display for ConfigOrigin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigOrigin::Default => write!(f, "default"),
+            ConfigOrigin::SystemFile(path) => write!(f, "system file {}", path.display()),
+            ConfigOrigin::UserFile(path) => write!(f, "user file {}", path.display()),
+            ConfigOrigin::Environment(name) => write!(f, "environment {}", name),
+            ConfigOrigin::Flag => write!(f, "--set flag"),
+        }
+    }
+}
+
+/// Machine-wide config file: `/etc/centotype/config.toml` on Unix and
+/// `%ProgramData%\centotype\config.toml` on Windows
+pub fn system_config_path() -> Option<PathBuf> {
+    if cfg!(windows) {
+        std::env::var_os("ProgramData")
+            .map(|dir| PathBuf::from(dir).join("centotype").join("config.toml"))
+    } else {
+        Some(PathBuf::from("/etc/centotype/config.toml"))
+    }
+}
+
+/// Environment variable overriding a setting, e.g. `CENTOTYPE_THEME` or
+/// `CENTOTYPE_KEYBINDINGS_QUIT`
+pub fn env_var(setting: Setting) -> String {
+    format!(
+        "CENTOTYPE_{}",
+        setting.name().to_ascii_uppercase().replace('.', "_")
+    )
+}
+
+/// The effective config and where each setting came from
+#[derive(Debug, Clone)]
+pub struct ResolvedConfig {
+    pub config: Config,
+    origins: HashMap<Setting, ConfigOrigin>,
+}
+
+impl ResolvedConfig {
+    pub fn origin(&self, setting: Setting) -> &ConfigOrigin {
+        self.origins.get(&setting).unwrap_or(&ConfigOrigin::Default)
+    }
+}
+
+/// Builds the effective config one layer at a time. Layers must be added
+/// lowest precedence first: files, then the environment, then flags.
+#[derive(Debug, Clone)]
+pub struct ConfigLayers {
+    /// Defaults merged with the files added so far
+    table: toml::Table,
+    config: Config,
+    origins: HashMap<Setting, ConfigOrigin>,
+}
+
+impl ConfigLayers {
+    /// Start from the built-in defaults
+    pub fn new() -> Self {
+        let config = Config::default();
+        Self {
+            table: to_table(&config),
+            config,
+            origins: HashMap::new(),
+        }
+    }
+
+    /// Merge a config file over the layers so far. A missing file adds
+    /// nothing; unknown keys are skipped with a warning.
+    pub fn add_file(&mut self, path: &Path, origin: ConfigOrigin) -> Result<()> {
+        if !path.exists() {
+            return Ok(());
+        }
+        let content = std::fs::read_to_string(path)?;
+        let layer: toml::Table = toml::from_str(&content).map_err(|e| {
+            CentotypeError::Config(format!("Failed to parse {}: {}", path.display(), e))
+        })?;
+
+        let settings = Setting::all();
+        for (key, value) in &layer {
+            let known = match value.as_table() {
+                Some(table) if key == "keybindings" => {
+                    for name in table.keys() {
+                        if !settings
+                            .iter()
+                            .any(|s| s.name() == format!("keybindings.{}", name))
+                        {
+                            warn!("Unknown key binding '{}' in {}", name, path.display());
+                        }
+                    }
+                    true
+                }
+                _ => settings.iter().any(|s| s.name() == *key),
+            };
+            if !known {
+                warn!("Unknown setting '{}' in {}", key, path.display());
+            }
+        }
+        for setting in settings {
+            if lookup(&layer, &setting.name()).is_some() {
+                self.origins.insert(setting, origin.clone());
+            }
+        }
+
+        let mut table = self.table.clone();
+        merge(&mut table, layer);
+        self.config = toml::Value::Table(table.clone()).try_into().map_err(|e| {
+            CentotypeError::Config(format!("Invalid config in {}: {}", path.display(), e))
+        })?;
+        self.table = table;
+        Ok(())
+    }
+
+    /// Apply the `CENTOTYPE_*` variable of every setting that `env` has
+    pub fn add_env(&mut self, env: impl Fn(&str) -> Option<String>) -> Result<()> {
+        for setting in Setting::all() {
+            let name = env_var(setting);
+            if let Some(value) = env(&name) {
+                self.apply(setting, &value, ConfigOrigin::Environment(name))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Apply `key=value` overrides from the command line
+    pub fn add_flags(&mut self, flags: &[String]) -> Result<()> {
+        for flag in flags {
+            let (key, value) = flag.split_once('=').ok_or_else(|| {
+                CentotypeError::Config(format!(
+                    "Expected KEY=VALUE in --set '{}', e.g. --set theme=mono",
+                    flag
+                ))
+            })?;
+            self.apply(key.parse()?, value, ConfigOrigin::Flag)?;
+        }
+        Ok(())
+    }
+
+    fn apply(&mut self, setting: Setting, value: &str, origin: ConfigOrigin) -> Result<()> {
+        self.config.set(setting, value).map_err(|e| match e {
+            CentotypeError::Config(message) => {
+                CentotypeError::Config(format!("{} (from {})", message, origin))
+            }
+            other => other,
+        })?;
+        self.origins.insert(setting, origin);
+        Ok(())
+    }
+
+    pub fn finish(self) -> ResolvedConfig {
+        ResolvedConfig {
+            config: self.config,
+            origins: self.origins,
+        }
+    }
+}
+
+impl Default for ConfigLayers {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The settings of `config` that differ from `base`, as a config file
+/// table. Optional settings that `base` has and `config` does not are
+/// written as `none` so they stay unset.
+pub fn overrides(config: &Config, base: &Config) -> toml::Table {
+    let (table, base) = (to_table(config), to_table(base));
+    let mut changed = diff(table.clone(), &base);
+    // Unset optional settings are left out when serialized
+    for key in base.keys().filter(|key| !table.contains_key(*key)) {
+        changed.insert(key.clone(), toml::Value::String(UNSET.to_string()));
+    }
+    changed
+}
+
+fn to_table(config: &Config) -> toml::Table {
+    match toml::Value::try_from(config) {
+        Ok(toml::Value::Table(table)) => table,
+        _ => unreachable!("Config serializes to a table"),
+    }
+}
+
+/// Value at a dotted setting name such as `keybindings.quit`
+fn lookup<'a>(table: &'a toml::Table, name: &str) -> Option<&'a toml::Value> {
+    match name.split_once('.') {
+        Some((head, rest)) => lookup(table.get(head)?.as_table()?, rest),
+        None => table.get(name),
+    }
+}
+
+/// Merge `layer` into `base`, table by table; `none` unsets a setting
+fn merge(base: &mut toml::Table, layer: toml::Table) {
+    for (key, value) in layer {
+        match (base.get_mut(&key), value) {
+            (Some(toml::Value::Table(base_table)), toml::Value::Table(table)) => {
+                merge(base_table, table)
+            }
+            (_, toml::Value::String(s)) if s == UNSET => {
+                base.remove(&key);
+            }
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+fn diff(table: toml::Table, base: &toml::Table) -> toml::Table {
+    let mut changed = toml::Table::new();
+    for (key, value) in table {
+        match (value, base.get(&key)) {
+            (toml::Value::Table(table), Some(toml::Value::Table(base))) => {
+                let nested = diff(table, base);
+                if !nested.is_empty() {
+                    changed.insert(key, toml::Value::Table(nested));
+                }
+            }
+            (value, Some(base)) if value == *base => {}
+            (value, _) => {
+                changed.insert(key, value);
+            }
+        }
+    }
+    changed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use centotype_core::SessionCommand;
+
+    #[test]
+    fn test_layer_precedence() {
+        let dir = tempfile::tempdir().unwrap();
+        let system = dir.path().join("system.toml");
+        let user = dir.path().join("user.toml");
+        std::fs::write(
+            &system,
+            "theme = \"Mono\"\nlayout = \"Dvorak\"\ncustom_theme = \"corp\"\n",
+        )
+        .unwrap();
+        std::fs::write(
+            &user,
+            "layout = \"Colemak\"\ncustom_theme = \"none\"\n[keybindings]\npause = [\"f5\"]\n",
+        )
+        .unwrap();
+
+        let mut layers = ConfigLayers::new();
+        layers
+            .add_file(&system, ConfigOrigin::SystemFile(system.clone()))
+            .unwrap();
+        layers
+            .add_file(&user, ConfigOrigin::UserFile(user.clone()))
+            .unwrap();
+        layers
+            .add_env(|name| (name == "CENTOTYPE_SOUND_ENABLED").then(|| "on".to_string()))
+            .unwrap();
+        layers.add_flags(&["layout=workman".to_string()]).unwrap();
+        let resolved = layers.finish();
+
+        assert_eq!(resolved.config.theme, Theme::Mono);
+        assert_eq!(
+            resolved.origin(Setting::Theme),
+            &ConfigOrigin::SystemFile(system)
+        );
+        assert_eq!(resolved.config.custom_theme, None);
+        assert_eq!(
+            resolved.origin(Setting::CustomTheme),
+            &ConfigOrigin::UserFile(user.clone())
+        );
+        assert_eq!(
+            resolved
+                .config
+                .get(Setting::KeyBinding(SessionCommand::PauseResume)),
+            "f5"
+        );
+        assert!(resolved.config.sound_enabled);
+        assert_eq!(
+            resolved.origin(Setting::SoundEnabled).to_string(),
+            "environment CENTOTYPE_SOUND_ENABLED"
+        );
+        assert_eq!(resolved.config.layout, KeyboardLayout::Workman);
+        assert_eq!(resolved.origin(Setting::Layout), &ConfigOrigin::Flag);
+        assert_eq!(resolved.origin(Setting::LogLevel), &ConfigOrigin::Default);
+    }
+
+    #[test]
+    fn test_layer_errors_name_their_source() {
+        let dir = tempfile::tempdir().unwrap();
+        let user = dir.path().join("config.toml");
+        std::fs::write(&user, "theme = \"Neon\"\n").unwrap();
+        let err = ConfigLayers::new()
+            .add_file(&user, ConfigOrigin::UserFile(user.clone()))
+            .unwrap_err();
+        assert!(err.to_string().contains(&user.display().to_string()));
+
+        let err = ConfigLayers::new()
+            .add_env(|name| (name == "CENTOTYPE_THEME").then(|| "neon".to_string()))
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("expected one of: default"));
+        assert!(err.contains("(from environment CENTOTYPE_THEME)"));
+
+        assert!(ConfigLayers::new()
+            .add_flags(&["theme".to_string()])
+            .unwrap_err()
+            .to_string()
+            .contains("Expected KEY=VALUE"));
+    }
+
+    #[test]
+    fn test_overrides_keep_only_changes() {
+        let base = Config {
+            theme: Theme::Mono,
+            custom_theme: Some("corp".to_string()),
+            ..Default::default()
+        };
+
+        let mut config = base.clone();
+        config.layout = KeyboardLayout::Azerty;
+        config.custom_theme = None;
+        config.keybindings.help = Vec::new();
+
+        let table = overrides(&config, &base);
+        assert_eq!(
+            toml::to_string(&table).unwrap(),
+            "custom_theme = \"none\"\nlayout = \"Azerty\"\n\n[keybindings]\nhelp = []\n"
+        );
+
+        // Written over the base, the overrides give the config back
+        let mut merged = to_table(&base);
+        merge(&mut merged, table);
+        let round_trip: Config = toml::Value::Table(merged).try_into().unwrap();
+        assert_eq!(to_table(&round_trip), to_table(&config));
+    }
+}
@@ -7,11 +7,15 @@ pub mod profile;
 pub mod storage;
 
 use centotype_core::types::*;
+use centotype_core::LayoutRegistry;
+use config::{ConfigLayers, ConfigOrigin, ResolvedConfig};
 use std::path::PathBuf;
 
 pub struct PersistenceManager {
     config_dir: PathBuf,
     data_dir: PathBuf,
+    /// Machine-wide config file below the user's
+    system_config: Option<PathBuf>,
 }
 
 impl PersistenceManager {
@@ -29,9 +33,12 @@ impl PersistenceManager {
         Ok(Self {
             config_dir,
             data_dir,
+            system_config: config::system_config_path(),
         })
     }
 
+    /// Storage under `path`, without a system config file unless one is
+    /// given with `with_system_config`
     pub fn new_with_path(path: &std::path::Path) -> Result<Self> {
         let config_dir = path.join("config");
         let data_dir = path.join("data");
@@ -42,28 +49,82 @@ impl PersistenceManager {
         Ok(Self {
             config_dir,
             data_dir,
+            system_config: None,
         })
     }
 
+    /// Read machine-wide settings from `path` instead of the default
+    /// location
+    pub fn with_system_config(mut self, path: PathBuf) -> Self {
+        self.system_config = Some(path);
+        self
+    }
+
+    /// The user's config file
+    pub fn config_path(&self) -> PathBuf {
+        self.config_dir.join("config.toml")
+    }
+
+    /// Defaults and the system file: what the user file is saved against
+    pub fn inherited_config(&self) -> Result<Config> {
+        Ok(self.file_layers(false)?.finish().config)
+    }
+
+    /// Config stored in files: defaults, the system file and the user file.
+    /// This is what settings editors change; environment variables and flags
+    /// are left out so they are never written back.
     pub fn load_config(&self) -> Result<Config> {
-        let config_path = self.config_dir.join("config.toml");
-        if config_path.exists() {
-            let content = std::fs::read_to_string(config_path)?;
-            toml::from_str(&content)
-                .map_err(|e| CentotypeError::Config(format!("Failed to parse config: {}", e)))
-        } else {
-            Ok(Config::default())
-        }
+        Ok(self.file_layers(true)?.finish().config)
     }
 
+    /// Effective config for this run: the files, then `CENTOTYPE_*`
+    /// environment variables, then `--set key=value` flags
+    pub fn resolve_config(&self, flags: &[String]) -> Result<ResolvedConfig> {
+        let mut layers = self.file_layers(true)?;
+        layers.add_env(|name| std::env::var(name).ok())?;
+        layers.add_flags(flags)?;
+        Ok(layers.finish())
+    }
+
+    /// Save to the user file the settings that differ from the system file
+    /// and defaults, so machine-wide changes still reach the rest
     pub fn save_config(&self, config: &Config) -> Result<()> {
-        let config_path = self.config_dir.join("config.toml");
-        let content = toml::to_string_pretty(config)
+        let table = config::overrides(config, &self.inherited_config()?);
+        let content = toml::to_string_pretty(&table)
             .map_err(|e| CentotypeError::Config(format!("Failed to serialize config: {}", e)))?;
-        std::fs::write(config_path, content)?;
+        std::fs::write(self.config_path(), content)?;
         Ok(())
     }
 
+    fn file_layers(&self, with_user_file: bool) -> Result<ConfigLayers> {
+        let mut layers = ConfigLayers::new();
+        if let Some(path) = &self.system_config {
+            layers.add_file(path, ConfigOrigin::SystemFile(path.clone()))?;
+        }
+        if with_user_file {
+            let path = self.config_path();
+            layers.add_file(&path, ConfigOrigin::UserFile(path.clone()))?;
+        }
+        Ok(layers)
+    }
+
+    /// Folder holding user keyboard layout files
+    pub fn layouts_dir(&self) -> PathBuf {
+        self.config_dir.join("layouts")
+    }
+
+    /// Folder holding user colour theme files
+    pub fn themes_dir(&self) -> PathBuf {
+        self.config_dir.join("themes")
+    }
+
+    /// Built-in layouts plus any user layouts from the config dir
+    pub fn load_layouts(&self) -> Result<LayoutRegistry> {
+        let mut registry = LayoutRegistry::new();
+        registry.load_dir(&self.layouts_dir())?;
+        Ok(registry)
+    }
+
     pub fn load_profile(&self) -> Result<UserProgress> {
         let profile_path = self.data_dir.join("profile.json");
         if profile_path.exists() {
@@ -87,14 +148,74 @@ impl PersistenceManager {
         Ok(())
     }
 
-    pub fn save_session_result(&self, _result: &SessionResult) -> Result<()> {
-        // TODO: Implementation for saving individual session results
+    /// Keep a session cut short by a termination signal so it can be
+    /// resumed or recorded later
+    pub fn save_interrupted_session(&self, state: &SessionState) -> Result<()> {
+        let session_path = self.data_dir.join("interrupted_session.json");
+        let temp_path = session_path.with_extension("json.tmp");
+
+        let content = serde_json::to_string_pretty(state)?;
+        std::fs::write(&temp_path, content)?;
+        std::fs::rename(temp_path, session_path)?;
+
+        Ok(())
+    }
+
+    /// Take the session saved by `save_interrupted_session`, if any
+    pub fn take_interrupted_session(&self) -> Result<Option<SessionState>> {
+        let session_path = self.data_dir.join("interrupted_session.json");
+        if !session_path.exists() {
+            return Ok(None);
+        }
+        let content = std::fs::read_to_string(&session_path)?;
+        std::fs::remove_file(session_path)?;
+        serde_json::from_str(&content).map(Some).map_err(|e| {
+            CentotypeError::Persistence(format!("Failed to parse interrupted session: {}", e))
+        })
+    }
+
+    /// Append a finished session to the history, one JSON object per line
+    pub fn save_session_result(&self, result: &SessionResult) -> Result<()> {
+        use std::io::Write;
+
+        let mut line = serde_json::to_string(result)?;
+        line.push('\n');
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.history_path())?;
+        file.write_all(line.as_bytes())?;
         Ok(())
     }
 
+    /// Every recorded session, oldest first. Lines that do not parse, such
+    /// as one cut short by a crash, are skipped with a warning.
     pub fn load_session_results(&self) -> Result<Vec<SessionResult>> {
-        // TODO: Implementation for loading session results
-        // For now, return empty vector
-        Ok(vec![])
+        let path = self.history_path();
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+        let content = std::fs::read_to_string(&path)?;
+        Ok(content
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .filter_map(|(index, line)| match serde_json::from_str(line) {
+                Ok(result) => Some(result),
+                Err(e) => {
+                    tracing::warn!(
+                        "Skipping session on line {} of {}: {}",
+                        index + 1,
+                        path.display(),
+                        e
+                    );
+                    None
+                }
+            })
+            .collect())
+    }
+
+    fn history_path(&self) -> PathBuf {
+        self.data_dir.join("sessions.jsonl")
     }
 }
@@ -1,6 +1,8 @@
 //! Platform detection and system information
 
+use super::terminal::{query_device_attributes, TerminalCapabilities, TerminalEnvironment};
 use centotype_core::types::*;
+use std::time::Duration;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum OSType {
@@ -26,7 +28,10 @@ pub struct PlatformInfo {
     pub total_memory_mb: u64,
 }
 
-pub struct PlatformDetector;
+pub struct PlatformDetector {
+    /// Ask the terminal for DA1/DA2 replies, waiting at most this long
+    device_query: Option<Duration>,
+}
 
 impl Default for PlatformDetector {
     fn default() -> Self {
@@ -36,7 +41,14 @@ impl Default for PlatformDetector {
 
 impl PlatformDetector {
     pub fn new() -> Self {
-        Self
+        Self { device_query: None }
+    }
+
+    /// Also query the terminal's device attributes during detection. The
+    /// query writes to the terminal, so it is off unless asked for.
+    pub fn with_device_query(mut self, timeout: Duration) -> Self {
+        self.device_query = Some(timeout);
+        self
     }
 
     pub fn detect_platform(&self) -> Result<PlatformInfo> {
@@ -67,22 +79,95 @@ impl PlatformDetector {
         })
     }
 
-    pub fn detect_terminal_capabilities(&self) -> Result<super::TerminalCapabilities> {
-        // Implementation would detect terminal type and capabilities
-        Ok(super::TerminalCapabilities {
-            terminal_type: super::TerminalType::Unknown,
-            supports_color: true,
-            supports_raw_mode: true,
-            supports_mouse: false,
-            max_colors: 256,
-        })
+    pub fn detect_terminal_capabilities(&self) -> Result<TerminalCapabilities> {
+        let mut env = TerminalEnvironment::from_process();
+        if let Some(timeout) = self.device_query {
+            env.device_attributes = query_device_attributes(timeout);
+        }
+        Ok(TerminalCapabilities::from_environment(&env))
     }
 
     fn get_os_version(&self) -> String {
+        #[cfg(target_os = "linux")]
+        {
+            let kernel = std::fs::read_to_string("/proc/sys/kernel/osrelease")
+                .map(|release| release.trim().to_string())
+                .ok();
+            let distribution = std::fs::read_to_string("/etc/os-release")
+                .ok()
+                .and_then(|contents| parse_os_release(&contents));
+
+            match (distribution, kernel) {
+                (Some(distribution), Some(kernel)) => {
+                    return format!("{} (kernel {})", distribution, kernel)
+                }
+                (Some(version), None) | (None, Some(version)) => return version,
+                (None, None) => {}
+            }
+        }
         "Unknown".to_string()
     }
 
     fn get_total_memory_mb(&self) -> u64 {
+        #[cfg(target_os = "linux")]
+        if let Some(total) = std::fs::read_to_string("/proc/meminfo")
+            .ok()
+            .and_then(|contents| parse_meminfo(&contents))
+        {
+            return total;
+        }
         8192 // Default 8GB
     }
 }
+
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+/// `MemTotal` from `/proc/meminfo`, in megabytes
+fn parse_meminfo(contents: &str) -> Option<u64> {
+    contents.lines().find_map(|line| {
+        let kilobytes = line.strip_prefix("MemTotal:")?.trim().strip_suffix("kB")?;
+        Some(kilobytes.trim().parse::<u64>().ok()? / 1024)
+    })
+}
+
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+/// `PRETTY_NAME` from `/etc/os-release`, falling back to `NAME` and `VERSION_ID`
+fn parse_os_release(contents: &str) -> Option<String> {
+    let field = |key: &str| {
+        contents.lines().find_map(|line| {
+            let value = line.strip_prefix(key)?.strip_prefix('=')?;
+            Some(value.trim().trim_matches('"').to_string())
+        })
+    };
+
+    field("PRETTY_NAME").or_else(|| {
+        let name = field("NAME")?;
+        Some(match field("VERSION_ID") {
+            Some(version) => format!("{} {}", name, version),
+            None => name,
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_proc_parsing() {
+        let meminfo = "MemTotal:       16318412 kB\nMemFree:         1034232 kB\n";
+        assert_eq!(parse_meminfo(meminfo), Some(15935));
+        assert_eq!(parse_meminfo("MemFree: 1 kB\n"), None);
+
+        let os_release =
+            "NAME=\"Ubuntu\"\nVERSION_ID=\"24.04\"\nPRETTY_NAME=\"Ubuntu 24.04.1 LTS\"\n";
+        assert_eq!(
+            parse_os_release(os_release).as_deref(),
+            Some("Ubuntu 24.04.1 LTS")
+        );
+        assert_eq!(
+            parse_os_release("NAME=Arch\nVERSION_ID=rolling\n").as_deref(),
+            Some("Arch rolling")
+        );
+        assert_eq!(parse_os_release(""), None);
+    }
+}
@@ -25,12 +25,16 @@ pub mod detection;
 pub mod input;
 pub mod performance;
 pub mod terminal;
+pub mod terminfo;
 
 // Re-export main types
 pub use detection::{Architecture, OSType, PlatformDetector, PlatformInfo};
 pub use input::{InputOptimizations, PlatformInput};
 pub use performance::{PlatformPerformance, SystemMetrics};
-pub use terminal::{TerminalCapabilities, TerminalManager, TerminalType};
+pub use terminal::{
+    ColorSupport, DeviceAttributes, TerminalCapabilities, TerminalEnvironment, TerminalManager,
+    TerminalType, UnicodeWidth,
+};
 
 use centotype_core::types::*;
 use once_cell::sync::Lazy;
@@ -1,31 +1,421 @@
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+//! Terminal identification and capability inference
+//!
+//! Capabilities are inferred from a [`TerminalEnvironment`]: the environment
+//! variables terminals set, the terminfo colour count and, when queried, the
+//! terminal's DA1/DA2 device attribute replies. Inference is a pure function
+//! of that snapshot, so known terminals are covered by the fixtures in
+//! `platform/fixtures/terminals/`.
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum TerminalType {
     XTerm,
+    /// VTE based terminals: GNOME Terminal, Tilix, Terminator
     GnomeTerminal,
+    Konsole,
     ITerm2,
+    AppleTerminal,
+    Kitty,
+    WezTerm,
+    Alacritty,
+    Foot,
+    Ghostty,
+    VSCode,
+    Tmux,
+    Screen,
+    /// The Linux virtual console
+    LinuxConsole,
     WindowsTerminal,
     CMD,
     Unknown,
 }
 
+/// Colours the terminal can show
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum ColorSupport {
+    Monochrome,
+    Ansi16,
+    Ansi256,
+    TrueColor,
+}
+
+impl ColorSupport {
+    pub fn max_colors(self) -> u32 {
+        match self {
+            ColorSupport::Monochrome => 2,
+            ColorSupport::Ansi16 => 16,
+            ColorSupport::Ansi256 => 256,
+            ColorSupport::TrueColor => 1 << 24,
+        }
+    }
+
+    /// Colour support implied by a terminfo `colors` count
+    fn from_color_count(colors: i32) -> Self {
+        match colors {
+            c if c >= 1 << 24 => ColorSupport::TrueColor,
+            c if c >= 256 => ColorSupport::Ansi256,
+            c if c >= 8 => ColorSupport::Ansi16,
+            _ => ColorSupport::Monochrome,
+        }
+    }
+}
+
+/// How the terminal lays out non-ASCII text
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum UnicodeWidth {
+    /// UTF-8 with wide characters (CJK, emoji) drawn two cells wide
+    Full,
+    /// UTF-8, but wide characters are missing or drawn one cell wide
+    NarrowOnly,
+    /// No UTF-8 locale; stick to ASCII
+    Ascii,
+}
+
 #[derive(Debug, Clone)]
 pub struct TerminalCapabilities {
     pub terminal_type: TerminalType,
     pub supports_color: bool,
     pub supports_raw_mode: bool,
     pub supports_mouse: bool,
-    pub max_colors: u16,
+    pub max_colors: u32,
+    pub color_support: ColorSupport,
+    pub unicode_width: UnicodeWidth,
+    /// Understands the kitty keyboard protocol, needed for key releases
+    pub kitty_keyboard: bool,
+    pub bracketed_paste: bool,
 }
 
 impl TerminalCapabilities {
+    /// Infer capabilities from an environment snapshot
+    pub fn from_environment(env: &TerminalEnvironment) -> Self {
+        let terminal_type = env.terminal_type();
+        let color_support = env.color_support(terminal_type);
+        let dumb =
+            env.term.is_empty() && terminal_type == TerminalType::Unknown || env.term == "dumb";
+
+        Self {
+            terminal_type,
+            supports_color: color_support != ColorSupport::Monochrome,
+            supports_raw_mode: true,
+            supports_mouse: !dumb && terminal_type != TerminalType::LinuxConsole,
+            max_colors: color_support.max_colors(),
+            color_support,
+            unicode_width: env.unicode_width(terminal_type, dumb),
+            kitty_keyboard: matches!(
+                terminal_type,
+                TerminalType::Kitty
+                    | TerminalType::Foot
+                    | TerminalType::Ghostty
+                    | TerminalType::Alacritty
+            ),
+            bracketed_paste: !dumb
+                && !matches!(
+                    terminal_type,
+                    TerminalType::LinuxConsole | TerminalType::CMD
+                ),
+        }
+    }
+
     pub fn has_limitations(&self) -> bool {
-        false
+        self.color_support == ColorSupport::Monochrome || self.unicode_width == UnicodeWidth::Ascii
     }
+
     pub fn configure_terminal(&self) -> centotype_core::types::Result<()> {
         Ok(())
     }
 }
 
+/// Device attribute replies: DA1 (`CSI ? ... c`) and DA2 (`CSI > ... c`)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DeviceAttributes {
+    pub primary: Vec<u32>,
+    pub secondary: Vec<u32>,
+}
+
+impl DeviceAttributes {
+    /// Parse the replies found in raw terminal output
+    pub fn parse(response: &str) -> Option<Self> {
+        let params = |prefix: &str| -> Option<Vec<u32>> {
+            let start = response.find(prefix)? + prefix.len();
+            let end = start + response[start..].find('c')?;
+            Some(
+                response[start..end]
+                    .split(';')
+                    .filter_map(|param| param.parse().ok())
+                    .collect(),
+            )
+        };
+
+        let primary = params("\x1b[?");
+        let secondary = params("\x1b[>");
+        if primary.is_none() && secondary.is_none() {
+            return None;
+        }
+        Some(Self {
+            primary: primary.unwrap_or_default(),
+            secondary: secondary.unwrap_or_default(),
+        })
+    }
+
+    /// Terminal named by the DA2 model number, for the few that are unique
+    fn terminal_type(&self) -> Option<TerminalType> {
+        match self.secondary.as_slice() {
+            [41, ..] => Some(TerminalType::XTerm),
+            [65, ..] => Some(TerminalType::GnomeTerminal),
+            [83, ..] => Some(TerminalType::Screen),
+            [84, ..] => Some(TerminalType::Tmux),
+            [1, version, ..] if *version >= 4000 => Some(TerminalType::Kitty),
+            _ => None,
+        }
+    }
+}
+
+/// What a terminal reveals about itself
+#[derive(Debug, Clone, Default)]
+pub struct TerminalEnvironment {
+    pub term: String,
+    pub colorterm: String,
+    pub term_program: String,
+    /// Effective `LC_ALL`, `LC_CTYPE` or `LANG`
+    pub locale: String,
+    /// Terminal-specific marker variables that are set, such as
+    /// `KITTY_WINDOW_ID` or `WT_SESSION`
+    pub markers: Vec<String>,
+    /// `VTE_VERSION` as a number, e.g. 6800 for VTE 0.68
+    pub vte_version: Option<u32>,
+    /// `NO_COLOR` is set to a non-empty value
+    pub no_color: bool,
+    /// `colors` from the terminfo entry for `term`
+    pub terminfo_colors: Option<i32>,
+    pub device_attributes: Option<DeviceAttributes>,
+    pub windows: bool,
+}
+
+/// Variables whose presence identifies a terminal
+const MARKER_VARIABLES: [&str; 7] = [
+    "KITTY_WINDOW_ID",
+    "WEZTERM_EXECUTABLE",
+    "ALACRITTY_WINDOW_ID",
+    "KONSOLE_VERSION",
+    "WT_SESSION",
+    "TMUX",
+    "GHOSTTY_RESOURCES_DIR",
+];
+
+impl TerminalEnvironment {
+    /// Snapshot the current process environment and terminfo entry
+    pub fn from_process() -> Self {
+        let mut env = Self::from_vars(|name| std::env::var(name).ok());
+        env.terminfo_colors = crate::terminfo::colors(&env.term);
+        env
+    }
+
+    /// Build from a variable lookup
+    pub fn from_vars(var: impl Fn(&str) -> Option<String>) -> Self {
+        let get = |name: &str| var(name).unwrap_or_default();
+        let locale = ["LC_ALL", "LC_CTYPE", "LANG"]
+            .into_iter()
+            .map(get)
+            .find(|value| !value.is_empty())
+            .unwrap_or_default();
+
+        Self {
+            term: get("TERM"),
+            colorterm: get("COLORTERM"),
+            term_program: get("TERM_PROGRAM"),
+            locale,
+            markers: MARKER_VARIABLES
+                .into_iter()
+                .filter(|name| var(name).is_some())
+                .map(str::to_string)
+                .collect(),
+            vte_version: var("VTE_VERSION").and_then(|v| v.parse().ok()),
+            no_color: var("NO_COLOR").is_some_and(|v| !v.is_empty()),
+            terminfo_colors: None,
+            device_attributes: None,
+            windows: cfg!(windows),
+        }
+    }
+
+    fn has_marker(&self, name: &str) -> bool {
+        self.markers.iter().any(|marker| marker == name)
+    }
+
+    fn terminal_type(&self) -> TerminalType {
+        // Multiplexers hide the outer terminal
+        if self.term_program == "tmux" || self.has_marker("TMUX") || self.term.starts_with("tmux") {
+            return TerminalType::Tmux;
+        }
+        if self.term.starts_with("screen") {
+            return TerminalType::Screen;
+        }
+
+        match self.term_program.as_str() {
+            "iTerm.app" => return TerminalType::ITerm2,
+            "Apple_Terminal" => return TerminalType::AppleTerminal,
+            "WezTerm" => return TerminalType::WezTerm,
+            "vscode" => return TerminalType::VSCode,
+            "ghostty" => return TerminalType::Ghostty,
+            _ => {}
+        }
+
+        if self.term == "xterm-kitty" || self.has_marker("KITTY_WINDOW_ID") {
+            TerminalType::Kitty
+        } else if self.has_marker("WEZTERM_EXECUTABLE") || self.term == "wezterm" {
+            TerminalType::WezTerm
+        } else if self.term == "xterm-ghostty" || self.has_marker("GHOSTTY_RESOURCES_DIR") {
+            TerminalType::Ghostty
+        } else if self.term == "alacritty" || self.has_marker("ALACRITTY_WINDOW_ID") {
+            TerminalType::Alacritty
+        } else if self.term.starts_with("foot") {
+            TerminalType::Foot
+        } else if self.has_marker("KONSOLE_VERSION") || self.term == "konsole" {
+            TerminalType::Konsole
+        } else if self.vte_version.is_some() {
+            TerminalType::GnomeTerminal
+        } else if self.has_marker("WT_SESSION") {
+            TerminalType::WindowsTerminal
+        } else if self.term == "linux" {
+            TerminalType::LinuxConsole
+        } else if let Some(from_da) = self
+            .device_attributes
+            .as_ref()
+            .and_then(DeviceAttributes::terminal_type)
+        {
+            from_da
+        } else if self.term.starts_with("xterm") {
+            TerminalType::XTerm
+        } else if self.windows && self.term.is_empty() {
+            TerminalType::CMD
+        } else {
+            TerminalType::Unknown
+        }
+    }
+
+    fn color_support(&self, terminal_type: TerminalType) -> ColorSupport {
+        if self.no_color || self.term == "dumb" {
+            return ColorSupport::Monochrome;
+        }
+        if matches!(self.colorterm.as_str(), "truecolor" | "24bit")
+            || self.term.ends_with("-direct")
+        {
+            return ColorSupport::TrueColor;
+        }
+
+        let native = match terminal_type {
+            TerminalType::ITerm2
+            | TerminalType::Kitty
+            | TerminalType::WezTerm
+            | TerminalType::Alacritty
+            | TerminalType::Foot
+            | TerminalType::Ghostty
+            | TerminalType::VSCode
+            | TerminalType::Konsole
+            | TerminalType::WindowsTerminal => Some(ColorSupport::TrueColor),
+            // VTE has had 24-bit colour since 0.36
+            TerminalType::GnomeTerminal if self.vte_version.is_some_and(|v| v >= 3600) => {
+                Some(ColorSupport::TrueColor)
+            }
+            TerminalType::AppleTerminal => Some(ColorSupport::Ansi256),
+            TerminalType::LinuxConsole | TerminalType::CMD => Some(ColorSupport::Ansi16),
+            _ => None,
+        };
+        if let Some(native) = native {
+            return native;
+        }
+
+        // Multiplexers and plain xterm entries: trust terminfo, then TERM
+        if let Some(colors) = self.terminfo_colors {
+            return ColorSupport::from_color_count(colors);
+        }
+        if self.term.contains("256color") {
+            ColorSupport::Ansi256
+        } else if self.term.is_empty() || self.term.starts_with("vt100") {
+            ColorSupport::Monochrome
+        } else {
+            ColorSupport::Ansi16
+        }
+    }
+
+    fn unicode_width(&self, terminal_type: TerminalType, dumb: bool) -> UnicodeWidth {
+        // Windows consoles have no locale variables and always take UTF-16
+        let utf8 = self.windows && self.locale.is_empty() || {
+            let locale = self.locale.to_ascii_lowercase();
+            locale.contains("utf-8") || locale.contains("utf8")
+        };
+
+        if dumb || !utf8 {
+            UnicodeWidth::Ascii
+        } else if matches!(
+            terminal_type,
+            TerminalType::LinuxConsole | TerminalType::CMD
+        ) {
+            UnicodeWidth::NarrowOnly
+        } else {
+            UnicodeWidth::Full
+        }
+    }
+}
+
+/// Ask the terminal for its DA2 and DA1 attributes, waiting at most
+/// `timeout`. Every terminal answers DA1, so its reply ends the wait; DA2 is
+/// sent first so that its reply arrives before.
+#[cfg(unix)]
+pub fn query_device_attributes(timeout: Duration) -> Option<DeviceAttributes> {
+    use crossterm::terminal::{disable_raw_mode, enable_raw_mode, is_raw_mode_enabled};
+    use nix::poll::{poll, PollFd, PollFlags};
+    use std::io::{Read, Write};
+    use std::time::Instant;
+
+    let mut tty = std::fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open("/dev/tty")
+        .ok()?;
+
+    let was_raw = is_raw_mode_enabled().unwrap_or(false);
+    if !was_raw {
+        enable_raw_mode().ok()?;
+    }
+
+    let mut response = Vec::new();
+    if tty
+        .write_all(b"\x1b[>c\x1b[c")
+        .and_then(|_| tty.flush())
+        .is_ok()
+    {
+        let deadline = Instant::now() + timeout;
+        let mut buffer = [0u8; 64];
+        while let Some(remaining) = deadline.checked_duration_since(Instant::now()) {
+            let mut poll_fds = [PollFd::new(&tty, PollFlags::POLLIN)];
+            let millis = remaining.as_millis().min(i32::MAX as u128) as i32;
+            if !matches!(poll(&mut poll_fds, millis), Ok(1..)) {
+                break;
+            }
+            match tty.read(&mut buffer) {
+                Ok(0) | Err(_) => break,
+                Ok(read) => response.extend_from_slice(&buffer[..read]),
+            }
+            if DeviceAttributes::parse(&String::from_utf8_lossy(&response))
+                .is_some_and(|attributes| !attributes.primary.is_empty())
+            {
+                break;
+            }
+        }
+    }
+
+    if !was_raw {
+        let _ = disable_raw_mode();
+    }
+    DeviceAttributes::parse(&String::from_utf8_lossy(&response))
+}
+
+#[cfg(not(unix))]
+pub fn query_device_attributes(_timeout: Duration) -> Option<DeviceAttributes> {
+    None
+}
+
 pub struct TerminalManager;
 impl Default for TerminalManager {
     fn default() -> Self {
@@ -38,3 +428,71 @@ impl TerminalManager {
         Self
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    /// A known terminal's environment and the capabilities expected from it
+    #[derive(Debug, Deserialize)]
+    struct Fixture {
+        env: HashMap<String, String>,
+        terminfo_colors: Option<i32>,
+        device_attributes: Option<String>,
+        #[serde(default)]
+        windows: bool,
+        expected: Expected,
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct Expected {
+        terminal_type: TerminalType,
+        color_support: ColorSupport,
+        unicode_width: UnicodeWidth,
+        kitty_keyboard: bool,
+        bracketed_paste: bool,
+    }
+
+    #[test]
+    fn test_terminal_fixtures() {
+        let dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures/terminals");
+        let mut checked = 0;
+
+        for entry in std::fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            let fixture: Fixture = toml::from_str(&std::fs::read_to_string(&path).unwrap())
+                .unwrap_or_else(|e| panic!("{}: {}", path.display(), e));
+
+            let mut env = TerminalEnvironment::from_vars(|name| fixture.env.get(name).cloned());
+            env.terminfo_colors = fixture.terminfo_colors;
+            env.device_attributes = fixture
+                .device_attributes
+                .as_deref()
+                .and_then(DeviceAttributes::parse);
+            env.windows = fixture.windows;
+
+            let caps = TerminalCapabilities::from_environment(&env);
+            let expected = &fixture.expected;
+            let name = path.file_stem().unwrap().to_string_lossy();
+            assert_eq!(caps.terminal_type, expected.terminal_type, "{}", name);
+            assert_eq!(caps.color_support, expected.color_support, "{}", name);
+            assert_eq!(caps.unicode_width, expected.unicode_width, "{}", name);
+            assert_eq!(caps.kitty_keyboard, expected.kitty_keyboard, "{}", name);
+            assert_eq!(caps.bracketed_paste, expected.bracketed_paste, "{}", name);
+            checked += 1;
+        }
+
+        assert!(checked >= 10, "only {} terminal fixtures found", checked);
+    }
+
+    #[test]
+    fn test_device_attribute_parsing() {
+        let attributes = DeviceAttributes::parse("\x1b[>41;390;0c\x1b[?64;1;2;6;9;15;22c").unwrap();
+        assert_eq!(attributes.secondary, vec![41, 390, 0]);
+        assert_eq!(attributes.primary, vec![64, 1, 2, 6, 9, 15, 22]);
+        assert_eq!(attributes.terminal_type(), Some(TerminalType::XTerm));
+
+        assert_eq!(DeviceAttributes::parse("no reply"), None);
+    }
+}
@@ -0,0 +1,138 @@
+//! Minimal reader for compiled terminfo entries
+//!
+//! Only the numeric `colors` capability is read, which is all capability
+//! detection needs. Both the legacy 16-bit format and the 32-bit format
+//! written by ncurses 6.1+ are understood.
+use std::path::PathBuf;
+
+/// Magic number of the legacy format with 16-bit numbers
+const MAGIC_16BIT: u16 = 0o432;
+/// Magic number of the extended format with 32-bit numbers
+const MAGIC_32BIT: u16 = 0o1036;
+/// Position of `colors` in the standard numeric capability list
+const COLORS_INDEX: usize = 13;
+
+/// The `colors` capability of `term`, if its terminfo entry can be found
+pub fn colors(term: &str) -> Option<i32> {
+    if term.is_empty() || term.contains('/') {
+        return None;
+    }
+    search_paths()
+        .into_iter()
+        .flat_map(|dir| entry_paths(dir, term))
+        .find_map(|path| std::fs::read(path).ok())
+        .and_then(|entry| parse_colors(&entry))
+}
+
+/// Directories searched in the order ncurses uses
+fn search_paths() -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+    if let Some(dir) = std::env::var_os("TERMINFO") {
+        dirs.push(PathBuf::from(dir));
+    }
+    if let Some(home) = std::env::var_os("HOME") {
+        dirs.push(PathBuf::from(home).join(".terminfo"));
+    }
+    if let Ok(list) = std::env::var("TERMINFO_DIRS") {
+        dirs.extend(list.split(':').filter(|d| !d.is_empty()).map(PathBuf::from));
+    }
+    dirs.extend(
+        ["/etc/terminfo", "/lib/terminfo", "/usr/share/terminfo"]
+            .into_iter()
+            .map(PathBuf::from),
+    );
+    dirs
+}
+
+/// Entries live under their first letter, or its hex code on macOS
+fn entry_paths(dir: PathBuf, term: &str) -> Vec<PathBuf> {
+    let first = term.as_bytes()[0];
+    vec![
+        dir.join((first as char).to_string()).join(term),
+        dir.join(format!("{:x}", first)).join(term),
+    ]
+}
+
+/// Read `colors` from a compiled entry; absent or cancelled yields `None`
+pub fn parse_colors(entry: &[u8]) -> Option<i32> {
+    let header = |index: usize| -> Option<usize> {
+        let bytes = entry.get(index * 2..index * 2 + 2)?;
+        Some(u16::from_le_bytes([bytes[0], bytes[1]]) as usize)
+    };
+
+    let number_size = match header(0)? as u16 {
+        MAGIC_16BIT => 2,
+        MAGIC_32BIT => 4,
+        _ => return None,
+    };
+    let names_size = header(1)?;
+    let bools_count = header(2)?;
+    let numbers_count = header(3)?;
+    if numbers_count <= COLORS_INDEX {
+        return None;
+    }
+
+    // The numbers section starts on an even byte
+    let mut offset = 12 + names_size + bools_count;
+    offset += offset % 2;
+    let start = offset + COLORS_INDEX * number_size;
+    let bytes = entry.get(start..start + number_size)?;
+
+    let colors = if number_size == 2 {
+        i16::from_le_bytes([bytes[0], bytes[1]]) as i32
+    } else {
+        i32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+    };
+    // Negative values mark missing or cancelled capabilities
+    (colors >= 0).then_some(colors)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(magic: u16, names: &[u8], bools: usize, colors: i32, wide: bool) -> Vec<u8> {
+        let numbers = COLORS_INDEX + 2;
+        let mut data = Vec::new();
+        for value in [
+            magic,
+            names.len() as u16,
+            bools as u16,
+            numbers as u16,
+            0,
+            0,
+        ] {
+            data.extend_from_slice(&value.to_le_bytes());
+        }
+        data.extend_from_slice(names);
+        data.extend(std::iter::repeat_n(1, bools));
+        if data.len() % 2 == 1 {
+            data.push(0);
+        }
+        for index in 0..numbers {
+            let value = if index == COLORS_INDEX { colors } else { -1 };
+            if wide {
+                data.extend_from_slice(&value.to_le_bytes());
+            } else {
+                data.extend_from_slice(&(value as i16).to_le_bytes());
+            }
+        }
+        data
+    }
+
+    #[test]
+    fn test_parse_colors() {
+        // An odd names + bools length exercises the alignment byte
+        let legacy = entry(MAGIC_16BIT, b"xterm-256color|xterm\0", 38, 256, false);
+        assert_eq!(parse_colors(&legacy), Some(256));
+
+        let extended = entry(MAGIC_32BIT, b"xterm-direct\0", 40, 1 << 24, true);
+        assert_eq!(parse_colors(&extended), Some(1 << 24));
+
+        let cancelled = entry(MAGIC_16BIT, b"vt100\0", 10, -2, false);
+        assert_eq!(parse_colors(&cancelled), None);
+
+        assert_eq!(parse_colors(b"not terminfo"), None);
+        assert_eq!(parse_colors(&legacy[..20]), None);
+    }
+}